@@ -0,0 +1,384 @@
+//! `#[derive(Coproto)]`, which implements `coproto::types::ToValue` and
+//! `coproto::types::FromValue`. See `coproto::types::convert` for the mapping
+//! and the `#[coproto(...)]` attributes.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Fields, Generics, Ident, LitStr,
+};
+
+#[proc_macro_derive(Coproto, attributes(coproto))]
+pub fn derive_coproto(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[derive(Default)]
+struct Options {
+    rename: Option<String>,
+    skip: bool,
+}
+
+fn options(attrs: &[Attribute]) -> syn::Result<Options> {
+    let mut options = Options::default();
+
+    for attr in attrs.iter().filter(|a| a.path().is_ident("coproto")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                options.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else if meta.path.is_ident("skip") {
+                options.skip = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `rename = \"...\"` or `skip`"))
+            }
+        })?;
+    }
+
+    Ok(options)
+}
+
+// A field as it appears in the generated code.
+struct Field {
+    member: TokenStream2,
+    // Name for named fields, binding for tuple fields.
+    binding: Ident,
+    name: String,
+    named: bool,
+    skip: bool,
+}
+
+fn fields(fields: &Fields) -> syn::Result<Vec<Field>> {
+    let parsed = fields
+        .iter()
+        .enumerate()
+        .map(|(idx, field)| {
+            let options = options(&field.attrs)?;
+
+            if options.rename.is_some() && field.ident.is_none() {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "only named fields can be renamed",
+                ));
+            }
+
+            let (member, binding) = match &field.ident {
+                Some(ident) => (quote!(#ident), ident.clone()),
+                None => {
+                    let idx = syn::Index::from(idx);
+                    (quote!(#idx), format_ident!("field_{}", idx))
+                }
+            };
+
+            let name = match (options.rename, &field.ident) {
+                (Some(rename), _) => rename,
+                (None, Some(ident)) => ident.to_string().trim_start_matches("r#").to_string(),
+                (None, None) => idx.to_string(),
+            };
+
+            Ok(Field {
+                member,
+                binding,
+                name,
+                named: field.ident.is_some(),
+                skip: options.skip,
+            })
+        })
+        .collect::<syn::Result<Vec<Field>>>()?;
+
+    // Reading picks fields by name, so two with the same one would collide.
+    for (idx, (field, syn_field)) in parsed.iter().zip(fields).enumerate() {
+        let taken = parsed[..idx]
+            .iter()
+            .any(|other| !other.skip && other.name == field.name);
+
+        if !field.skip && taken {
+            return Err(syn::Error::new_spanned(
+                syn_field,
+                format!("another field is already named `{}`", field.name),
+            ));
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn add_bounds(generics: &Generics, bound: TokenStream2) -> Generics {
+    let mut generics = generics.clone();
+
+    for param in generics.type_params_mut() {
+        param.bounds.push(parse_quote!(#bound));
+    }
+
+    generics
+}
+
+// Builds the value from the bindings of `fields`.
+fn write_fields(kind: &Fields, fields: &[Field]) -> TokenStream2 {
+    let kept: Vec<&Field> = fields.iter().filter(|f| !f.skip).collect();
+    let bindings = kept.iter().map(|f| &f.binding);
+
+    match kind {
+        Fields::Named(_) => {
+            let names = kept.iter().map(|f| &f.name);
+
+            quote! {
+                ::coproto::SupportedTypes::Array(::std::vec![#(
+                    ::coproto::SupportedTypes::NamedValue(
+                        ::std::string::String::from(#names),
+                        ::std::boxed::Box::new(::coproto::types::ToValue::to_value(#bindings)),
+                    )
+                ),*])
+            }
+        }
+        Fields::Unnamed(_) if kept.len() == 1 => {
+            quote!(#(::coproto::types::ToValue::to_value(#bindings))*)
+        }
+        Fields::Unnamed(_) => quote! {
+            ::coproto::SupportedTypes::Array(::std::vec![
+                #(::coproto::types::ToValue::to_value(#bindings)),*
+            ])
+        },
+        Fields::Unit => quote!(::coproto::SupportedTypes::Null(::std::option::Option::None)),
+    }
+}
+
+// Reads `value` into `path { ... }` for a struct or a variant.
+fn read_fields(kind: &Fields, fields: &[Field], path: TokenStream2) -> TokenStream2 {
+    let kept = fields.iter().filter(|f| !f.skip).count();
+
+    let inits = fields.iter().map(|f| {
+        let member = &f.member;
+        let name = &f.name;
+
+        let init = match (f.skip, kind) {
+            (true, _) => quote!(::std::default::Default::default()),
+            (false, Fields::Named(_)) => quote!(fields.take(#name)?),
+            (false, _) if kept == 1 => quote!(::coproto::types::FromValue::from_value(value)?),
+            (false, _) => quote!(items.take()?),
+        };
+
+        quote!(#member: #init)
+    });
+
+    let setup = match kind {
+        Fields::Named(_) => quote! {
+            let mut fields = ::coproto::types::convert::Fields::new::<Self>(value)?;
+        },
+        Fields::Unnamed(_) if kept != 1 => quote! {
+            let mut items = ::coproto::types::convert::Items::new::<Self>(value, #kept)?;
+        },
+        Fields::Unnamed(_) => quote!(),
+        Fields::Unit => quote!(let _ = value;),
+    };
+
+    quote! {{
+        #setup
+        ::std::result::Result::Ok(#path { #(#inits),* })
+    }}
+}
+
+fn destructure(fields: &[Field]) -> TokenStream2 {
+    let patterns = fields.iter().map(|f| {
+        let member = &f.member;
+        let binding = &f.binding;
+
+        // Named fields bind to their own name, which needs the shorthand.
+        match (f.skip, f.named) {
+            (true, _) => quote!(#member: _),
+            (false, true) => quote!(#binding),
+            (false, false) => quote!(#member: #binding),
+        }
+    });
+
+    quote!({ #(#patterns),* })
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+
+    let (to_value, slice_to_value, from_value, vec_from_value) = match &input.data {
+        Data::Struct(data) => {
+            let fields = fields(&data.fields)?;
+            let pattern = destructure(&fields);
+            let write = write_fields(&data.fields, &fields);
+            let read = read_fields(&data.fields, &fields, quote!(Self));
+
+            let kept: Vec<&Field> = fields.iter().filter(|f| !f.skip).collect();
+
+            // Vecs of structs with named fields become Tables.
+            let tables = matches!(data.fields, Fields::Named(_)) && !kept.is_empty();
+
+            let (slice_to_value, vec_from_value) = match tables {
+                true => {
+                    let names = kept.iter().map(|f| &f.name);
+                    let members = kept.iter().map(|f| &f.member);
+
+                    (
+                        quote! {
+                            fn slice_to_value(items: &[Self]) -> ::coproto::SupportedTypes {
+                                ::coproto::SupportedTypes::Table(
+                                    ::std::vec![#(::std::string::String::from(#names)),*],
+                                    items
+                                        .iter()
+                                        .map(|item| ::std::vec![#(
+                                            ::coproto::types::ToValue::to_value(&item.#members)
+                                        ),*])
+                                        .collect(),
+                                )
+                            }
+                        },
+                        quote! {
+                            fn vec_from_value(
+                                value: ::coproto::SupportedTypes,
+                            ) -> ::coproto::TypeResult<::std::vec::Vec<Self>> {
+                                ::coproto::types::convert::rows_from_value(value)
+                            }
+                        },
+                    )
+                }
+                false => (quote!(), quote!()),
+            };
+
+            (
+                quote! {
+                    let Self #pattern = self;
+                    #write
+                },
+                slice_to_value,
+                read,
+                vec_from_value,
+            )
+        }
+        Data::Enum(data) => {
+            let mut writes = vec![];
+            let mut reads = vec![];
+            // Unit variants are read from a String and the others from a
+            // NamedValue, so a name only has to be unique within each kind.
+            let mut unit_names: Vec<String> = vec![];
+            let mut data_names: Vec<String> = vec![];
+
+            for variant in &data.variants {
+                let options = options(&variant.attrs)?;
+
+                if options.skip {
+                    return Err(syn::Error::new_spanned(
+                        variant,
+                        "variants cannot be skipped",
+                    ));
+                }
+
+                let variant_ident = &variant.ident;
+                let name = options.rename.unwrap_or_else(|| {
+                    variant_ident
+                        .to_string()
+                        .trim_start_matches("r#")
+                        .to_string()
+                });
+                let taken = match &variant.fields {
+                    Fields::Unit => &mut unit_names,
+                    _ => &mut data_names,
+                };
+
+                if taken.contains(&name) {
+                    return Err(syn::Error::new_spanned(
+                        variant,
+                        format!("another variant is already named `{}`", name),
+                    ));
+                }
+
+                taken.push(name.clone());
+
+                let fields = fields(&variant.fields)?;
+                let pattern = destructure(&fields);
+
+                match &variant.fields {
+                    Fields::Unit => {
+                        writes.push(quote! {
+                            Self::#variant_ident => ::coproto::SupportedTypes::String(
+                                ::std::string::String::from(#name),
+                            )
+                        });
+                        reads.push(quote! {
+                            (#name, ::std::option::Option::None) => {
+                                ::std::result::Result::Ok(Self::#variant_ident)
+                            }
+                        });
+                    }
+                    kind => {
+                        let write = write_fields(kind, &fields);
+                        let read = read_fields(kind, &fields, quote!(Self::#variant_ident));
+
+                        writes.push(quote! {
+                            Self::#variant_ident #pattern => ::coproto::SupportedTypes::NamedValue(
+                                ::std::string::String::from(#name),
+                                ::std::boxed::Box::new(#write),
+                            )
+                        });
+                        reads.push(quote! {
+                            (#name, ::std::option::Option::Some(value)) => #read
+                        });
+                    }
+                }
+            }
+
+            (
+                quote! {
+                    match self {
+                        #(#writes,)*
+                    }
+                },
+                quote!(),
+                quote! {
+                    let (name, value) = ::coproto::types::convert::variant_of::<Self>(value)?;
+
+                    match (name.as_str(), value) {
+                        #(#reads,)*
+                        _ => ::std::result::Result::Err(
+                            ::coproto::types::convert::unknown_variant::<Self>(&name),
+                        ),
+                    }
+                },
+                quote!(),
+            )
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "Coproto cannot be derived for unions",
+            ))
+        }
+    };
+
+    let to_generics = add_bounds(&input.generics, quote!(::coproto::types::ToValue));
+    let (to_impl, to_type, to_where) = to_generics.split_for_impl();
+
+    let from_generics = add_bounds(&input.generics, quote!(::coproto::types::FromValue));
+    let (from_impl, from_type, from_where) = from_generics.split_for_impl();
+
+    Ok(quote! {
+        impl #to_impl ::coproto::types::ToValue for #ident #to_type #to_where {
+            fn to_value(&self) -> ::coproto::SupportedTypes {
+                #to_value
+            }
+
+            #slice_to_value
+        }
+
+        impl #from_impl ::coproto::types::FromValue for #ident #from_type #from_where {
+            fn from_value(
+                value: ::coproto::SupportedTypes,
+            ) -> ::coproto::TypeResult<Self> {
+                #from_value
+            }
+
+            #vec_from_value
+        }
+    })
+}
@@ -0,0 +1,4 @@
+#[test]
+fn invalid_derives() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,148 @@
+use coproto::{
+    decode, encode,
+    types::{FromValue, ToValue},
+    Coproto, PathSegment, SupportedTypes, TypeError,
+};
+
+#[derive(Debug, PartialEq, Coproto)]
+enum Role {
+    Admin,
+    #[coproto(rename = "guest")]
+    Guest {
+        expires: i64,
+    },
+    Custom(String),
+    Pair(u8, u8),
+}
+
+#[derive(Debug, PartialEq, Coproto)]
+struct User {
+    id: u32,
+    #[coproto(rename = "full_name")]
+    name: String,
+    email: Option<String>,
+    #[coproto(skip)]
+    cached: Vec<u8>,
+    role: Role,
+}
+
+#[derive(Debug, PartialEq, Coproto)]
+struct Page<T> {
+    items: Vec<T>,
+    next: Option<u64>,
+}
+
+#[derive(Debug, PartialEq, Coproto)]
+struct Meters(f64);
+
+#[derive(Debug, PartialEq, Coproto)]
+struct Marker;
+
+fn user(id: u32, role: Role) -> User {
+    User {
+        id,
+        name: format!("user {}", id),
+        email: None,
+        cached: vec![],
+        role,
+    }
+}
+
+#[test]
+fn structs_become_named_values() {
+    let mut value = user(7, Role::Admin);
+    value.cached = vec![1, 2, 3];
+
+    assert_eq!(
+        value.to_value(),
+        SupportedTypes::Array(vec![
+            SupportedTypes::NamedValue("id".to_string(), Box::new(SupportedTypes::BigInt(7))),
+            SupportedTypes::NamedValue(
+                "full_name".to_string(),
+                Box::new(SupportedTypes::String("user 7".to_string()))
+            ),
+            SupportedTypes::NamedValue("email".to_string(), Box::new(SupportedTypes::Null(None))),
+            SupportedTypes::NamedValue(
+                "role".to_string(),
+                Box::new(SupportedTypes::String("Admin".to_string()))
+            ),
+        ])
+    );
+
+    // Skipped fields come back as their default.
+    value.cached = vec![];
+    assert_eq!(User::from_value(value.to_value()).unwrap(), value);
+}
+
+#[test]
+fn vecs_of_structs_become_tables() {
+    let page = Page {
+        items: vec![
+            user(1, Role::Guest { expires: 99 }),
+            user(2, Role::Custom("ops".to_string())),
+            user(3, Role::Pair(4, 5)),
+        ],
+        next: Some(4),
+    };
+
+    let value = page.to_value();
+
+    let SupportedTypes::Array(fields) = &value else {
+        panic!("Not an Array: {:?}", value);
+    };
+
+    match &fields[0] {
+        SupportedTypes::NamedValue(name, items) => {
+            assert_eq!(name, "items");
+            assert!(matches!(
+                &**items,
+                SupportedTypes::Table(headers, rows)
+                    if headers == &["id", "full_name", "email", "role"] && rows.len() == 3
+            ));
+        }
+        other => panic!("Unexpected {:?}", other),
+    }
+
+    let decoded = decode(&encode(&value).unwrap()).unwrap();
+    assert_eq!(Page::<User>::from_value(decoded).unwrap(), page);
+}
+
+#[test]
+fn tuple_and_unit_structs() {
+    assert_eq!(Meters(1.5).to_value(), SupportedTypes::Double(1.5));
+    assert_eq!(
+        Meters::from_value(SupportedTypes::Integer(2)).unwrap(),
+        Meters(2.0)
+    );
+    assert_eq!(Marker.to_value(), SupportedTypes::Null(None));
+    assert_eq!(
+        Role::Pair(1, 2).to_value(),
+        SupportedTypes::NamedValue(
+            "Pair".to_string(),
+            Box::new(SupportedTypes::Array(vec![
+                SupportedTypes::Integer(1),
+                SupportedTypes::Integer(2)
+            ]))
+        )
+    );
+}
+
+#[test]
+fn errors_name_the_field() {
+    let value = SupportedTypes::Array(vec![
+        SupportedTypes::NamedValue("id".to_string(), Box::new(SupportedTypes::Integer(1))),
+        SupportedTypes::NamedValue(
+            "full_name".to_string(),
+            Box::new(SupportedTypes::Boolean(true)),
+        ),
+    ]);
+
+    match User::from_value(value) {
+        Err(TypeError::Decoding(e)) => {
+            assert_eq!(e.path, vec![PathSegment::Value("full_name".to_string())])
+        }
+        other => panic!("Unexpected {:?}", other),
+    }
+
+    assert!(Role::from_value(SupportedTypes::String("Root".to_string())).is_err());
+}
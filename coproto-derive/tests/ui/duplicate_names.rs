@@ -0,0 +1,10 @@
+use coproto::Coproto;
+
+#[derive(Coproto)]
+struct User {
+    name: String,
+    #[coproto(rename = "name")]
+    full_name: String,
+}
+
+fn main() {}
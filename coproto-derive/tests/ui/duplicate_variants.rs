@@ -0,0 +1,15 @@
+use coproto::Coproto;
+
+// A unit variant and a data variant may share a name.
+#[derive(Coproto)]
+enum Shape {
+    Empty,
+    #[coproto(rename = "Empty")]
+    Blank(u8),
+    #[coproto(rename = "x")]
+    A(u8),
+    #[coproto(rename = "x")]
+    B(u8),
+}
+
+fn main() {}
@@ -0,0 +1,103 @@
+use coproto::types::{
+    Array, BigInt, Boolean, Command, Double, Integer, NamedValue, Null, String, SupportedTypes,
+};
+use coproto::Codec;
+use std::fmt::Debug;
+
+fn show<C: Codec>(value: C::Value)
+where
+    C::Value: Debug,
+{
+    let buff = C::encode(&value).unwrap();
+    println!("  Encoding:\n  {:?}", buff);
+    println!("  Decoding:\n  {:?}", C::decode(&buff).unwrap());
+}
+
+fn main() {
+    println!("Integer: --------------------------------------------");
+    println!("Positive:");
+    show::<Integer>(1000);
+
+    println!("Negative:");
+    show::<Integer>(-1000);
+    println!("-----------------------------------------------------\n\n");
+
+    println!("Double: --------------------------------------------");
+    println!("Positive:");
+    show::<Double>(123.456);
+
+    println!("Negative:");
+    show::<Double>(-123.456);
+    println!("-----------------------------------------------------\n\n");
+
+    println!("Boolean: --------------------------------------------");
+    println!("True:");
+    show::<Boolean>(true);
+
+    println!("False:");
+    show::<Boolean>(false);
+    println!("-----------------------------------------------------\n\n");
+
+    println!("Null: --------------------------------------------");
+    show::<Null>(None);
+    println!("-----------------------------------------------------\n\n");
+
+    println!("String: --------------------------------------------");
+    show::<String>("Hello from the main".to_string());
+    println!("-----------------------------------------------------\n\n");
+
+    println!("BigInt: --------------------------------------------");
+    println!("Positive:");
+    show::<BigInt>(1000);
+
+    println!("Negative:");
+    show::<BigInt>(-1000);
+    println!("-----------------------------------------------------\n\n");
+
+    println!("Array: --------------------------------------------");
+    println!("Filled:");
+    let vec_to_test = vec![
+        SupportedTypes::BigInt(123456789),
+        SupportedTypes::Boolean(false),
+        SupportedTypes::Double(1.2345),
+        SupportedTypes::Integer(123),
+        SupportedTypes::Null(None),
+        SupportedTypes::String("Hello, fellow rustacean!".to_string()),
+    ];
+
+    show::<Array>(vec_to_test.clone());
+
+    println!("Empty:");
+    show::<Array>(vec![]);
+    println!("-----------------------------------------------------\n\n");
+
+    println!("NamedValue: --------------------------------------------");
+    let types_to_test = [
+        SupportedTypes::BigInt(123456789),
+        SupportedTypes::Boolean(false),
+        SupportedTypes::Double(1.2345),
+        SupportedTypes::Integer(123),
+        SupportedTypes::Null(None),
+        SupportedTypes::String("Hello, fellow rustacean!".to_string()),
+    ];
+
+    for tp in types_to_test.iter() {
+        println!("  {}:", tp.get_name());
+        show::<NamedValue>((tp.get_name().to_string(), tp.clone()));
+    }
+    println!("-----------------------------------------------------\n\n");
+
+    println!("Command: --------------------------------------------");
+    show::<Command>((
+        "OK".to_string(),
+        vec![
+            "Be".to_string(),
+            "happy".to_string(),
+            ".".to_string(),
+            "Its".to_string(),
+            "all".to_string(),
+            "ours".to_string(),
+        ],
+    ));
+    println!("-----------------------------------------------------\n\n");
+}
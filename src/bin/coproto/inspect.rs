@@ -0,0 +1,209 @@
+use std::fmt::Write;
+
+use coproto::{
+    commom::{
+        delimiters::{BUFFER_END, END_RECORD, START_RECORD, VALUE_DELIMITER},
+        modifiers::{MINUS, PLUS},
+    },
+    types::{
+        registry, Array, BigInt, Command, Double, HugeInt, Integer, NamedValue, Table, TypeRegistry,
+    },
+    Codec,
+};
+
+// Payload bytes shown on a single line.
+const PAYLOAD_WIDTH: usize = 8;
+
+fn is_composite(first_byte: u8) -> bool {
+    matches!(
+        first_byte,
+        Array::FIRST_BYTE | NamedValue::FIRST_BYTE | Table::FIRST_BYTE | Command::FIRST_BYTE
+    )
+}
+
+fn has_modifier(first_byte: u8) -> bool {
+    matches!(
+        first_byte,
+        BigInt::FIRST_BYTE | Double::FIRST_BYTE | Integer::FIRST_BYTE | HugeInt::FIRST_BYTE
+    )
+}
+
+// A value being walked through, and the record depth its START_RECORD opens at.
+struct Open {
+    composite: bool,
+    depth: usize,
+}
+
+/// Renders `buff` as one line per structural byte, with payload bytes grouped
+/// in runs of up to eight. Each line shows the offset, the bytes and a label.
+/// First bytes are looked up in the registry in use on this thread.
+pub fn annotate(buff: &[u8]) -> String {
+    registry::current(|registry| annotate_with(registry, buff))
+}
+
+fn annotate_with(registry: &TypeRegistry, buff: &[u8]) -> String {
+    let mut out = String::new();
+    let mut payload: Vec<(usize, u8)> = vec![];
+
+    let mut open: Vec<Open> = vec![];
+    let mut depth: usize = 0;
+    let mut expect_value = true;
+    let mut modifier_allowed = false;
+
+    for (offset, byte) in buff.iter().copied().enumerate() {
+        let in_composite = open.last().is_none_or(|value| value.composite);
+        let mut is_first_byte = false;
+
+        let label = match byte {
+            START_RECORD => {
+                depth += 1;
+                expect_value = in_composite;
+                Some("START_RECORD".to_string())
+            }
+            END_RECORD => {
+                depth = depth.saturating_sub(1);
+
+                if open.last().is_some_and(|value| value.depth == depth) {
+                    open.pop();
+                }
+
+                expect_value = false;
+                Some("END_RECORD".to_string())
+            }
+            VALUE_DELIMITER => {
+                expect_value = in_composite;
+                Some("VALUE_DELIMITER".to_string())
+            }
+            BUFFER_END => {
+                open.clear();
+                depth = 0;
+                expect_value = true;
+                Some("BUFFER_END".to_string())
+            }
+            PLUS | MINUS if modifier_allowed => Some(format!("modifier '{}'", byte as char)),
+            fb if expect_value && registry.contains(fb) => {
+                open.push(Open {
+                    composite: is_composite(fb),
+                    depth,
+                });
+
+                expect_value = false;
+                is_first_byte = true;
+                Some(format!(
+                    "first byte '{}' {}",
+                    fb as char,
+                    registry.name(fb).unwrap_or_default()
+                ))
+            }
+            _ => None,
+        };
+
+        modifier_allowed = is_first_byte && has_modifier(byte);
+
+        match label {
+            Some(label) => {
+                flush_payload(&mut out, &mut payload);
+                // Writing into a String cannot fail.
+                let _ = writeln!(out, "{:08x}  {:02x}  {}", offset, byte, label);
+            }
+            None => {
+                payload.push((offset, byte));
+
+                if payload.len() == PAYLOAD_WIDTH {
+                    flush_payload(&mut out, &mut payload);
+                }
+            }
+        }
+    }
+
+    flush_payload(&mut out, &mut payload);
+
+    out
+}
+
+fn flush_payload(out: &mut String, payload: &mut Vec<(usize, u8)>) {
+    let Some((offset, _)) = payload.first() else {
+        return;
+    };
+
+    let hex: Vec<String> = payload.iter().map(|(_, b)| format!("{:02x}", b)).collect();
+    let text: String = payload
+        .iter()
+        .map(|(_, b)| match b {
+            0x20..=0x7e => *b as char,
+            _ => '.',
+        })
+        .collect();
+
+    let _ = writeln!(out, "{:08x}  {}  payload |{}|", offset, hex.join(" "), text);
+
+    payload.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use coproto::{
+        commom::delimiters::{BUFFER_END, END_RECORD, START_RECORD},
+        encode,
+        types::{with_registry, TypeRegistry},
+        SupportedTypes,
+    };
+
+    use super::annotate;
+
+    #[test]
+    fn labels_structure() {
+        let buff = encode(&SupportedTypes::Integer(-12)).unwrap();
+
+        assert_eq!(
+            annotate(&buff),
+            "00000000  3a  first byte ':' Integer\n\
+             00000001  2d  modifier '-'\n\
+             00000002  1c  START_RECORD\n\
+             00000003  01 02  payload |..|\n\
+             00000005  1d  END_RECORD\n\
+             00000006  1e  BUFFER_END\n"
+        );
+    }
+
+    #[test]
+    fn nested_first_bytes_are_found() {
+        let buff = encode(&SupportedTypes::Array(vec![
+            SupportedTypes::Null(None),
+            SupportedTypes::String("-+".to_string()),
+        ]))
+        .unwrap();
+
+        let annotated = annotate(&buff);
+
+        assert!(annotated.contains("first byte '[' Array"));
+        assert!(annotated.contains("first byte '-' Null"));
+        assert!(annotated.contains("first byte '+' String"));
+        // The string's own bytes are payload, not first bytes or modifiers.
+        assert!(annotated.contains("2d 2b  payload |-+|"));
+        assert!(!annotated.contains("modifier"));
+    }
+
+    #[test]
+    fn installed_extensions_are_named() {
+        let buff = [0x80, START_RECORD, 7, END_RECORD, BUFFER_END];
+
+        let mut registry = TypeRegistry::new();
+        registry
+            .register(
+                0x80,
+                "GeoPoint",
+                |_, _| Ok(SupportedTypes::Null(None)),
+                |_, _| Ok(()),
+            )
+            .unwrap();
+
+        assert!(!annotate(&buff).contains("GeoPoint"));
+
+        let annotated = with_registry(Rc::new(registry), || annotate(&buff));
+        assert!(annotated.contains("00000000  80  first byte"));
+        assert!(annotated.contains("GeoPoint"));
+    }
+}
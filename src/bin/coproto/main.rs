@@ -0,0 +1,173 @@
+use std::{
+    fs,
+    io::{self, Read, Write},
+    process::ExitCode,
+};
+
+use coproto::{
+    encode,
+    types::{frames, from_csv, from_json, to_json, write_csv},
+    SupportedTypes,
+};
+
+mod inspect;
+mod tree;
+
+const USAGE: &str = "\
+Usage: coproto <COMMAND> [FILE] [-o OUTPUT] [--text | --json | --csv]
+
+Commands:
+  encode   Read a JSON description and write its binary encoding
+  decode   Read binary frames and print their value trees
+  inspect  Print an annotated hexdump of binary frames
+
+Input is read from FILE, or from stdin when FILE is missing or '-'.
+Output goes to stdout unless -o OUTPUT is given.
+With --text, encode reads the text notation instead of JSON, and decode
+prints one line of text notation per frame instead of a value tree.
+With --json, decode prints one line of JSON per frame.
+With --csv, encode reads a CSV file as a Table, and decode prints every frame,
+which must be a Table, as CSV.";
+
+#[derive(PartialEq)]
+enum Format {
+    Default,
+    Text,
+    Json,
+    Csv,
+}
+
+struct Args {
+    command: String,
+    input: Option<String>,
+    output: Option<String>,
+    format: Format,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let command = args.next().ok_or("Missing command")?;
+
+    if !matches!(command.as_str(), "encode" | "decode" | "inspect") {
+        return Err(format!("Unknown command '{}'", command));
+    }
+
+    let mut input = None;
+    let mut output = None;
+    let mut format = Format::Default;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-o" | "--output" => output = Some(args.next().ok_or("Missing path after -o")?),
+            "--text" | "--json" | "--csv" if format != Format::Default => {
+                return Err("Only one of --text, --json and --csv can be given".to_string())
+            }
+            "--text" => format = Format::Text,
+            "--json" => format = Format::Json,
+            "--csv" => format = Format::Csv,
+            "-" => input = None,
+            _ if input.is_none() => input = Some(arg),
+            _ => return Err(format!("Unexpected argument '{}'", arg)),
+        }
+    }
+
+    Ok(Args {
+        command,
+        input,
+        output,
+        format,
+    })
+}
+
+fn read_input(input: &Option<String>) -> Result<Vec<u8>, String> {
+    match input {
+        Some(path) => fs::read(path).map_err(|e| format!("Could not read {}: {}", path, e)),
+        None => {
+            let mut buff = vec![];
+            io::stdin()
+                .read_to_end(&mut buff)
+                .map_err(|e| format!("Could not read stdin: {}", e))?;
+            Ok(buff)
+        }
+    }
+}
+
+fn write_output(output: &Option<String>, bytes: &[u8]) -> Result<(), String> {
+    match output {
+        Some(path) => {
+            fs::write(path, bytes).map_err(|e| format!("Could not write {}: {}", path, e))
+        }
+        None => io::stdout()
+            .write_all(bytes)
+            .map_err(|e| format!("Could not write stdout: {}", e)),
+    }
+}
+
+fn run(args: Args) -> Result<(), String> {
+    let input = read_input(&args.input)?;
+
+    let output = match args.command.as_str() {
+        "encode" => {
+            let text = String::from_utf8(input).map_err(|e| e.to_string())?;
+
+            let value = match args.format {
+                Format::Text => text.parse::<SupportedTypes>(),
+                Format::Csv => from_csv(&text),
+                Format::Default | Format::Json => from_json(&text),
+            }
+            .map_err(|e| e.to_string())?;
+
+            encode(&value).map_err(|e| e.to_string())?
+        }
+        "decode" => {
+            let mut rendered = vec![];
+
+            for value in frames(&input) {
+                let value = value.map_err(|e| e.to_string())?;
+
+                match (&args.format, value) {
+                    (Format::Text, value) => rendered.extend(format!("{}\n", value).bytes()),
+                    (Format::Json, value) => {
+                        let json = to_json(&value).map_err(|e| e.to_string())?;
+                        rendered.extend(format!("{}\n", json).bytes())
+                    }
+                    (Format::Csv, SupportedTypes::Table(headers, rows)) => {
+                        write_csv(&headers, &rows, &mut rendered).map_err(|e| e.to_string())?
+                    }
+                    (Format::Csv, _) => return Err("Only Tables can be written as CSV".to_string()),
+                    (Format::Default, value) => rendered.extend(tree::render(&value).bytes()),
+                }
+            }
+
+            rendered
+        }
+        "inspect" => inspect::annotate(&input).into_bytes(),
+        other => unreachable!("parse_args accepted '{}'", other),
+    };
+
+    write_output(&args.output, &output)
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    if args.is_empty() || matches!(args[0].as_str(), "-h" | "--help" | "help") {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    let args = match parse_args(args.into_iter()) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match run(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
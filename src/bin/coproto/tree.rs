@@ -0,0 +1,113 @@
+use std::fmt::Write;
+
+use coproto::SupportedTypes;
+
+const INDENT: &str = "  ";
+
+/// Renders `value` one node per line, children indented under their parent.
+pub fn render(value: &SupportedTypes) -> String {
+    let mut out = String::new();
+    write_node(&mut out, value, 0, None);
+    out
+}
+
+fn write_node(out: &mut String, value: &SupportedTypes, level: usize, label: Option<&str>) {
+    let indent = INDENT.repeat(level);
+    let label = label.map(|l| format!("{}: ", l)).unwrap_or_default();
+
+    // Writing into a String cannot fail.
+    let _ = match value {
+        SupportedTypes::BigInt(v) => writeln!(out, "{}{}BigInt {}", indent, label, v),
+        SupportedTypes::Boolean(v) => writeln!(out, "{}{}Boolean {}", indent, label, v),
+        SupportedTypes::Double(v) => writeln!(out, "{}{}Double {:?}", indent, label, v),
+        SupportedTypes::Integer(v) => writeln!(out, "{}{}Integer {}", indent, label, v),
+        SupportedTypes::Null(_) => writeln!(out, "{}{}Null", indent, label),
+        SupportedTypes::String(v) => writeln!(out, "{}{}String {:?}", indent, label, v),
+        SupportedTypes::HugeInt(v) => writeln!(out, "{}{}HugeInt {}", indent, label, v),
+        SupportedTypes::Bytes(v) => {
+            let hex: Vec<String> = v.iter().map(|b| format!("{:02x}", b)).collect();
+            writeln!(out, "{}{}Bytes [{}]", indent, label, hex.join(" "))
+        }
+        SupportedTypes::Array(values) => {
+            let _ = writeln!(out, "{}{}Array ({} items)", indent, label, values.len());
+
+            for value in values {
+                write_node(out, value, level + 1, None);
+            }
+
+            Ok(())
+        }
+        SupportedTypes::NamedValue(name, value) => {
+            let _ = writeln!(out, "{}{}NamedValue {:?}", indent, label, name);
+            write_node(out, value, level + 1, None);
+
+            Ok(())
+        }
+        SupportedTypes::Table(headers, rows) => {
+            let _ = writeln!(
+                out,
+                "{}{}Table ({} columns, {} rows)",
+                indent,
+                label,
+                headers.len(),
+                rows.len()
+            );
+
+            for (idx, row) in rows.iter().enumerate() {
+                let _ = writeln!(out, "{}{}row {}", indent, INDENT, idx);
+
+                for (header, cell) in headers.iter().zip(row) {
+                    write_node(out, cell, level + 2, Some(header));
+                }
+            }
+
+            Ok(())
+        }
+        SupportedTypes::Extension(first_byte, value) => {
+            let _ = writeln!(out, "{}{}Extension {:#04x}", indent, label, first_byte);
+            write_node(out, value, level + 1, None);
+
+            Ok(())
+        }
+        SupportedTypes::Command(name, args) => {
+            let _ = writeln!(out, "{}{}Command {:?}", indent, label, name);
+
+            for arg in args {
+                let _ = writeln!(out, "{}{}{:?}", indent, INDENT, arg);
+            }
+
+            Ok(())
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use coproto::SupportedTypes;
+
+    use super::render;
+
+    #[test]
+    fn renders_nested_values() {
+        let value = SupportedTypes::Array(vec![
+            SupportedTypes::NamedValue("flag".to_string(), Box::new(SupportedTypes::Boolean(true))),
+            SupportedTypes::Table(
+                vec!["id".to_string()],
+                vec![vec![SupportedTypes::Integer(1)]],
+            ),
+            SupportedTypes::Command("GET".to_string(), vec!["k".to_string()]),
+        ]);
+
+        assert_eq!(
+            render(&value),
+            "Array (3 items)\n\
+            \x20 NamedValue \"flag\"\n\
+            \x20   Boolean true\n\
+            \x20 Table (1 columns, 1 rows)\n\
+            \x20   row 0\n\
+            \x20     id: Integer 1\n\
+            \x20 Command \"GET\"\n\
+            \x20   \"k\"\n"
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use std::cell::OnceCell;
+use std::fmt;
+use std::io::Write;
+
+use super::{
+    delimiters::BUFFER_END, errors::TypeResult, write_bytes, ByteCounter, DecodeLimits, Uint8Buff,
+};
+
+pub enum ValueOrBuffer<T> {
+    Value(T),
+    Buffer(Uint8Buff),
+}
+
+/// Encoding and decoding of one wire type. Codecs hold no state: each type
+/// in [`crate::types`] is a unit struct and every method is an associated
+/// function, so nothing is computed until it is asked for.
+pub trait Codec {
+    type Value;
+    const FIRST_BYTE: u8;
+
+    /// Writes `value` as it appears nested in a composite, without the
+    /// trailing `BUFFER_END`.
+    fn write_value<W: Write>(value: &Self::Value, writer: &mut W) -> TypeResult<()>;
+    fn decode_with(buff: &[u8], limits: &DecodeLimits) -> TypeResult<Self::Value>;
+
+    fn decode(buff: &[u8]) -> TypeResult<Self::Value> {
+        Self::decode_with(buff, &DecodeLimits::default())
+    }
+
+    fn encode_to<W: Write>(value: &Self::Value, writer: &mut W) -> TypeResult<()> {
+        Self::write_value(value, writer)?;
+        write_bytes(writer, &[BUFFER_END])
+    }
+
+    fn encode(value: &Self::Value) -> TypeResult<Uint8Buff> {
+        let mut buff: Uint8Buff = vec![];
+        Self::encode_to(value, &mut buff)?;
+        Ok(buff)
+    }
+
+    fn encoded_len(value: &Self::Value) -> TypeResult<usize> {
+        let mut counter = ByteCounter::default();
+        Self::encode_to(value, &mut counter)?;
+        Ok(counter.count())
+    }
+}
+
+/// The object-safe part of [`Codec`], for choosing a codec at runtime:
+/// `&Integer as &dyn DynCodec<i32>`. Implemented for every `Codec`.
+pub trait DynCodec<T> {
+    fn first_byte(&self) -> u8;
+    fn write_nested(&self, value: &T, writer: &mut dyn Write) -> TypeResult<()>;
+    fn encode_value(&self, value: &T) -> TypeResult<Uint8Buff>;
+    fn decode_value(&self, buff: &[u8], limits: &DecodeLimits) -> TypeResult<T>;
+}
+
+impl<C: Codec> DynCodec<C::Value> for C {
+    fn first_byte(&self) -> u8 {
+        C::FIRST_BYTE
+    }
+
+    fn write_nested(&self, value: &C::Value, mut writer: &mut dyn Write) -> TypeResult<()> {
+        C::write_value(value, &mut writer)
+    }
+
+    fn encode_value(&self, value: &C::Value) -> TypeResult<Uint8Buff> {
+        C::encode(value)
+    }
+
+    fn decode_value(&self, buff: &[u8], limits: &DecodeLimits) -> TypeResult<C::Value> {
+        C::decode_with(buff, limits)
+    }
+}
+
+/// A value and its encoding, where whichever one was not given is computed
+/// on first use and then kept. Failures are not cached.
+pub struct Lazy<C: Codec> {
+    value: OnceCell<C::Value>,
+    buff: OnceCell<Uint8Buff>,
+}
+
+impl<C: Codec> Lazy<C> {
+    pub fn new(value: ValueOrBuffer<C::Value>) -> Self {
+        match value {
+            ValueOrBuffer::Value(value) => Self {
+                value: OnceCell::from(value),
+                buff: OnceCell::new(),
+            },
+            ValueOrBuffer::Buffer(buff) => Self {
+                value: OnceCell::new(),
+                buff: OnceCell::from(buff),
+            },
+        }
+    }
+
+    pub fn from_value(value: C::Value) -> Self {
+        Self::new(ValueOrBuffer::Value(value))
+    }
+
+    pub fn from_buff(buff: Uint8Buff) -> Self {
+        Self::new(ValueOrBuffer::Buffer(buff))
+    }
+
+    pub fn value(&self) -> TypeResult<&C::Value> {
+        match self.value.get() {
+            Some(value) => Ok(value),
+            None => {
+                let value = decode_cell::<C>(&self.buff)?;
+                Ok(self.value.get_or_init(|| value))
+            }
+        }
+    }
+
+    pub fn buff(&self) -> TypeResult<&[u8]> {
+        match self.buff.get() {
+            Some(buff) => Ok(buff),
+            None => {
+                let buff = encode_cell::<C>(&self.value)?;
+                Ok(self.buff.get_or_init(|| buff))
+            }
+        }
+    }
+
+    pub fn into_value(self) -> TypeResult<C::Value> {
+        match self.value.into_inner() {
+            Some(value) => Ok(value),
+            None => decode_cell::<C>(&self.buff),
+        }
+    }
+
+    pub fn into_buff(self) -> TypeResult<Uint8Buff> {
+        match self.buff.into_inner() {
+            Some(buff) => Ok(buff),
+            None => encode_cell::<C>(&self.value),
+        }
+    }
+}
+
+// `Lazy::new` sets exactly one cell, so these only ever run on the other one.
+fn decode_cell<C: Codec>(buff: &OnceCell<Uint8Buff>) -> TypeResult<C::Value> {
+    C::decode(buff.get().map_or(&[], Vec::as_slice))
+}
+
+fn encode_cell<C: Codec>(value: &OnceCell<C::Value>) -> TypeResult<Uint8Buff> {
+    value.get().map_or(Ok(vec![]), C::encode)
+}
+
+impl<C: Codec> fmt::Debug for Lazy<C>
+where
+    C::Value: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Lazy")
+            .field("value", &self.value.get())
+            .field("buff", &self.buff.get())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        commom::{Codec, DynCodec, Lazy, Uint8Buff},
+        types::{Boolean, Integer},
+    };
+
+    fn send<C: Codec>(value: &C::Value, wire: &mut Uint8Buff) {
+        C::encode_to(value, wire).unwrap();
+    }
+
+    #[test]
+    fn codecs_are_usable_generically() {
+        let mut wire: Uint8Buff = vec![];
+        send::<Integer>(&-42, &mut wire);
+
+        assert_eq!(wire, Integer::encode(&-42).unwrap());
+        assert_eq!(Integer::decode(&wire).unwrap(), -42);
+        assert_eq!(Integer::encoded_len(&-42).unwrap(), wire.len());
+    }
+
+    #[test]
+    fn codecs_are_object_safe() {
+        let codecs: [&dyn DynCodec<bool>; 1] = [&Boolean];
+
+        let buff = codecs[0].encode_value(&true).unwrap();
+        assert_eq!(codecs[0].first_byte(), b'#');
+        assert!(codecs[0].decode_value(&buff, &Default::default()).unwrap());
+    }
+
+    #[test]
+    fn lazy_computes_on_first_use() {
+        let from_value = Lazy::<Integer>::from_value(7);
+        let buff = from_value.buff().unwrap().to_vec();
+        assert_eq!(buff, Integer::encode(&7).unwrap());
+        assert_eq!(from_value.into_value().unwrap(), 7);
+
+        let from_buff = Lazy::<Integer>::from_buff(buff.clone());
+        assert_eq!(*from_buff.value().unwrap(), 7);
+        assert_eq!(from_buff.into_buff().unwrap(), buff);
+
+        let broken = Lazy::<Integer>::from_buff(vec![b'#']);
+        assert!(broken.value().is_err());
+        assert!(broken.into_value().is_err());
+    }
+}
@@ -0,0 +1,106 @@
+use super::errors::{DecodingError, DecodingErrors, DecodingResult};
+
+/// Upper bounds enforced while decoding, so a hostile peer cannot make the
+/// decoder allocate or recurse without limit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodeLimits {
+    /// Size in bytes of a whole message, `BUFFER_END` included.
+    pub max_message_size: usize,
+    /// Decoded length in bytes of a String or Bytes payload.
+    pub max_string_length: usize,
+    /// Digits of a HugeInt. Parsing and formatting are linear, but no real
+    /// number needs thousands of digits, so this bounds what one can allocate.
+    pub max_digits: usize,
+    /// Elements of an Array or arguments of a Command.
+    pub max_elements: usize,
+    pub max_table_rows: usize,
+    /// How many composites may be nested inside each other.
+    pub max_depth: usize,
+    depth: usize,
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        Self {
+            max_message_size: 16 * 1024 * 1024,
+            max_string_length: 4 * 1024 * 1024,
+            max_digits: 4096,
+            max_elements: 100_000,
+            max_table_rows: 100_000,
+            max_depth: 64,
+            depth: 0,
+        }
+    }
+}
+
+impl DecodeLimits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether these are the limits of a whole message rather than of a value
+    /// nested in it.
+    pub(crate) fn is_outermost(&self) -> bool {
+        self.depth == 0
+    }
+
+    /// Limits for the children of a composite found in `buff`.
+    pub(crate) fn nested(&self, buff: &[u8], to: &str) -> DecodingResult<Self> {
+        check_limit(buff, to, "Nesting depth", self.max_depth, self.depth + 1)?;
+
+        Ok(Self {
+            depth: self.depth + 1,
+            ..self.clone()
+        })
+    }
+}
+
+pub(crate) fn check_limit(
+    buff: &[u8],
+    to: &str,
+    what: &str,
+    limit: usize,
+    found: usize,
+) -> DecodingResult<()> {
+    if found <= limit {
+        return Ok(());
+    }
+
+    Err(DecodingError::new(
+        buff.to_vec(),
+        to,
+        DecodingErrors::LimitExceeded {
+            what: what.to_string(),
+            limit,
+            found,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commom::{decode_limits::check_limit, errors::DecodingErrors, DecodeLimits};
+
+    #[test]
+    fn limits_are_inclusive() {
+        assert!(check_limit(&[], "String", "String length", 3, 3).is_ok());
+
+        match check_limit(&[], "String", "String length", 3, 4).map_err(|e| *e.cause) {
+            Err(DecodingErrors::LimitExceeded { what, limit, found }) => {
+                assert_eq!((what.as_str(), limit, found), ("String length", 3, 4))
+            }
+            other => panic!("Unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn nesting_is_counted() {
+        let mut limits = DecodeLimits::new();
+        limits.max_depth = 2;
+
+        let first = limits.nested(&[], "Array").unwrap();
+        let second = first.nested(&[], "Array").unwrap();
+
+        assert!(second.nested(&[], "Array").is_err());
+    }
+}
@@ -2,3 +2,4 @@ pub const START_RECORD: u8 = 0x1c;
 pub const END_RECORD: u8 = 0x1d;
 pub const BUFFER_END: u8 = 0x1e;
 pub const VALUE_DELIMITER: u8 = 0x1f;
+pub const ESCAPE: u8 = 0x1b;
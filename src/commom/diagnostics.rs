@@ -0,0 +1,166 @@
+use core::fmt;
+use std::{cell::RefCell, rc::Rc};
+
+use super::errors::{DecodingError, EncodingError};
+
+/// Something an encoder or decoder did that may help while debugging.
+#[derive(Debug)]
+pub enum Event<'a> {
+    /// `slice_records` found `records` top-level records in `bytes` bytes.
+    RecordsSliced {
+        records: usize,
+        bytes: usize,
+    },
+    /// A decoder picked a type from `first_byte`.
+    TypeInferred {
+        first_byte: u8,
+    },
+    /// An encoder started writing a value, named as in `SupportedTypes::get_name`.
+    ValueWritten {
+        name: &'a str,
+    },
+    DecodingFailed(&'a DecodingError),
+    EncodingFailed(&'a EncodingError),
+}
+
+impl fmt::Display for Event<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Event::RecordsSliced { records, bytes } => {
+                write!(f, "sliced {} records from {} bytes", records, bytes)
+            }
+            Event::TypeInferred { first_byte } => {
+                write!(f, "inferred type '{}'", *first_byte as char)
+            }
+            Event::ValueWritten { name } => write!(f, "writing {}", name),
+            Event::DecodingFailed(e) => write!(f, "{}", e),
+            Event::EncodingFailed(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// Receives the [`Event`]s reported while an observer is installed with
+/// [`with_observer`]. Does nothing by default.
+pub trait Observer {
+    fn on_event(&self, _event: &Event<'_>) {}
+}
+
+/// Ignores every event. This is what runs when no observer is installed.
+#[derive(Debug, Default)]
+pub struct NoopObserver;
+
+impl Observer for NoopObserver {}
+
+/// Prints every event to stderr.
+#[derive(Debug, Default)]
+pub struct StderrObserver;
+
+impl Observer for StderrObserver {
+    fn on_event(&self, event: &Event<'_>) {
+        eprintln!("[COPROTO] {}", event);
+    }
+}
+
+/// Keeps every event as a line of text, to be read back with [`LogBuffer::lines`].
+#[derive(Debug, Default)]
+pub struct LogBuffer {
+    lines: RefCell<Vec<String>>,
+}
+
+impl LogBuffer {
+    pub fn lines(&self) -> Vec<String> {
+        self.lines.borrow().clone()
+    }
+
+    pub fn clear(&self) {
+        self.lines.borrow_mut().clear();
+    }
+}
+
+impl Observer for LogBuffer {
+    fn on_event(&self, event: &Event<'_>) {
+        self.lines.borrow_mut().push(event.to_string());
+    }
+}
+
+thread_local! {
+    static OBSERVER: RefCell<Option<Rc<dyn Observer>>> = const { RefCell::new(None) };
+}
+
+// Puts the previous observer back even if `f` panics.
+struct Restore(Option<Rc<dyn Observer>>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        OBSERVER.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+/// Runs `f` with `observer` receiving the events reported on this thread.
+/// Calls can be nested; the innermost observer wins until it returns.
+pub fn with_observer<R>(observer: Rc<dyn Observer>, f: impl FnOnce() -> R) -> R {
+    let previous = OBSERVER.with(|current| current.borrow_mut().replace(observer));
+    let _restore = Restore(previous);
+
+    f()
+}
+
+pub(crate) fn emit(event: Event<'_>) {
+    let observer = OBSERVER.with(|current| current.borrow().clone());
+
+    if let Some(observer) = observer {
+        observer.on_event(&event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::{emit, with_observer, Event, LogBuffer};
+    use crate::types::{encode_value, infer_buffer, SupportedTypes};
+
+    #[test]
+    fn events_reach_the_observer() {
+        let log = Rc::new(LogBuffer::default());
+
+        let buff = encode_value(SupportedTypes::Array(vec![SupportedTypes::Integer(1)])).unwrap();
+
+        with_observer(log.clone(), || infer_buffer(&buff).unwrap());
+
+        let lines = log.lines();
+        assert!(lines.contains(&"inferred type '['".to_string()));
+        assert!(lines.contains(&"inferred type ':'".to_string()));
+        assert!(lines
+            .iter()
+            .any(|line| line.starts_with("sliced 1 records")));
+    }
+
+    #[test]
+    fn errors_are_reported() {
+        let log = Rc::new(LogBuffer::default());
+
+        with_observer(log.clone(), || infer_buffer(b"?").unwrap_err());
+
+        assert!(log.lines()[0].contains("[DECODING ERROR]"));
+    }
+
+    #[test]
+    fn observer_is_scoped() {
+        let outer = Rc::new(LogBuffer::default());
+        let inner = Rc::new(LogBuffer::default());
+
+        with_observer(outer.clone(), || {
+            with_observer(inner.clone(), || {
+                emit(Event::TypeInferred { first_byte: b':' })
+            });
+            emit(Event::TypeInferred { first_byte: b'#' });
+        });
+
+        emit(Event::TypeInferred { first_byte: b'-' });
+
+        assert_eq!(inner.lines(), vec!["inferred type ':'"]);
+        assert_eq!(outer.lines(), vec!["inferred type '#'"]);
+    }
+}
@@ -13,6 +13,16 @@ pub enum DecodingErrors {
     InternalError(Box<dyn Error>),
     InvalidTypeInCompositeType(String, String), // (found, expected)
     CantFitValues(String),                      // Just an explanation
+    Overflow(String, String),                   // (digits, target)
+    LimitExceeded {
+        what: String,
+        limit: usize,
+        found: usize,
+    },
+    TypeMismatch {
+        expected: String,
+        found: String,
+    },
 }
 
 impl fmt::Display for DecodingErrors {
@@ -54,17 +64,58 @@ impl fmt::Display for DecodingErrors {
                 )
             }
             DecodingErrors::CantFitValues(str) => str.to_string(),
+            DecodingErrors::Overflow(digits, target) => {
+                format!("{} does not fit in {}", digits, target)
+            }
+            DecodingErrors::LimitExceeded { what, limit, found } => {
+                format!("{} exceeds the limit of {}. Found {}", what, limit, found)
+            }
+            DecodingErrors::TypeMismatch { expected, found } => {
+                format!("Expected {}. Found {}", expected, found)
+            }
         };
 
         write!(f, "{}", err_str)
     }
 }
 
+/// Where a failing value sits inside the composites that contain it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathSegment {
+    Index(usize),
+    Name,
+    Value(String),
+    Header(usize),
+    Cell { row: usize, column: String },
+    Argument(usize),
+}
+
+impl fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PathSegment::Index(idx) => write!(f, "Array index {}", idx),
+            PathSegment::Name => write!(f, "name"),
+            PathSegment::Value(name) => write!(f, "value of '{}'", name),
+            PathSegment::Header(idx) => write!(f, "Table header {}", idx),
+            PathSegment::Cell { row, column } => {
+                write!(f, "Table row {}, column '{}'", row, column)
+            }
+            PathSegment::Argument(idx) => write!(f, "Command argument {}", idx),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct DecodingError {
     pub from: Vec<u8>,
     pub to: String,
-    pub cause: DecodingErrors,
+    // Boxed to keep the error cheap to return by value now that it also
+    // carries an offset and a path.
+    pub cause: Box<DecodingErrors>,
+    /// Position in `from` of the offending byte, when it is known.
+    pub offset: Option<usize>,
+    /// Outermost segment first.
+    pub path: Vec<PathSegment>,
 }
 
 impl DecodingError {
@@ -72,21 +123,144 @@ impl DecodingError {
         Self {
             from,
             to: to.to_string(),
-            cause,
+            cause: Box::new(cause),
+            offset: None,
+            path: vec![],
         }
     }
+
+    pub fn at(mut self, offset: usize) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Rebases the offset of an error raised on a slice that starts at
+    /// `start` in its parent. `from` is left alone: copying every parent would
+    /// cost the size of the message at each level, so the outermost decoder
+    /// sets it once with [`DecodingError::within`].
+    pub fn inside(mut self, start: usize) -> Self {
+        self.offset = Some(start + self.offset.unwrap_or(0));
+        self
+    }
+
+    /// Points the error at `message`, the buffer its offset is relative to.
+    pub fn within(mut self, message: &[u8]) -> Self {
+        self.from = message.to_vec();
+        self
+    }
+
+    pub fn under(mut self, segment: PathSegment) -> Self {
+        self.path.insert(0, segment);
+        self
+    }
 }
 
-impl fmt::Display for DecodingError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+// Bytes shown on each side of the offending one.
+const EXCERPT_CONTEXT: usize = 8;
+
+fn write_excerpt(
+    f: &mut std::fmt::Formatter<'_>,
+    from: &[u8],
+    offset: Option<usize>,
+) -> std::fmt::Result {
+    if from.is_empty() {
+        return write!(f, "\n    (empty buffer)");
+    }
+
+    let center = offset.unwrap_or(0).min(from.len() - 1);
+    let start = center.saturating_sub(EXCERPT_CONTEXT);
+    let end = (center + EXCERPT_CONTEXT + 1).min(from.len());
+
+    let lead = if start > 0 { "... " } else { "" };
+    let trail = if end < from.len() { " ..." } else { "" };
+
+    let hex: Vec<String> = from[start..end]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+
+    write!(f, "\n    {}{}{}", lead, hex.join(" "), trail)?;
+
+    if offset.is_some() {
         write!(
             f,
-            "[DECODING ERROR] Could not convert {:?} to {}\n  [ERROR ORIGIN] {}",
-            self.from, self.to, self.cause
-        )
+            "\n    {}{}^^",
+            " ".repeat(lead.len()),
+            " ".repeat((center - start) * 3)
+        )?;
+    }
+
+    Ok(())
+}
+
+impl fmt::Display for DecodingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[DECODING ERROR] Could not convert to {}", self.to)?;
+
+        if let Some(offset) = self.offset {
+            write!(f, " (byte {} of {})", offset, self.from.len())?;
+        }
+
+        if !self.path.is_empty() {
+            let path: Vec<String> = self.path.iter().map(|s| s.to_string()).collect();
+            write!(f, "\n  [PATH] {}", path.join(" > "))?;
+        }
+
+        write!(f, "\n  [ERROR ORIGIN] {}", self.cause)?;
+
+        write_excerpt(f, &self.from, self.offset)
     }
 }
 
 impl Error for DecodingError {}
 
 pub type DecodingResult<T> = Result<T, DecodingError>;
+
+#[cfg(test)]
+mod tests {
+    use super::{DecodingError, DecodingErrors, PathSegment};
+
+    #[test]
+    fn display_points_at_offset() {
+        let from: Vec<u8> = (0..32).collect();
+
+        let error =
+            DecodingError::new(from, "Integer", DecodingErrors::InvalidByte(20, 20, vec![]))
+                .at(20)
+                .under(PathSegment::Index(3));
+
+        let shown = error.to_string();
+
+        assert!(shown.contains("(byte 20 of 32)"));
+        assert!(shown.contains("[PATH] Array index 3"));
+        assert!(shown.contains("... 0c 0d 0e 0f 10 11 12 13 14 15 16 17 18 19 1a 1b 1c ..."));
+        // The caret sits under "14", the ninth byte of the excerpt.
+        assert!(shown.contains(&format!("\n    {}^^", " ".repeat(4 + 8 * 3))));
+    }
+
+    #[test]
+    fn inside_rebases_offset() {
+        let error = DecodingError::new(
+            vec![1, 2, 3],
+            "Boolean",
+            DecodingErrors::CantFitValues(String::new()),
+        )
+        .at(1)
+        .inside(2)
+        .under(PathSegment::Name)
+        .under(PathSegment::Argument(0));
+
+        assert_eq!(error.offset, Some(3));
+        assert_eq!(error.from, vec![1, 2, 3]);
+        assert_eq!(
+            error.path,
+            vec![PathSegment::Argument(0), PathSegment::Name]
+        );
+        assert!(error.to_string().contains("Command argument 0 > name"));
+
+        let error = error.within(&[9, 9, 1, 2, 3]);
+
+        assert_eq!(error.from, vec![9, 9, 1, 2, 3]);
+        assert!(error.to_string().contains("(byte 3 of 5)"));
+    }
+}
@@ -1,6 +1,8 @@
 pub mod decoding_error;
 pub mod encoding_error;
+pub mod registry_error;
 pub mod type_error;
 pub use decoding_error::*;
 pub use encoding_error::*;
+pub use registry_error::*;
 pub use type_error::*;
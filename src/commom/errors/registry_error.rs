@@ -0,0 +1,34 @@
+use core::fmt;
+use std::error::Error;
+
+/// Why [`crate::types::TypeRegistry::register`] refused a decoder.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RegistryError {
+    /// The byte is outside `EXTENSION_FIRST_BYTES`, so it belongs to the
+    /// built-in types or the delimiters.
+    Reserved(u8),
+    /// The byte already decodes the type named `existing`.
+    Conflict { first_byte: u8, existing: String },
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegistryError::Reserved(byte) => write!(
+                f,
+                "{:#04x} is reserved. Extension types must use 0x80 to 0xff",
+                byte
+            ),
+            RegistryError::Conflict {
+                first_byte,
+                existing,
+            } => write!(
+                f,
+                "{:#04x} is already registered to {}",
+                first_byte, existing
+            ),
+        }
+    }
+}
+
+impl Error for RegistryError {}
@@ -1,7 +1,9 @@
 use core::fmt;
 use std::error::Error;
 
-use super::{DecodingError, EncodingError};
+use crate::commom::diagnostics::{emit, Event};
+
+use super::{DecodingError, EncodingError, PathSegment};
 
 #[derive(Debug)]
 pub enum TypeError {
@@ -20,11 +22,37 @@ impl fmt::Display for TypeError {
 
 impl Error for TypeError {}
 
+impl TypeError {
+    /// See [`DecodingError::inside`]. Encoding errors pass through untouched.
+    pub fn inside(self, start: usize) -> Self {
+        match self {
+            TypeError::Decoding(de) => TypeError::Decoding(de.inside(start)),
+            other => other,
+        }
+    }
+
+    pub fn within(self, message: &[u8]) -> Self {
+        match self {
+            TypeError::Decoding(de) => TypeError::Decoding(de.within(message)),
+            other => other,
+        }
+    }
+
+    pub fn under(self, segment: PathSegment) -> Self {
+        match self {
+            TypeError::Decoding(de) => TypeError::Decoding(de.under(segment)),
+            other => other,
+        }
+    }
+}
+
 pub fn encoding_error(e: EncodingError) -> TypeError {
+    emit(Event::EncodingFailed(&e));
     TypeError::Encoding(e)
 }
 
 pub fn decoding_error(e: DecodingError) -> TypeError {
+    emit(Event::DecodingFailed(&e));
     TypeError::Decoding(e)
 }
 
@@ -0,0 +1,149 @@
+use std::io::Write;
+
+use super::{
+    delimiters::{ESCAPE, VALUE_DELIMITER},
+    errors::{DecodingError, DecodingErrors, DecodingResult, TypeResult},
+    write_bytes, Uint8Buff,
+};
+
+// Bytes from ESCAPE up to VALUE_DELIMITER are written as ESCAPE followed by
+// the byte with bit 0x20 flipped, so an escaped payload never carries a
+// delimiter and the record slicers can stay unaware of the escaping.
+const ESCAPE_MASK: u8 = 0x20;
+
+fn must_escape(byte: u8) -> bool {
+    (ESCAPE..=VALUE_DELIMITER).contains(&byte)
+}
+
+// Encoders go through `write_escaped`; this stays as its reference for tests.
+#[cfg(test)]
+pub fn escape_bytes(buff: Uint8Buff) -> Uint8Buff {
+    if !buff.iter().any(|b| must_escape(*b)) {
+        return buff;
+    }
+
+    let mut escaped: Uint8Buff = Vec::with_capacity(buff.len() + 4);
+
+    for byte in buff.into_iter() {
+        if must_escape(byte) {
+            escaped.push(ESCAPE);
+            escaped.push(byte ^ ESCAPE_MASK);
+            continue;
+        }
+
+        escaped.push(byte);
+    }
+
+    escaped
+}
+
+pub fn write_escaped<W: Write>(writer: &mut W, buff: &[u8]) -> TypeResult<()> {
+    for run in buff.split_inclusive(|b| must_escape(*b)) {
+        match run.split_last() {
+            Some((last, plain)) if must_escape(*last) => {
+                write_bytes(writer, plain)?;
+                write_bytes(writer, &[ESCAPE, last ^ ESCAPE_MASK])?;
+            }
+            _ => write_bytes(writer, run)?,
+        }
+    }
+
+    Ok(())
+}
+
+pub fn unescape_bytes(buff: Uint8Buff) -> DecodingResult<Uint8Buff> {
+    if !buff.contains(&ESCAPE) {
+        return Ok(buff);
+    }
+
+    let mut unescaped: Uint8Buff = Vec::with_capacity(buff.len());
+
+    let mut bytes = buff.iter().enumerate();
+
+    while let Some((idx, byte)) = bytes.next() {
+        if *byte != ESCAPE {
+            unescaped.push(*byte);
+            continue;
+        }
+
+        match bytes.next() {
+            Some((_, escaped)) if must_escape(escaped ^ ESCAPE_MASK) => {
+                unescaped.push(escaped ^ ESCAPE_MASK)
+            }
+            Some((position, escaped)) => {
+                return Err(DecodingError::new(
+                    buff.clone(),
+                    "Unescaped bytes",
+                    DecodingErrors::InvalidByte(
+                        *escaped,
+                        position.try_into().unwrap_or(u32::MAX),
+                        (ESCAPE..=VALUE_DELIMITER)
+                            .map(|b| b ^ ESCAPE_MASK)
+                            .collect(),
+                    ),
+                )
+                .at(position))
+            }
+            None => {
+                return Err(DecodingError::new(
+                    buff.clone(),
+                    "Unescaped bytes",
+                    DecodingErrors::NotEnough("Escaped bytes".to_string(), 1, 0),
+                )
+                .at(idx))
+            }
+        }
+    }
+
+    Ok(unescaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commom::{
+        delimiters::{BUFFER_END, END_RECORD, ESCAPE, START_RECORD, VALUE_DELIMITER},
+        escape_bytes, unescape_bytes, write_escaped, Uint8Buff,
+    };
+
+    #[test]
+    fn every_byte_round_trips() {
+        for byte in 0..=u8::MAX {
+            let escaped = escape_bytes(vec![byte]);
+
+            for delimiter in [START_RECORD, END_RECORD, BUFFER_END, VALUE_DELIMITER] {
+                assert!(!escaped.contains(&delimiter), "byte {} leaked", byte);
+            }
+
+            assert_eq!(unescape_bytes(escaped).unwrap(), vec![byte]);
+        }
+
+        let all_bytes: Uint8Buff = (0..=u8::MAX).collect();
+
+        let escaped = escape_bytes(all_bytes.clone());
+
+        assert_eq!(unescape_bytes(escaped).unwrap(), all_bytes);
+    }
+
+    #[test]
+    fn write_escaped_matches_escape_bytes() {
+        let all_bytes: Uint8Buff = (0..=u8::MAX).chain([ESCAPE, ESCAPE, b'a']).collect();
+
+        let mut written: Uint8Buff = vec![];
+        write_escaped(&mut written, &all_bytes).unwrap();
+
+        assert_eq!(written, escape_bytes(all_bytes));
+    }
+
+    #[test]
+    fn plain_bytes_are_untouched() {
+        let plain = b"Hello, fellow rustacean!".to_vec();
+
+        assert_eq!(escape_bytes(plain.clone()), plain);
+    }
+
+    #[test]
+    fn invalid_escape_sequences() {
+        assert!(unescape_bytes(vec![b'a', ESCAPE]).is_err());
+        assert!(unescape_bytes(vec![ESCAPE, b'a']).is_err());
+    }
+}
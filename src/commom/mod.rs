@@ -1,19 +1,26 @@
-pub mod coproto_type;
+pub mod codec;
+pub mod decode_limits;
 pub mod delimiters;
+pub mod diagnostics;
 pub mod errors;
-pub mod get_up_to;
+pub(crate) mod escape;
 pub mod is_known_firs_byte;
-pub mod join_parts;
+#[cfg(test)]
+pub(crate) mod join_parts;
 pub mod modifiers;
-pub mod slice_records;
-pub mod split_values;
-pub mod to_ascii_code;
+pub(crate) mod scan_frame;
+pub(crate) mod slice_records;
+pub(crate) mod take_first_byte;
 pub mod uint8_buff;
-pub use coproto_type::*;
-pub use get_up_to::*;
-pub use is_known_firs_byte::is_known_first_byte;
-pub use join_parts::*;
-pub use slice_records::*;
-pub use split_values::*;
-pub use to_ascii_code::*;
+pub(crate) mod write_bytes;
+pub use codec::*;
+pub use decode_limits::DecodeLimits;
+pub(crate) use escape::*;
+pub use is_known_firs_byte::{is_known_first_byte, known_first_bytes};
+#[cfg(test)]
+pub(crate) use join_parts::*;
+pub(crate) use scan_frame::*;
+pub(crate) use slice_records::*;
+pub(crate) use take_first_byte::*;
 pub use uint8_buff::Uint8Buff;
+pub(crate) use write_bytes::*;
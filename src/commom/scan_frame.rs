@@ -0,0 +1,156 @@
+use super::{
+    delimiters::{BUFFER_END, END_RECORD, START_RECORD},
+    errors::{DecodingError, DecodingErrors, DecodingResult},
+    is_known_first_byte, known_first_bytes,
+};
+
+/// Looks for the end of the first frame in `buff`. A frame ends at the first
+/// `BUFFER_END` found outside of any record.
+///
+/// Returns `Ok(Some(len))` with the frame length (`BUFFER_END` included),
+/// `Ok(None)` when more bytes are needed, or an error when the bytes can never
+/// form a valid frame.
+pub fn scan_frame(buff: &[u8]) -> DecodingResult<Option<usize>> {
+    FrameScanner::default().resume(buff)
+}
+
+/// [`scan_frame`] split across calls: it remembers how far it got and the
+/// record depth there, so bytes that arrive later are the only ones scanned.
+/// Escaped payloads never carry a delimiter, so no other state is needed.
+#[derive(Debug, Default)]
+pub struct FrameScanner {
+    scanned: usize,
+    depth: usize,
+}
+
+impl FrameScanner {
+    /// Continues scanning `buff`, which must start at the same frame and only
+    /// have grown since the last call. After a frame is found, or an error,
+    /// the scanner starts over with the next call.
+    pub fn resume(&mut self, buff: &[u8]) -> DecodingResult<Option<usize>> {
+        let first_byte = match buff.first() {
+            Some(fb) => *fb,
+            None => return Ok(None),
+        };
+
+        if self.scanned == 0 && !is_known_first_byte(first_byte) {
+            return Err(DecodingError::new(
+                buff.to_vec(),
+                "Frame",
+                DecodingErrors::UnknownFirstByte(first_byte, known_first_bytes()),
+            )
+            .at(0));
+        }
+
+        for (idx, byte) in buff.iter().enumerate().skip(self.scanned) {
+            match *byte {
+                START_RECORD => self.depth += 1,
+                END_RECORD => {
+                    self.depth = match self.depth.checked_sub(1) {
+                        Some(d) => d,
+                        None => {
+                            self.reset();
+
+                            return Err(DecodingError::new(
+                                buff[..=idx].to_vec(),
+                                "Frame",
+                                DecodingErrors::InvalidByte(
+                                    END_RECORD,
+                                    idx.try_into().unwrap_or(u32::MAX),
+                                    vec![START_RECORD, BUFFER_END],
+                                ),
+                            )
+                            .at(idx));
+                        }
+                    }
+                }
+                BUFFER_END if self.depth == 0 => {
+                    self.reset();
+                    return Ok(Some(idx + 1));
+                }
+                _ => {}
+            }
+        }
+
+        self.scanned = buff.len();
+
+        Ok(None)
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commom::{
+        delimiters::{BUFFER_END, END_RECORD, START_RECORD},
+        scan_frame, FrameScanner,
+    };
+
+    #[test]
+    fn complete_and_partial_frames() {
+        let frame = vec![b':', b'+', START_RECORD, 4, 2, END_RECORD, BUFFER_END];
+
+        assert_eq!(scan_frame(&frame).unwrap(), Some(frame.len()));
+
+        for end in 0..frame.len() {
+            assert_eq!(scan_frame(&frame[..end]).unwrap(), None);
+        }
+
+        let mut two_frames = frame.clone();
+        two_frames.extend_from_slice(&frame);
+
+        assert_eq!(scan_frame(&two_frames).unwrap(), Some(frame.len()));
+    }
+
+    #[test]
+    fn nested_buffer_end_is_ignored() {
+        let frame = vec![
+            b'[',
+            START_RECORD,
+            START_RECORD,
+            b'-',
+            START_RECORD,
+            END_RECORD,
+            BUFFER_END,
+            END_RECORD,
+            END_RECORD,
+            BUFFER_END,
+        ];
+
+        assert_eq!(scan_frame(&frame).unwrap(), Some(frame.len()));
+    }
+
+    #[test]
+    fn malformed_frames() {
+        assert!(scan_frame(&[b'?', START_RECORD]).is_err());
+        assert!(scan_frame(&[b'#', END_RECORD, BUFFER_END]).is_err());
+    }
+
+    #[test]
+    fn resumed_scans_match_whole_scans() {
+        let frame = vec![
+            b'[',
+            START_RECORD,
+            START_RECORD,
+            b'-',
+            START_RECORD,
+            END_RECORD,
+            BUFFER_END,
+            END_RECORD,
+            END_RECORD,
+            BUFFER_END,
+        ];
+
+        let mut scanner = FrameScanner::default();
+
+        for end in 0..frame.len() {
+            assert_eq!(scanner.resume(&frame[..end]).unwrap(), None);
+        }
+
+        assert_eq!(scanner.resume(&frame).unwrap(), Some(frame.len()));
+        assert_eq!(scanner.resume(&frame[..3]).unwrap(), None);
+    }
+}
@@ -1,88 +1,70 @@
+use std::ops::Range;
+
 use super::{
-    delimiters::{END_RECORD, START_RECORD, VALUE_DELIMITER},
-    is_known_first_byte, Uint8Buff,
+    delimiters::{END_RECORD, START_RECORD},
+    diagnostics::{emit, Event},
+    errors::{DecodingError, DecodingErrors, DecodingResult},
 };
 
-fn trim_record_delimiters(buff: &mut Uint8Buff) -> Option<&mut Uint8Buff> {
-    let first_byte = buff.remove(0);
-
-    let last_byte = match buff.pop() {
-        Some(lb) => lb,
-        None => return None,
-    };
-
-    if first_byte != START_RECORD || last_byte != END_RECORD {
-        return None;
-    };
-
-    Some(buff)
-}
-
-fn inspect_record(buff: &mut Uint8Buff) -> Option<Uint8Buff> {
-    if buff.is_empty() {
-        return None;
-    }
-
-    trim_record_delimiters(buff);
-
-    let next_byte = match buff.first() {
-        Some(nb) => nb,
-        None => return None,
-    };
+/// Finds the top-level records of `buff` in a single pass. Each range covers
+/// the record body, without its `START_RECORD`/`END_RECORD` pair; nested
+/// records stay inside their parent's range.
+pub fn slice_records(buff: &[u8]) -> DecodingResult<Vec<Range<usize>>> {
+    let mut records: Vec<Range<usize>> = vec![];
 
-    match is_known_first_byte(*next_byte) {
-        true => Some(buff.to_vec()),
-        false => None,
-    }
-}
-
-fn slicer_must_inspect_records(buff: &Uint8Buff) -> bool {
-    buff.contains(&VALUE_DELIMITER)
-}
-
-pub fn slice_records(buff: Uint8Buff) -> Vec<Uint8Buff> {
-    let should_inspect = slicer_must_inspect_records(&buff);
-
-    let mut records: Vec<Uint8Buff> = vec![];
+    let mut depth: usize = 0;
+    let mut start: usize = 0;
 
     for (idx, byte) in buff.iter().enumerate() {
-        if *byte == START_RECORD {
-            let mut should_ignore_next = false;
-
-            for (n_idx, n_byte) in buff[idx + 1..buff.len()].iter().enumerate() {
-                if *n_byte == START_RECORD {
-                    should_ignore_next = true;
-                    continue;
+        match *byte {
+            START_RECORD => {
+                if depth == 0 {
+                    start = idx + 1;
                 }
 
-                if *n_byte == END_RECORD {
-                    if !should_ignore_next {
-                        let mut found_record = buff[idx..=n_idx + idx + 1].to_vec();
-
-                        if should_inspect {
-                            if let Some(b) = inspect_record(&mut found_record) {
-                                records.push(b)
-                            }
-
-                            break;
-                        }
-
-                        if let Some(fr) = trim_record_delimiters(&mut found_record) {
-                            records.push(fr.to_vec())
-                        };
-
-                        break;
+                depth += 1;
+            }
+            END_RECORD => {
+                depth = match depth.checked_sub(1) {
+                    Some(d) => d,
+                    None => {
+                        return Err(DecodingError::new(
+                            buff.to_vec(),
+                            "Records",
+                            DecodingErrors::InvalidByte(
+                                END_RECORD,
+                                idx.try_into().unwrap_or(u32::MAX),
+                                vec![START_RECORD],
+                            ),
+                        )
+                        .at(idx))
                     }
+                };
 
-                    should_ignore_next = false;
+                if depth == 0 {
+                    records.push(start..idx);
                 }
             }
-
-            continue;
+            _ => {}
         }
     }
 
-    records
+    if depth != 0 {
+        return Err(DecodingError::new(
+            buff.to_vec(),
+            "Records",
+            DecodingErrors::CouldNotFind(END_RECORD, "END_RECORD".to_string()),
+        )
+        // Points at the outermost START_RECORD left open.
+        .at(start - 1));
+    }
+
+    emit(Event::RecordsSliced {
+        records: records.len(),
+        bytes: buff.len(),
+    });
+
+    Ok(records)
 }
 
 #[cfg(test)]
@@ -92,13 +74,21 @@ mod tests {
         slice_records, Uint8Buff,
     };
 
+    fn sliced(buff: &[u8]) -> Vec<Uint8Buff> {
+        slice_records(buff)
+            .unwrap()
+            .into_iter()
+            .map(|range| buff[range].to_vec())
+            .collect()
+    }
+
     #[test]
     fn primitive_types_buffer() {
         let vec_to_test = vec![START_RECORD, 1, 2, 3, 4, 5, 6, 7, 8, 9, END_RECORD];
 
         let vec_to_be: Uint8Buff = vec![1, 2, 3, 4, 5, 6, 7, 8, 9];
 
-        let sliced = slice_records(vec_to_test);
+        let sliced = sliced(&vec_to_test);
 
         println!("Sliced:\n{:?}", sliced);
 
@@ -231,7 +221,7 @@ mod tests {
             .to_vec(),
         ];
 
-        let sliced = slice_records(vec_to_test);
+        let sliced = sliced(&vec_to_test);
 
         println!("Sliced:\n{:?}", sliced);
 
@@ -240,4 +230,61 @@ mod tests {
             assert_eq!(sliced[idx], vec_to_be[idx])
         }
     }
+
+    #[test]
+    fn deep_nesting_stays_in_parent() {
+        // [ [ [ [1] ] ] ] [2]
+        let buff = vec![
+            START_RECORD,
+            START_RECORD,
+            START_RECORD,
+            START_RECORD,
+            1,
+            END_RECORD,
+            END_RECORD,
+            END_RECORD,
+            END_RECORD,
+            START_RECORD,
+            2,
+            END_RECORD,
+        ];
+
+        let records = slice_records(&buff).unwrap();
+
+        assert_eq!(records, vec![1..8, 10..11]);
+        assert_eq!(
+            slice_records(&buff[records[0].clone()]).unwrap(),
+            vec![1..6]
+        );
+    }
+
+    #[test]
+    fn large_buffers_are_sliced_linearly() {
+        let depth = 50_000;
+        let records = 50_000;
+
+        let mut buff: Uint8Buff = vec![START_RECORD; depth];
+        buff.extend(vec![END_RECORD; depth]);
+
+        for n in 0..records {
+            buff.extend([START_RECORD, (n % 10) as u8, END_RECORD]);
+        }
+
+        let sliced = slice_records(&buff).unwrap();
+
+        assert_eq!(sliced.len(), records + 1);
+        assert_eq!(sliced[0], 1..(depth * 2 - 1));
+        assert_eq!(buff[sliced[records].clone()], [((records - 1) % 10) as u8]);
+    }
+
+    #[test]
+    fn unbalanced_delimiters_are_rejected() {
+        let stray_end = [START_RECORD, 1, END_RECORD, END_RECORD];
+        assert!(slice_records(&stray_end).is_err());
+
+        let unclosed = [START_RECORD, 1, START_RECORD, 2, END_RECORD];
+        assert!(slice_records(&unclosed).is_err());
+
+        assert_eq!(slice_records(&[]).unwrap(), vec![]);
+    }
 }
@@ -0,0 +1,36 @@
+use super::{
+    errors::{DecodingError, DecodingErrors, DecodingResult},
+    Uint8Buff,
+};
+
+/// Removes and returns the first byte of `buff`, failing instead of panicking
+/// when there is nothing to take.
+pub fn take_first_byte(buff: &mut Uint8Buff, to: &str) -> DecodingResult<u8> {
+    if buff.is_empty() {
+        return Err(DecodingError::new(
+            vec![],
+            to,
+            DecodingErrors::NotEnough("Bytes".to_string(), 1, 0),
+        ));
+    }
+
+    Ok(buff.remove(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commom::take_first_byte;
+
+    #[test]
+    fn empty_buffer_is_an_error() {
+        assert!(take_first_byte(&mut vec![], "Integer").is_err());
+    }
+
+    #[test]
+    fn first_byte_is_taken() {
+        let mut buff = vec![b':', b'+'];
+
+        assert_eq!(take_first_byte(&mut buff, "Integer").unwrap(), b':');
+        assert_eq!(buff, vec![b'+']);
+    }
+}
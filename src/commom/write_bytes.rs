@@ -0,0 +1,79 @@
+use std::io::Write;
+
+use super::errors::{encoding_error, EncodingError, EncodingErrors, TypeResult};
+
+pub fn write_bytes<W: Write>(writer: &mut W, bytes: &[u8]) -> TypeResult<()> {
+    match writer.write_all(bytes) {
+        Ok(()) => Ok(()),
+        Err(e) => Err(encoding_error(EncodingError::new(
+            "Bytes",
+            "Writer",
+            EncodingErrors::InternalError(Box::new(e)),
+        ))),
+    }
+}
+
+/// Writes the decimal digits of `magnitude` as values from 0 to 9, most
+/// significant first. Zero writes nothing.
+pub fn write_digits<W: Write>(writer: &mut W, mut magnitude: u64) -> TypeResult<()> {
+    let mut digits = [0u8; 20];
+    let mut start = digits.len();
+
+    while magnitude != 0 {
+        start -= 1;
+        digits[start] = (magnitude % 10) as u8;
+        magnitude /= 10;
+    }
+
+    write_bytes(writer, &digits[start..])
+}
+
+/// `Write` implementation that only counts what goes through it.
+#[derive(Debug, Default)]
+pub struct ByteCounter {
+    count: usize,
+}
+
+impl ByteCounter {
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+impl Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.count += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commom::{write_digits, ByteCounter, Uint8Buff};
+
+    #[test]
+    fn digits_are_written_most_significant_first() {
+        let mut written: Uint8Buff = vec![];
+        write_digits(&mut written, 1203).unwrap();
+        assert_eq!(written, vec![1, 2, 0, 3]);
+
+        let mut written: Uint8Buff = vec![];
+        write_digits(&mut written, u64::MAX).unwrap();
+        assert_eq!(written.len(), 20);
+
+        let mut written: Uint8Buff = vec![];
+        write_digits(&mut written, 0).unwrap();
+        assert!(written.is_empty());
+    }
+
+    #[test]
+    fn counter_counts_digits() {
+        let mut counter = ByteCounter::default();
+        write_digits(&mut counter, 987654321).unwrap();
+        assert_eq!(counter.count(), 9);
+    }
+}
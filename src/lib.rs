@@ -0,0 +1,57 @@
+//! Encoding and decoding for the coproto wire format.
+//!
+//! [`encode`] and [`decode`] cover most uses. Each type also has its own
+//! [`Codec`] implementation in [`types`], and [`types::Decoder`] reads
+//! values out of a byte stream.
+
+pub mod commom;
+#[cfg(feature = "serde")]
+pub mod serde_support;
+pub mod types;
+
+pub use commom::errors::{
+    DecodingError, DecodingErrors, EncodingError, EncodingErrors, PathSegment, RegistryError,
+    TypeError, TypeResult,
+};
+pub use commom::{Codec, DecodeLimits, DynCodec, Lazy, Uint8Buff, ValueOrBuffer};
+pub use types::{FromValue, SupportedTypes, ToValue, ValueRef};
+
+#[cfg(feature = "derive")]
+pub use coproto_derive::Coproto;
+#[cfg(feature = "serde")]
+pub use serde_support::{from_bytes, to_bytes};
+
+/// Encodes `value` as a complete message, `BUFFER_END` included.
+pub fn encode(value: &SupportedTypes) -> TypeResult<Uint8Buff> {
+    let mut buff: Uint8Buff = vec![];
+    types::encode_value_to(value, &mut buff)?;
+    Ok(buff)
+}
+
+/// Decodes a complete message, inferring its type from the first byte.
+pub fn decode(buff: &[u8]) -> TypeResult<SupportedTypes> {
+    types::infer_buffer(buff)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{decode, encode, types::BigNum, SupportedTypes, TypeError};
+
+    #[test]
+    fn round_trip() {
+        let value = SupportedTypes::NamedValue(
+            "answer".to_string(),
+            Box::new(SupportedTypes::Array(vec![
+                SupportedTypes::Integer(42),
+                SupportedTypes::HugeInt(BigNum::from(u128::MAX)),
+            ])),
+        );
+
+        assert_eq!(decode(&encode(&value).unwrap()).unwrap(), value);
+    }
+
+    #[test]
+    fn decode_rejects_garbage() {
+        assert!(matches!(decode(b"?"), Err(TypeError::Decoding(_))));
+    }
+}
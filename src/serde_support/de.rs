@@ -0,0 +1,365 @@
+use std::fmt::Display;
+
+use ::serde::de::{
+    self, value::StringDeserializer, DeserializeSeed, IntoDeserializer, Unexpected, Visitor,
+};
+use ::serde::forward_to_deserialize_any;
+
+use crate::{
+    commom::errors::{
+        decoding_error, DecodingError, DecodingErrors, PathSegment, TypeError, TypeResult,
+    },
+    types::SupportedTypes,
+};
+
+impl de::Error for TypeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        decoding_error(DecodingError::new(
+            vec![],
+            "Rust value",
+            DecodingErrors::CantFitValues(msg.to_string()),
+        ))
+    }
+}
+
+/// Reads any `Deserialize` value out of a [`SupportedTypes`] tree.
+pub struct Deserializer {
+    value: SupportedTypes,
+}
+
+impl Deserializer {
+    pub fn new(value: SupportedTypes) -> Self {
+        Self { value }
+    }
+}
+
+fn unexpected(value: &SupportedTypes) -> Unexpected<'_> {
+    match value {
+        SupportedTypes::BigInt(v) => Unexpected::Signed(*v),
+        SupportedTypes::Boolean(v) => Unexpected::Bool(*v),
+        SupportedTypes::Double(v) => Unexpected::Float(*v),
+        SupportedTypes::Integer(v) => Unexpected::Signed((*v).into()),
+        SupportedTypes::Null(_) => Unexpected::Unit,
+        SupportedTypes::String(v) => Unexpected::Str(v),
+        SupportedTypes::Bytes(v) => Unexpected::Bytes(v),
+        SupportedTypes::Array(_) | SupportedTypes::Table(_, _) => Unexpected::Seq,
+        SupportedTypes::NamedValue(_, _) | SupportedTypes::Command(_, _) => Unexpected::Map,
+        SupportedTypes::HugeInt(_) => Unexpected::Other("HugeInt"),
+        SupportedTypes::Extension(_, value) => unexpected(value),
+    }
+}
+
+// Named fields, when every item of the Array is a NamedValue.
+fn fields(items: &[SupportedTypes]) -> bool {
+    items
+        .iter()
+        .all(|item| matches!(item, SupportedTypes::NamedValue(_, _)))
+}
+
+// Each Table row as the named fields it was built from.
+fn rows(headers: Vec<String>, rows: Vec<Vec<SupportedTypes>>) -> Vec<SupportedTypes> {
+    rows.into_iter()
+        .map(|row| {
+            SupportedTypes::Array(
+                headers
+                    .iter()
+                    .zip(row)
+                    .map(|(header, cell)| {
+                        SupportedTypes::NamedValue(header.clone(), Box::new(cell))
+                    })
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+struct SeqAccess {
+    items: std::vec::IntoIter<SupportedTypes>,
+    index: usize,
+}
+
+impl<'de> de::SeqAccess<'de> for SeqAccess {
+    type Error = TypeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> TypeResult<Option<T::Value>> {
+        match self.items.next() {
+            Some(item) => {
+                let index = self.index;
+                self.index += 1;
+
+                seed.deserialize(Deserializer::new(item))
+                    .map(Some)
+                    .map_err(|e| e.under(PathSegment::Index(index)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.items.len())
+    }
+}
+
+fn visit_seq<'de, V: Visitor<'de>>(items: Vec<SupportedTypes>, visitor: V) -> TypeResult<V::Value> {
+    let mut access = SeqAccess {
+        items: items.into_iter(),
+        index: 0,
+    };
+
+    let value = visitor.visit_seq(&mut access)?;
+
+    match access.items.len() {
+        0 => Ok(value),
+        left => Err(de::Error::invalid_length(
+            access.index + left,
+            &"fewer elements",
+        )),
+    }
+}
+
+struct MapAccess {
+    fields: std::vec::IntoIter<SupportedTypes>,
+    pending: Option<(String, SupportedTypes)>,
+}
+
+impl<'de> de::MapAccess<'de> for MapAccess {
+    type Error = TypeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> TypeResult<Option<K::Value>> {
+        match self.fields.next() {
+            Some(SupportedTypes::NamedValue(name, value)) => {
+                let key = seed.deserialize(name.clone().into_deserializer())?;
+                self.pending = Some((name, *value));
+                Ok(Some(key))
+            }
+            Some(other) => Err(de::Error::invalid_type(unexpected(&other), &"a NamedValue")),
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> TypeResult<V::Value> {
+        match self.pending.take() {
+            Some((name, value)) => seed
+                .deserialize(Deserializer::new(value))
+                .map_err(|e| e.under(PathSegment::Value(name))),
+            None => Err(de::Error::custom("value requested before its key")),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.fields.len())
+    }
+}
+
+fn visit_map<'de, V: Visitor<'de>>(
+    fields: Vec<SupportedTypes>,
+    visitor: V,
+) -> TypeResult<V::Value> {
+    visitor.visit_map(MapAccess {
+        fields: fields.into_iter(),
+        pending: None,
+    })
+}
+
+struct EnumAccess {
+    variant: String,
+    value: SupportedTypes,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumAccess {
+    type Error = TypeError;
+    type Variant = Deserializer;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> TypeResult<(V::Value, Deserializer)> {
+        let variant: StringDeserializer<TypeError> = self.variant.into_deserializer();
+        Ok((seed.deserialize(variant)?, Deserializer::new(self.value)))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for Deserializer {
+    type Error = TypeError;
+
+    fn unit_variant(self) -> TypeResult<()> {
+        match self.value {
+            SupportedTypes::Null(_) => Ok(()),
+            other => Err(de::Error::invalid_type(
+                unexpected(&other),
+                &"a unit variant",
+            )),
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> TypeResult<T::Value> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> TypeResult<V::Value> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> TypeResult<V::Value> {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer {
+    type Error = TypeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> TypeResult<V::Value> {
+        match self.value {
+            SupportedTypes::BigInt(v) => visitor.visit_i64(v),
+            SupportedTypes::Boolean(v) => visitor.visit_bool(v),
+            SupportedTypes::Double(v) => visitor.visit_f64(v),
+            SupportedTypes::Integer(v) => visitor.visit_i32(v),
+            SupportedTypes::Null(_) => visitor.visit_unit(),
+            SupportedTypes::String(v) => visitor.visit_string(v),
+            // Visitors that buffer the value, like untagged enums, may not
+            // take 128 bits, so those are only used when nothing smaller fits.
+            SupportedTypes::HugeInt(v) => {
+                if let Ok(v) = u64::try_from(&v) {
+                    return visitor.visit_u64(v);
+                }
+
+                if let Ok(v) = i64::try_from(&v) {
+                    return visitor.visit_i64(v);
+                }
+
+                match (i128::try_from(&v), u128::try_from(&v)) {
+                    (Ok(v), _) => visitor.visit_i128(v),
+                    (_, Ok(v)) => visitor.visit_u128(v),
+                    (Err(e), _) => Err(de::Error::custom(e)),
+                }
+            }
+            SupportedTypes::Bytes(v) => visitor.visit_byte_buf(v),
+            SupportedTypes::Array(items) if !items.is_empty() && fields(&items) => {
+                visit_map(items, visitor)
+            }
+            SupportedTypes::Array(items) => visit_seq(items, visitor),
+            SupportedTypes::NamedValue(name, value) => {
+                visit_map(vec![SupportedTypes::NamedValue(name, value)], visitor)
+            }
+            SupportedTypes::Table(headers, table_rows) => {
+                visit_seq(rows(headers, table_rows), visitor)
+            }
+            SupportedTypes::Extension(_, value) => {
+                Deserializer::new(*value).deserialize_any(visitor)
+            }
+            SupportedTypes::Command(name, args) => {
+                let args = args.into_iter().map(SupportedTypes::String).collect();
+                visit_map(
+                    vec![SupportedTypes::NamedValue(
+                        name,
+                        Box::new(SupportedTypes::Array(args)),
+                    )],
+                    visitor,
+                )
+            }
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> TypeResult<V::Value> {
+        match self.value {
+            SupportedTypes::Null(_) => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> TypeResult<V::Value> {
+        match self.value {
+            SupportedTypes::Null(_) => visitor.visit_unit(),
+            other => Err(de::Error::invalid_type(unexpected(&other), &visitor)),
+        }
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> TypeResult<V::Value> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> TypeResult<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> TypeResult<V::Value> {
+        match self.value {
+            SupportedTypes::Array(items) => visit_seq(items, visitor),
+            SupportedTypes::Table(headers, table_rows) => {
+                visit_seq(rows(headers, table_rows), visitor)
+            }
+            other => Err(de::Error::invalid_type(unexpected(&other), &visitor)),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> TypeResult<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> TypeResult<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> TypeResult<V::Value> {
+        match self.value {
+            SupportedTypes::Array(items) if fields(&items) => visit_map(items, visitor),
+            SupportedTypes::NamedValue(name, value) => {
+                visit_map(vec![SupportedTypes::NamedValue(name, value)], visitor)
+            }
+            other => Err(de::Error::invalid_type(unexpected(&other), &visitor)),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> TypeResult<V::Value> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> TypeResult<V::Value> {
+        match self.value {
+            SupportedTypes::String(variant) => visitor.visit_enum(EnumAccess {
+                variant,
+                value: SupportedTypes::Null(None),
+            }),
+            SupportedTypes::NamedValue(variant, value) => visitor.visit_enum(EnumAccess {
+                variant,
+                value: *value,
+            }),
+            other => Err(de::Error::invalid_type(unexpected(&other), &visitor)),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf identifier ignored_any
+    }
+}
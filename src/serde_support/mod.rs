@@ -0,0 +1,270 @@
+//! `serde` support, behind the `serde` feature.
+//!
+//! | Rust                                   | coproto                              |
+//! |----------------------------------------|--------------------------------------|
+//! | `bool`                                 | Boolean                              |
+//! | `i8`, `i16`, `i32`, `u8`, `u16`        | Integer                              |
+//! | `i64`, `u32`                           | BigInt                               |
+//! | `u64`, `usize`                         | BigInt, or HugeInt past `i64::MAX`   |
+//! | `i128`, `u128`                         | HugeInt                              |
+//! | `f32`, `f64`                           | Double                               |
+//! | `char`, `String`                       | String                               |
+//! | `serde_bytes` and other byte buffers   | Bytes                                |
+//! | `None`, `()`, unit structs             | Null                                 |
+//! | `Some(v)`, newtype structs             | `v`                                  |
+//! | sequences, tuples                      | Array                                |
+//! | structs, string-keyed maps             | Array of NamedValues, one per field  |
+//! | sequences of structs with equal fields | Table, headed by the field names     |
+//! | unit variants                          | String with the variant name         |
+//! | other variants                         | NamedValue with the variant name     |
+//!
+//! Reading is more lenient: any integer type reads any number that fits, a
+//! single NamedValue reads as a one-field struct, and a Table reads as a
+//! sequence of structs.
+//!
+//! Options are not nested on the wire, so `Some(None)` and `Some(())` are
+//! both Null and read back as `None`.
+
+mod de;
+mod ser;
+
+pub use de::Deserializer;
+pub use ser::Serializer;
+
+use ::serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    commom::errors::{TypeError, TypeResult},
+    types::SupportedTypes,
+    Uint8Buff,
+};
+
+pub fn to_value<T: ?Sized + Serialize>(value: &T) -> TypeResult<SupportedTypes> {
+    value.serialize(Serializer)
+}
+
+pub fn from_value<T: DeserializeOwned>(value: SupportedTypes) -> TypeResult<T> {
+    T::deserialize(Deserializer::new(value))
+}
+
+/// Encodes `value` as a complete message, like [`crate::encode`].
+pub fn to_bytes<T: ?Sized + Serialize>(value: &T) -> TypeResult<Uint8Buff> {
+    crate::encode(&to_value(value)?)
+}
+
+/// Decodes a complete message into `T`.
+pub fn from_bytes<T: DeserializeOwned>(buff: &[u8]) -> TypeResult<T> {
+    match from_value(crate::decode(buff)?) {
+        // Point errors about the shape of the value at the message it came from.
+        Err(TypeError::Decoding(mut e)) if e.from.is_empty() => {
+            e.from = buff.to_vec();
+            Err(TypeError::Decoding(e))
+        }
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        commom::errors::{PathSegment, TypeError},
+        types::{BigNum, SupportedTypes},
+    };
+
+    use super::{from_bytes, from_value, to_bytes, to_value};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Status {
+        Active,
+        Suspended { days: u8 },
+        Renamed(String),
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct User {
+        id: u32,
+        name: String,
+        email: Option<String>,
+        status: Status,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Report {
+        title: String,
+        users: Vec<User>,
+        totals: (i8, i64, u128),
+        tags: BTreeMap<String, bool>,
+    }
+
+    fn report() -> Report {
+        Report {
+            title: "weekly".to_string(),
+            users: vec![
+                User {
+                    id: 1,
+                    name: "Ann".to_string(),
+                    email: None,
+                    status: Status::Active,
+                },
+                User {
+                    id: 2,
+                    name: "Bob".to_string(),
+                    email: Some("bob@example.com".to_string()),
+                    status: Status::Suspended { days: 3 },
+                },
+            ],
+            totals: (-1, 1 << 40, u128::MAX),
+            tags: BTreeMap::from([("beta".to_string(), true)]),
+        }
+    }
+
+    #[test]
+    fn round_trips_through_bytes() {
+        let report = report();
+
+        assert_eq!(
+            from_bytes::<Report>(&to_bytes(&report).unwrap()).unwrap(),
+            report
+        );
+
+        let renamed = vec![Status::Renamed("x".to_string()), Status::Active];
+        assert_eq!(
+            from_bytes::<Vec<Status>>(&to_bytes(&renamed).unwrap()).unwrap(),
+            renamed
+        );
+    }
+
+    #[test]
+    fn structs_map_to_the_wire_types() {
+        let value = to_value(&report()).unwrap();
+
+        let SupportedTypes::Array(fields) = value else {
+            panic!("Not an Array: {:?}", value);
+        };
+
+        let field = |name: &str| match fields
+            .iter()
+            .find(|f| matches!(f, SupportedTypes::NamedValue(n, _) if n == name))
+        {
+            Some(SupportedTypes::NamedValue(_, value)) => (**value).clone(),
+            _ => panic!("Missing {}", name),
+        };
+
+        assert_eq!(
+            field("users"),
+            SupportedTypes::Table(
+                vec![
+                    "id".to_string(),
+                    "name".to_string(),
+                    "email".to_string(),
+                    "status".to_string()
+                ],
+                vec![
+                    vec![
+                        SupportedTypes::BigInt(1),
+                        SupportedTypes::String("Ann".to_string()),
+                        SupportedTypes::Null(None),
+                        SupportedTypes::String("Active".to_string()),
+                    ],
+                    vec![
+                        SupportedTypes::BigInt(2),
+                        SupportedTypes::String("Bob".to_string()),
+                        SupportedTypes::String("bob@example.com".to_string()),
+                        SupportedTypes::NamedValue(
+                            "Suspended".to_string(),
+                            Box::new(SupportedTypes::Array(vec![SupportedTypes::NamedValue(
+                                "days".to_string(),
+                                Box::new(SupportedTypes::Integer(3))
+                            )]))
+                        ),
+                    ],
+                ]
+            )
+        );
+
+        assert_eq!(
+            field("totals"),
+            SupportedTypes::Array(vec![
+                SupportedTypes::Integer(-1),
+                SupportedTypes::BigInt(1 << 40),
+                SupportedTypes::HugeInt(BigNum::from(u128::MAX)),
+            ])
+        );
+    }
+
+    #[test]
+    fn wide_integers_use_the_narrowest_visitor() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        #[serde(untagged)]
+        enum Number {
+            Unsigned(u64),
+            Signed(i64),
+        }
+
+        for value in [u64::MAX, 1 << 63] {
+            assert_eq!(
+                from_bytes::<u64>(&to_bytes(&value).unwrap()).unwrap(),
+                value
+            );
+            assert_eq!(
+                from_bytes::<Number>(&to_bytes(&value).unwrap()).unwrap(),
+                Number::Unsigned(value)
+            );
+        }
+
+        let huge = SupportedTypes::HugeInt(BigNum::from(i64::MIN));
+        assert_eq!(
+            from_value::<Number>(huge).unwrap(),
+            Number::Signed(i64::MIN)
+        );
+    }
+
+    #[test]
+    fn nested_options_are_lossy() {
+        let bytes = to_bytes(&Some(None::<u8>)).unwrap();
+
+        assert_eq!(bytes, to_bytes(&None::<u8>).unwrap());
+        assert_eq!(from_bytes::<Option<Option<u8>>>(&bytes).unwrap(), None);
+        assert_eq!(
+            from_bytes::<Option<()>>(&to_bytes(&Some(())).unwrap()).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn errors_carry_the_field_path() {
+        let value = SupportedTypes::Array(vec![SupportedTypes::NamedValue(
+            "users".to_string(),
+            Box::new(SupportedTypes::Array(vec![SupportedTypes::Array(vec![
+                SupportedTypes::NamedValue("id".to_string(), Box::new(SupportedTypes::BigInt(-5))),
+            ])])),
+        )]);
+
+        #[derive(Debug, Deserialize)]
+        struct Users {
+            #[allow(dead_code)]
+            users: Vec<User>,
+        }
+
+        match from_value::<Users>(value) {
+            Err(TypeError::Decoding(e)) => assert_eq!(
+                e.path,
+                vec![
+                    PathSegment::Value("users".to_string()),
+                    PathSegment::Index(0),
+                    PathSegment::Value("id".to_string()),
+                ]
+            ),
+            other => panic!("Unexpected {:?}", other),
+        }
+
+        assert!(matches!(
+            to_value(&BTreeMap::from([(1, 2)])),
+            Err(TypeError::Encoding(_))
+        ));
+    }
+}
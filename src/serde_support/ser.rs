@@ -0,0 +1,411 @@
+use std::fmt::Display;
+
+use ::serde::ser::{self, Serialize};
+
+use crate::{
+    commom::errors::{encoding_error, EncodingError, EncodingErrors, TypeError, TypeResult},
+    types::{BigNum, SupportedTypes},
+};
+
+impl ser::Error for TypeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        encoding_error(EncodingError::new(
+            "Rust value",
+            "SupportedTypes",
+            EncodingErrors::InvalidValue(msg.to_string()),
+        ))
+    }
+}
+
+/// Builds a [`SupportedTypes`] tree out of any `Serialize` value.
+pub struct Serializer;
+
+fn named(name: &str, value: SupportedTypes) -> SupportedTypes {
+    SupportedTypes::NamedValue(name.to_string(), Box::new(value))
+}
+
+// Rows of named fields that all share the same, non-repeating names.
+fn as_table(items: &[SupportedTypes]) -> Option<Vec<String>> {
+    let names = |item: &SupportedTypes| -> Option<Vec<String>> {
+        match item {
+            SupportedTypes::Array(fields) if !fields.is_empty() => fields
+                .iter()
+                .map(|field| match field {
+                    SupportedTypes::NamedValue(name, _) => Some(name.clone()),
+                    _ => None,
+                })
+                .collect(),
+            _ => None,
+        }
+    };
+
+    let headers = names(items.first()?)?;
+
+    if (1..headers.len()).any(|idx| headers[..idx].contains(&headers[idx])) {
+        return None;
+    }
+
+    items[1..]
+        .iter()
+        .all(|item| names(item).as_ref() == Some(&headers))
+        .then_some(headers)
+}
+
+pub struct SeqSerializer {
+    items: Vec<SupportedTypes>,
+    // Only sequences become Tables; tuples keep their Array.
+    table: bool,
+    variant: Option<&'static str>,
+}
+
+impl SeqSerializer {
+    fn push<T: ?Sized + Serialize>(&mut self, value: &T) -> TypeResult<()> {
+        self.items.push(value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> TypeResult<SupportedTypes> {
+        let value = match self.table.then(|| as_table(&self.items)).flatten() {
+            Some(headers) => SupportedTypes::Table(
+                headers,
+                self.items
+                    .into_iter()
+                    .map(|item| match item {
+                        SupportedTypes::Array(fields) => fields
+                            .into_iter()
+                            .map(|field| match field {
+                                SupportedTypes::NamedValue(_, value) => *value,
+                                other => other,
+                            })
+                            .collect(),
+                        other => vec![other],
+                    })
+                    .collect(),
+            ),
+            None => SupportedTypes::Array(self.items),
+        };
+
+        Ok(match self.variant {
+            Some(variant) => named(variant, value),
+            None => value,
+        })
+    }
+}
+
+pub struct MapSerializer {
+    fields: Vec<SupportedTypes>,
+    key: Option<String>,
+    variant: Option<&'static str>,
+}
+
+impl MapSerializer {
+    fn push<T: ?Sized + Serialize>(&mut self, name: &str, value: &T) -> TypeResult<()> {
+        self.fields.push(named(name, value.serialize(Serializer)?));
+        Ok(())
+    }
+
+    fn finish(self) -> TypeResult<SupportedTypes> {
+        let value = SupportedTypes::Array(self.fields);
+
+        Ok(match self.variant {
+            Some(variant) => named(variant, value),
+            None => value,
+        })
+    }
+}
+
+impl ser::Serializer for Serializer {
+    type Ok = SupportedTypes;
+    type Error = TypeError;
+    type SerializeSeq = SeqSerializer;
+    type SerializeTuple = SeqSerializer;
+    type SerializeTupleStruct = SeqSerializer;
+    type SerializeTupleVariant = SeqSerializer;
+    type SerializeMap = MapSerializer;
+    type SerializeStruct = MapSerializer;
+    type SerializeStructVariant = MapSerializer;
+
+    fn serialize_bool(self, v: bool) -> TypeResult<SupportedTypes> {
+        Ok(SupportedTypes::Boolean(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> TypeResult<SupportedTypes> {
+        Ok(SupportedTypes::Integer(v.into()))
+    }
+
+    fn serialize_i16(self, v: i16) -> TypeResult<SupportedTypes> {
+        Ok(SupportedTypes::Integer(v.into()))
+    }
+
+    fn serialize_i32(self, v: i32) -> TypeResult<SupportedTypes> {
+        Ok(SupportedTypes::Integer(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> TypeResult<SupportedTypes> {
+        Ok(SupportedTypes::BigInt(v))
+    }
+
+    fn serialize_i128(self, v: i128) -> TypeResult<SupportedTypes> {
+        Ok(SupportedTypes::HugeInt(BigNum::from(v)))
+    }
+
+    fn serialize_u8(self, v: u8) -> TypeResult<SupportedTypes> {
+        Ok(SupportedTypes::Integer(v.into()))
+    }
+
+    fn serialize_u16(self, v: u16) -> TypeResult<SupportedTypes> {
+        Ok(SupportedTypes::Integer(v.into()))
+    }
+
+    fn serialize_u32(self, v: u32) -> TypeResult<SupportedTypes> {
+        Ok(SupportedTypes::BigInt(v.into()))
+    }
+
+    // usize goes through here too, so values that fit stay BigInts.
+    fn serialize_u64(self, v: u64) -> TypeResult<SupportedTypes> {
+        match i64::try_from(v) {
+            Ok(v) => Ok(SupportedTypes::BigInt(v)),
+            Err(_) => Ok(SupportedTypes::HugeInt(BigNum::from(v))),
+        }
+    }
+
+    fn serialize_u128(self, v: u128) -> TypeResult<SupportedTypes> {
+        Ok(SupportedTypes::HugeInt(BigNum::from(v)))
+    }
+
+    fn serialize_f32(self, v: f32) -> TypeResult<SupportedTypes> {
+        Ok(SupportedTypes::Double(v.into()))
+    }
+
+    fn serialize_f64(self, v: f64) -> TypeResult<SupportedTypes> {
+        Ok(SupportedTypes::Double(v))
+    }
+
+    fn serialize_char(self, v: char) -> TypeResult<SupportedTypes> {
+        Ok(SupportedTypes::String(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> TypeResult<SupportedTypes> {
+        Ok(SupportedTypes::String(v.to_string()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> TypeResult<SupportedTypes> {
+        Ok(SupportedTypes::Bytes(v.to_vec()))
+    }
+
+    fn serialize_none(self) -> TypeResult<SupportedTypes> {
+        Ok(SupportedTypes::Null(None))
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> TypeResult<SupportedTypes> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> TypeResult<SupportedTypes> {
+        Ok(SupportedTypes::Null(None))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> TypeResult<SupportedTypes> {
+        Ok(SupportedTypes::Null(None))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> TypeResult<SupportedTypes> {
+        Ok(SupportedTypes::String(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> TypeResult<SupportedTypes> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> TypeResult<SupportedTypes> {
+        Ok(named(variant, value.serialize(self)?))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> TypeResult<SeqSerializer> {
+        Ok(SeqSerializer {
+            items: Vec::with_capacity(len.unwrap_or(0)),
+            table: true,
+            variant: None,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> TypeResult<SeqSerializer> {
+        Ok(SeqSerializer {
+            items: Vec::with_capacity(len),
+            table: false,
+            variant: None,
+        })
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> TypeResult<SeqSerializer> {
+        self.serialize_tuple(len)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> TypeResult<SeqSerializer> {
+        Ok(SeqSerializer {
+            items: Vec::with_capacity(len),
+            table: false,
+            variant: Some(variant),
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> TypeResult<MapSerializer> {
+        Ok(MapSerializer {
+            fields: Vec::with_capacity(len.unwrap_or(0)),
+            key: None,
+            variant: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> TypeResult<MapSerializer> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> TypeResult<MapSerializer> {
+        Ok(MapSerializer {
+            fields: Vec::with_capacity(len),
+            key: None,
+            variant: Some(variant),
+        })
+    }
+}
+
+impl ser::SerializeSeq for SeqSerializer {
+    type Ok = SupportedTypes;
+    type Error = TypeError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> TypeResult<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> TypeResult<SupportedTypes> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for SeqSerializer {
+    type Ok = SupportedTypes;
+    type Error = TypeError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> TypeResult<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> TypeResult<SupportedTypes> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for SeqSerializer {
+    type Ok = SupportedTypes;
+    type Error = TypeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> TypeResult<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> TypeResult<SupportedTypes> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for SeqSerializer {
+    type Ok = SupportedTypes;
+    type Error = TypeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> TypeResult<()> {
+        self.push(value)
+    }
+
+    fn end(self) -> TypeResult<SupportedTypes> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeMap for MapSerializer {
+    type Ok = SupportedTypes;
+    type Error = TypeError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> TypeResult<()> {
+        match key.serialize(Serializer)? {
+            SupportedTypes::String(key) => {
+                self.key = Some(key);
+                Ok(())
+            }
+            other => Err(ser::Error::custom(format!(
+                "Map keys become NamedValue names and must be strings. Found {}",
+                other.get_name()
+            ))),
+        }
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> TypeResult<()> {
+        let key = self.key.take().unwrap_or_default();
+        self.push(&key, value)
+    }
+
+    fn end(self) -> TypeResult<SupportedTypes> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for MapSerializer {
+    type Ok = SupportedTypes;
+    type Error = TypeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> TypeResult<()> {
+        self.push(key, value)
+    }
+
+    fn end(self) -> TypeResult<SupportedTypes> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for MapSerializer {
+    type Ok = SupportedTypes;
+    type Error = TypeError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> TypeResult<()> {
+        self.push(key, value)
+    }
+
+    fn end(self) -> TypeResult<SupportedTypes> {
+        self.finish()
+    }
+}
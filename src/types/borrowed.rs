@@ -0,0 +1,711 @@
+use std::borrow::Cow;
+
+use crate::commom::delimiters::{END_RECORD, ESCAPE, START_RECORD, VALUE_DELIMITER};
+use crate::commom::diagnostics::{emit, Event};
+use crate::commom::errors::{
+    decoding_error, DecodingError, DecodingErrors, PathSegment, TypeResult,
+};
+use crate::commom::{
+    decode_limits::check_limit, is_known_first_byte, known_first_bytes, slice_records,
+    unescape_bytes, Codec, DecodeLimits,
+};
+use crate::types::{
+    infer_buffer_with, Array, BigNum, Bytes, Command, NamedValue, SupportedTypes, Table,
+};
+
+/// Borrowed counterpart of [`SupportedTypes`]. Strings and byte payloads point
+/// into the decoded buffer unless they had to be unescaped.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValueRef<'a> {
+    BigInt(i64),
+    Boolean(bool),
+    Double(f64),
+    Integer(i32),
+    Null(Option<()>),
+    String(Cow<'a, str>),
+    HugeInt(BigNum),
+    Bytes(Cow<'a, [u8]>),
+    Array(Vec<ValueRef<'a>>),
+    NamedValue(Cow<'a, str>, Box<ValueRef<'a>>),
+    Table(Vec<Cow<'a, str>>, Vec<Vec<ValueRef<'a>>>),
+    Command(Cow<'a, str>, Vec<Cow<'a, str>>),
+    Extension(u8, Box<ValueRef<'a>>),
+}
+
+impl ValueRef<'_> {
+    pub fn into_owned(self) -> SupportedTypes {
+        match self {
+            ValueRef::BigInt(v) => SupportedTypes::BigInt(v),
+            ValueRef::Boolean(v) => SupportedTypes::Boolean(v),
+            ValueRef::Double(v) => SupportedTypes::Double(v),
+            ValueRef::Integer(v) => SupportedTypes::Integer(v),
+            ValueRef::Null(v) => SupportedTypes::Null(v),
+            ValueRef::String(v) => SupportedTypes::String(v.into_owned()),
+            ValueRef::HugeInt(v) => SupportedTypes::HugeInt(v),
+            ValueRef::Bytes(v) => SupportedTypes::Bytes(v.into_owned()),
+            ValueRef::Array(values) => {
+                SupportedTypes::Array(values.into_iter().map(ValueRef::into_owned).collect())
+            }
+            ValueRef::NamedValue(name, value) => {
+                SupportedTypes::NamedValue(name.into_owned(), Box::new(value.into_owned()))
+            }
+            ValueRef::Table(headers, rows) => SupportedTypes::Table(
+                headers.into_iter().map(Cow::into_owned).collect(),
+                rows.into_iter()
+                    .map(|row| row.into_iter().map(ValueRef::into_owned).collect())
+                    .collect(),
+            ),
+            ValueRef::Command(name, args) => SupportedTypes::Command(
+                name.into_owned(),
+                args.into_iter().map(Cow::into_owned).collect(),
+            ),
+            ValueRef::Extension(first_byte, value) => {
+                SupportedTypes::Extension(first_byte, Box::new(value.into_owned()))
+            }
+        }
+    }
+}
+
+impl From<SupportedTypes> for ValueRef<'static> {
+    fn from(value: SupportedTypes) -> Self {
+        match value {
+            SupportedTypes::BigInt(v) => ValueRef::BigInt(v),
+            SupportedTypes::Boolean(v) => ValueRef::Boolean(v),
+            SupportedTypes::Double(v) => ValueRef::Double(v),
+            SupportedTypes::Integer(v) => ValueRef::Integer(v),
+            SupportedTypes::Null(v) => ValueRef::Null(v),
+            SupportedTypes::String(v) => ValueRef::String(Cow::Owned(v)),
+            SupportedTypes::HugeInt(v) => ValueRef::HugeInt(v),
+            SupportedTypes::Bytes(v) => ValueRef::Bytes(Cow::Owned(v)),
+            SupportedTypes::Array(values) => {
+                ValueRef::Array(values.into_iter().map(ValueRef::from).collect())
+            }
+            SupportedTypes::NamedValue(name, value) => {
+                ValueRef::NamedValue(Cow::Owned(name), Box::new(ValueRef::from(*value)))
+            }
+            SupportedTypes::Table(headers, rows) => ValueRef::Table(
+                headers.into_iter().map(Cow::Owned).collect(),
+                rows.into_iter()
+                    .map(|row| row.into_iter().map(ValueRef::from).collect())
+                    .collect(),
+            ),
+            SupportedTypes::Command(name, args) => {
+                ValueRef::Command(Cow::Owned(name), args.into_iter().map(Cow::Owned).collect())
+            }
+            SupportedTypes::Extension(first_byte, value) => {
+                ValueRef::Extension(first_byte, Box::new(ValueRef::from(*value)))
+            }
+        }
+    }
+}
+
+/// Position of `child` inside `parent`. Every slice handled here borrows from
+/// the buffer being decoded, so the pointers share an allocation.
+fn offset_in(parent: &[u8], child: &[u8]) -> usize {
+    (child.as_ptr() as usize).saturating_sub(parent.as_ptr() as usize)
+}
+
+fn records<'a>(parent: &[u8], buff: &'a [u8]) -> TypeResult<Vec<&'a [u8]>> {
+    match slice_records(buff) {
+        Ok(ranges) => Ok(ranges.into_iter().map(|range| &buff[range]).collect()),
+        Err(e) => Err(decoding_error(e.inside(offset_in(parent, buff)))),
+    }
+}
+
+fn nested_error(
+    e: crate::commom::errors::TypeError,
+    parent: &[u8],
+    child: &[u8],
+    segment: PathSegment,
+) -> crate::commom::errors::TypeError {
+    e.inside(offset_in(parent, child)).under(segment)
+}
+
+fn values(buff: &[u8]) -> Vec<&[u8]> {
+    let mut values: Vec<&[u8]> = vec![];
+
+    let mut depth: usize = 0;
+    let mut start: usize = 0;
+
+    for (idx, byte) in buff.iter().enumerate() {
+        match *byte {
+            START_RECORD => depth += 1,
+            END_RECORD => depth = depth.saturating_sub(1),
+            VALUE_DELIMITER if depth == 0 => {
+                values.push(&buff[start..idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+
+    values
+}
+
+fn error(buff: &[u8], to: &str, cause: DecodingErrors) -> crate::commom::errors::TypeError {
+    decoding_error(DecodingError::new(buff.to_vec(), to, cause))
+}
+
+fn single_record<'a>(buff: &'a [u8], to: &str) -> TypeResult<&'a [u8]> {
+    let records = records(buff, &buff[1..])?;
+
+    match records.as_slice() {
+        [record] => Ok(record),
+        [] => Err(error(
+            buff,
+            to,
+            DecodingErrors::NotEnough("Records".to_string(), 1, 0),
+        )),
+        _ => Err(error(
+            buff,
+            to,
+            DecodingErrors::TooMuch(
+                "Records".to_string(),
+                1,
+                records.len().try_into().unwrap_or(u32::MAX),
+            ),
+        )),
+    }
+}
+
+fn limit(buff: &[u8], to: &str, what: &str, limit: usize, found: usize) -> TypeResult<()> {
+    check_limit(buff, to, what, limit, found).map_err(decoding_error)
+}
+
+// The one record of a String, Bytes or composite, once the checks they all
+// share have passed.
+fn body<'a>(
+    buff: &'a [u8],
+    to: &str,
+    first_byte: u8,
+    limits: &DecodeLimits,
+) -> TypeResult<&'a [u8]> {
+    limit(
+        buff,
+        to,
+        "Message size",
+        limits.max_message_size,
+        buff.len(),
+    )?;
+
+    match buff.first() {
+        None => Err(error(
+            buff,
+            to,
+            DecodingErrors::NotEnough("Bytes".to_string(), 1, 0),
+        )),
+        Some(fb) if *fb != first_byte => Err(decoding_error(
+            DecodingError::new(
+                buff.to_vec(),
+                to,
+                DecodingErrors::FirstByteError(to.to_string(), first_byte, *fb),
+            )
+            .at(0),
+        )),
+        Some(_) => single_record(buff, to),
+    }
+}
+
+fn payload<'a>(
+    record: &'a [u8],
+    buff: &[u8],
+    to: &str,
+    limits: &DecodeLimits,
+) -> TypeResult<Cow<'a, [u8]>> {
+    let payload = match record.contains(&ESCAPE) {
+        true => match unescape_bytes(record.to_vec()) {
+            Ok(unescaped) => Cow::Owned(unescaped),
+            Err(e) => return Err(decoding_error(e.inside(offset_in(buff, record)))),
+        },
+        false => Cow::Borrowed(record),
+    };
+
+    limit(
+        buff,
+        to,
+        &format!("{} length", to),
+        limits.max_string_length,
+        payload.len(),
+    )?;
+
+    Ok(payload)
+}
+
+// A nested value that has to be a String, like Table headers and Command
+// arguments. A mismatch is reported by `to`, the composite holding it.
+fn string_value<'a>(buff: &'a [u8], to: &str, limits: &DecodeLimits) -> TypeResult<Cow<'a, str>> {
+    match infer_borrowed_with(buff, limits)? {
+        ValueRef::String(string) => Ok(string),
+        other => Err(decoding_error(
+            DecodingError::new(
+                buff.to_vec(),
+                to,
+                DecodingErrors::InvalidTypeInCompositeType(
+                    other.into_owned().get_name().to_string(),
+                    "String".to_string(),
+                ),
+            )
+            .at(0),
+        )),
+    }
+}
+
+fn decode_string<'a>(buff: &'a [u8], limits: &DecodeLimits) -> TypeResult<Cow<'a, str>> {
+    let record = body(buff, "String", crate::types::String::FIRST_BYTE, limits)?;
+
+    match payload(record, buff, "String", limits)? {
+        Cow::Borrowed(bytes) => match std::str::from_utf8(bytes) {
+            Ok(str) => Ok(Cow::Borrowed(str)),
+            Err(e) => Err(error(
+                buff,
+                "String",
+                DecodingErrors::InternalError(Box::new(e)),
+            )),
+        },
+        Cow::Owned(bytes) => match std::string::String::from_utf8(bytes) {
+            Ok(string) => Ok(Cow::Owned(string)),
+            Err(e) => Err(error(
+                buff,
+                "String",
+                DecodingErrors::InternalError(Box::new(e)),
+            )),
+        },
+    }
+}
+
+fn decode_bytes<'a>(buff: &'a [u8], limits: &DecodeLimits) -> TypeResult<Cow<'a, [u8]>> {
+    let record = body(buff, "Bytes", Bytes::FIRST_BYTE, limits)?;
+
+    payload(record, buff, "Bytes", limits)
+}
+
+fn decode_array<'a>(buff: &'a [u8], limits: &DecodeLimits) -> TypeResult<Vec<ValueRef<'a>>> {
+    let element_records = records(buff, body(buff, "Array", Array::FIRST_BYTE, limits)?)?;
+
+    limit(
+        buff,
+        "Array",
+        "Array elements",
+        limits.max_elements,
+        element_records.len(),
+    )?;
+
+    let nested = limits.nested(buff, "Array").map_err(decoding_error)?;
+
+    let mut array: Vec<ValueRef> = vec![];
+
+    for record in element_records {
+        if record.is_empty() {
+            continue;
+        }
+
+        let value = infer_borrowed_with(record, &nested)
+            .map_err(|e| nested_error(e, buff, record, PathSegment::Index(array.len())))?;
+
+        array.push(value);
+    }
+
+    Ok(array)
+}
+
+fn decode_named_value<'a>(
+    buff: &'a [u8],
+    limits: &DecodeLimits,
+) -> TypeResult<(Cow<'a, str>, ValueRef<'a>)> {
+    let values = values(body(buff, "NamedValue", NamedValue::FIRST_BYTE, limits)?);
+
+    let (name, value) = match values.as_slice() {
+        [name, value, ..] => (name, value),
+        _ => {
+            return Err(error(
+                buff,
+                "NamedValue",
+                DecodingErrors::NotEnough(
+                    "Values".to_string(),
+                    2,
+                    values.len().try_into().unwrap_or(u32::MAX),
+                ),
+            ))
+        }
+    };
+
+    let name =
+        decode_string(name, limits).map_err(|e| nested_error(e, buff, name, PathSegment::Name))?;
+
+    let nested = limits.nested(buff, "NamedValue").map_err(decoding_error)?;
+
+    let inner = infer_borrowed_with(value, &nested)
+        .map_err(|e| nested_error(e, buff, value, PathSegment::Value(name.to_string())))?;
+
+    Ok((name, inner))
+}
+
+type TableRef<'a> = (Vec<Cow<'a, str>>, Vec<Vec<ValueRef<'a>>>);
+
+fn decode_table<'a>(buff: &'a [u8], limits: &DecodeLimits) -> TypeResult<TableRef<'a>> {
+    let table_records = records(buff, body(buff, "Table", Table::FIRST_BYTE, limits)?)?;
+
+    // The headers and the Null marker come before the rows.
+    limit(
+        buff,
+        "Table",
+        "Table rows",
+        limits.max_table_rows,
+        table_records.len().saturating_sub(2),
+    )?;
+
+    let nested = limits.nested(buff, "Table").map_err(decoding_error)?;
+
+    let (headers_record, marker, row_records) = match table_records.as_slice() {
+        [headers_record, marker, row_records @ ..] => (headers_record, marker, row_records),
+        _ => {
+            return Err(error(
+                buff,
+                "Table",
+                DecodingErrors::NotEnough(
+                    "Records".to_string(),
+                    2,
+                    table_records.len().try_into().unwrap_or(u32::MAX),
+                ),
+            ))
+        }
+    };
+
+    let header_records = records(buff, headers_record)?;
+
+    limit(
+        buff,
+        "Table",
+        "Table columns",
+        limits.max_elements,
+        header_records.len(),
+    )?;
+
+    let mut headers: Vec<Cow<str>> = vec![];
+
+    for (idx, header) in header_records.into_iter().enumerate() {
+        headers.push(
+            string_value(header, "Table", &nested)
+                .map_err(|e| nested_error(e, buff, header, PathSegment::Header(idx)))?,
+        );
+    }
+
+    match infer_borrowed_with(marker, &nested).map_err(|e| e.inside(offset_in(buff, marker)))? {
+        ValueRef::Null(_) => {}
+        other => {
+            return Err(decoding_error(
+                DecodingError::new(
+                    buff.to_vec(),
+                    "Table",
+                    DecodingErrors::InvalidTypeInCompositeType(
+                        other.into_owned().get_name().to_string(),
+                        "Null".to_string(),
+                    ),
+                )
+                .at(offset_in(buff, marker)),
+            ))
+        }
+    }
+
+    let mut rows: Vec<Vec<ValueRef>> = vec![];
+
+    for (row_idx, row_record) in row_records.iter().enumerate() {
+        let cell_records = records(buff, row_record)?;
+
+        // Checked before decoding, so an oversized row costs no allocations.
+        if cell_records.len() != headers.len() {
+            return Err(decoding_error(
+                DecodingError::new(
+                    buff.to_vec(),
+                    "Table",
+                    DecodingErrors::CantFitValues(format!(
+                        "The table has {} values. But a row was found to have {} values.",
+                        headers.len(),
+                        cell_records.len()
+                    )),
+                )
+                .at(offset_in(buff, row_record)),
+            ));
+        }
+
+        let mut row: Vec<ValueRef> = vec![];
+
+        for (column, cell) in headers.iter().zip(cell_records) {
+            let value = infer_borrowed_with(cell, &nested).map_err(|e| {
+                nested_error(
+                    e,
+                    buff,
+                    cell,
+                    PathSegment::Cell {
+                        row: row_idx,
+                        column: column.to_string(),
+                    },
+                )
+            })?;
+
+            row.push(value);
+        }
+
+        rows.push(row);
+    }
+
+    Ok((headers, rows))
+}
+
+fn decode_command<'a>(
+    buff: &'a [u8],
+    limits: &DecodeLimits,
+) -> TypeResult<(Cow<'a, str>, Vec<Cow<'a, str>>)> {
+    let values = values(body(buff, "Command", Command::FIRST_BYTE, limits)?);
+
+    let (name, args) = match values.split_first() {
+        Some(parts) => parts,
+        None => {
+            return Err(error(
+                buff,
+                "Command",
+                DecodingErrors::NotEnough("Values".to_string(), 1, 0),
+            ))
+        }
+    };
+
+    let name =
+        decode_string(name, limits).map_err(|e| nested_error(e, buff, name, PathSegment::Name))?;
+
+    limit(
+        buff,
+        "Command",
+        "Command arguments",
+        limits.max_elements,
+        args.len(),
+    )?;
+
+    let nested = limits.nested(buff, "Command").map_err(decoding_error)?;
+
+    let mut coproto_args: Vec<Cow<str>> = vec![];
+
+    for (idx, arg) in args.iter().enumerate() {
+        coproto_args.push(
+            string_value(arg, "Command", &nested)
+                .map_err(|e| nested_error(e, buff, arg, PathSegment::Argument(idx)))?,
+        );
+    }
+
+    Ok((name, coproto_args))
+}
+
+// Errors raised below the outermost value keep the bytes they came from and
+// only have their offsets rebased on the way up. The outermost call points
+// them at the whole message, so it is copied once whatever the depth.
+fn anchored<T>(result: TypeResult<T>, buff: &[u8], limits: &DecodeLimits) -> TypeResult<T> {
+    match result {
+        Err(e) if limits.is_outermost() => Err(e.within(buff)),
+        other => other,
+    }
+}
+
+pub(crate) fn string_ref<'a>(buff: &'a [u8], limits: &DecodeLimits) -> TypeResult<Cow<'a, str>> {
+    anchored(decode_string(buff, limits), buff, limits)
+}
+
+pub(crate) fn bytes_ref<'a>(buff: &'a [u8], limits: &DecodeLimits) -> TypeResult<Cow<'a, [u8]>> {
+    anchored(decode_bytes(buff, limits), buff, limits)
+}
+
+pub(crate) fn array_ref<'a>(
+    buff: &'a [u8],
+    limits: &DecodeLimits,
+) -> TypeResult<Vec<ValueRef<'a>>> {
+    anchored(decode_array(buff, limits), buff, limits)
+}
+
+pub(crate) fn named_value_ref<'a>(
+    buff: &'a [u8],
+    limits: &DecodeLimits,
+) -> TypeResult<(Cow<'a, str>, ValueRef<'a>)> {
+    anchored(decode_named_value(buff, limits), buff, limits)
+}
+
+pub(crate) fn table_ref<'a>(buff: &'a [u8], limits: &DecodeLimits) -> TypeResult<TableRef<'a>> {
+    anchored(decode_table(buff, limits), buff, limits)
+}
+
+pub(crate) fn command_ref<'a>(
+    buff: &'a [u8],
+    limits: &DecodeLimits,
+) -> TypeResult<(Cow<'a, str>, Vec<Cow<'a, str>>)> {
+    anchored(decode_command(buff, limits), buff, limits)
+}
+
+/// Decodes `buff` without copying string and byte payloads. Numbers, booleans
+/// and nulls are small and go through their regular decoders.
+pub fn infer_borrowed(buff: &[u8]) -> TypeResult<ValueRef<'_>> {
+    infer_borrowed_with(buff, &DecodeLimits::default())
+}
+
+/// The owned decoders of String, Bytes and the composites are built on this,
+/// so they share its walk over the records.
+pub fn infer_borrowed_with<'a>(buff: &'a [u8], limits: &DecodeLimits) -> TypeResult<ValueRef<'a>> {
+    let first_byte = match buff.first() {
+        Some(fb) => *fb,
+        None => {
+            return Err(error(
+                buff,
+                "Infer",
+                DecodingErrors::CouldNotFind(0, "First byte".to_string()),
+            ))
+        }
+    };
+
+    // Primitives are reported by `infer_buffer_with` when they fall through.
+    if matches!(
+        first_byte,
+        crate::types::String::FIRST_BYTE
+            | Bytes::FIRST_BYTE
+            | Array::FIRST_BYTE
+            | NamedValue::FIRST_BYTE
+            | Table::FIRST_BYTE
+            | Command::FIRST_BYTE
+    ) {
+        emit(Event::TypeInferred { first_byte });
+    }
+
+    match first_byte {
+        crate::types::String::FIRST_BYTE => Ok(ValueRef::String(string_ref(buff, limits)?)),
+        Bytes::FIRST_BYTE => Ok(ValueRef::Bytes(bytes_ref(buff, limits)?)),
+        Array::FIRST_BYTE => Ok(ValueRef::Array(array_ref(buff, limits)?)),
+        NamedValue::FIRST_BYTE => {
+            let (name, value) = named_value_ref(buff, limits)?;
+            Ok(ValueRef::NamedValue(name, Box::new(value)))
+        }
+        Table::FIRST_BYTE => {
+            let (headers, rows) = table_ref(buff, limits)?;
+            Ok(ValueRef::Table(headers, rows))
+        }
+        Command::FIRST_BYTE => {
+            let (name, args) = command_ref(buff, limits)?;
+            Ok(ValueRef::Command(name, args))
+        }
+        fb if is_known_first_byte(fb) => Ok(ValueRef::from(infer_buffer_with(buff, limits)?)),
+        _ => Err(decoding_error(
+            DecodingError::new(
+                buff.to_vec(),
+                "Infer",
+                DecodingErrors::UnknownFirstByte(first_byte, known_first_bytes()),
+            )
+            .at(0),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use crate::{
+        commom::DecodeLimits,
+        types::{
+            encode_value, infer_borrowed, infer_borrowed_with, BigNum, SupportedTypes, ValueRef,
+        },
+    };
+
+    #[test]
+    fn matches_owned_decoding() {
+        let values = vec![
+            SupportedTypes::Integer(i32::MIN),
+            SupportedTypes::HugeInt(BigNum::from(u128::MAX)),
+            SupportedTypes::String("escaped \u{1c}".to_string()),
+            SupportedTypes::Bytes(vec![0, 1, 2, 0x1f]),
+            SupportedTypes::Array(vec![
+                SupportedTypes::Double(0.1),
+                SupportedTypes::Array(vec![SupportedTypes::Null(None)]),
+            ]),
+            SupportedTypes::NamedValue(
+                "inner".to_string(),
+                Box::new(SupportedTypes::Boolean(false)),
+            ),
+            SupportedTypes::Table(
+                vec!["id".to_string(), "name".to_string()],
+                vec![
+                    vec![
+                        SupportedTypes::BigInt(1),
+                        SupportedTypes::String("one".to_string()),
+                    ],
+                    vec![
+                        SupportedTypes::BigInt(2),
+                        SupportedTypes::String("two".to_string()),
+                    ],
+                ],
+            ),
+            SupportedTypes::Command("SET".to_string(), vec!["k".to_string(), "v".to_string()]),
+        ];
+
+        for value in values {
+            let buff = encode_value(value.clone()).unwrap();
+
+            assert_eq!(infer_borrowed(&buff).unwrap().into_owned(), value);
+        }
+    }
+
+    #[test]
+    fn borrows_from_the_input() {
+        let buff = encode_value(SupportedTypes::Table(
+            vec!["name".to_string()],
+            vec![vec![SupportedTypes::String("plain".to_string())]],
+        ))
+        .unwrap();
+
+        let decoded = infer_borrowed(&buff).unwrap();
+
+        let input = buff.as_ptr_range();
+
+        match decoded {
+            ValueRef::Table(headers, rows) => {
+                assert!(matches!(headers[0], Cow::Borrowed(_)));
+
+                match &rows[0][0] {
+                    ValueRef::String(Cow::Borrowed(str)) => {
+                        assert!(input.contains(&str.as_ptr()))
+                    }
+                    other => panic!("Expected a borrowed string, got {:?}", other),
+                }
+            }
+            other => panic!("Expected a table, got {:?}", other),
+        }
+
+        let escaped = encode_value(SupportedTypes::String("\u{1e}".to_string())).unwrap();
+
+        assert_eq!(
+            infer_borrowed(&escaped).unwrap(),
+            ValueRef::String(Cow::Owned("\u{1e}".to_string()))
+        );
+    }
+
+    #[test]
+    fn wrong_buffer() {
+        assert!(infer_borrowed(&[]).is_err());
+        assert!(infer_borrowed(b"?").is_err());
+    }
+
+    #[test]
+    fn limits_apply_to_borrowed_decoding() {
+        let nested = encode_value(SupportedTypes::NamedValue(
+            "outer".to_string(),
+            Box::new(SupportedTypes::Array(vec![SupportedTypes::String(
+                "inner".to_string(),
+            )])),
+        ))
+        .unwrap();
+
+        let mut limits = DecodeLimits::new();
+        limits.max_depth = 2;
+        assert!(infer_borrowed_with(&nested, &limits).is_ok());
+
+        limits.max_depth = 1;
+        assert!(infer_borrowed_with(&nested, &limits).is_err());
+
+        let mut limits = DecodeLimits::new();
+        limits.max_string_length = 4;
+        assert!(infer_borrowed_with(&nested, &limits).is_err());
+    }
+}
@@ -1,125 +1,42 @@
+use std::io::Write;
+
 use crate::{
     commom::{
-        delimiters::{BUFFER_END, END_RECORD, START_RECORD, VALUE_DELIMITER},
-        errors::{decoding_error, DecodingError, DecodingErrors, TypeResult},
-        join_parts, slice_records, BuffPart, CoprotoType, Uint8Buff, ValueOrBuffer,
+        delimiters::{END_RECORD, START_RECORD, VALUE_DELIMITER},
+        errors::TypeResult,
+        write_bytes, Codec, DecodeLimits,
+    },
+    types::{
+        borrowed::{array_ref, ValueRef},
+        encode_value::write_nested_value,
+        SupportedTypes,
     },
-    types::{infer_buffer, primitive, BigInt, Boolean, Double, Integer, Null, SupportedTypes},
 };
 
 #[derive(Debug)]
-pub struct Array {
-    pub first_byte: u8,
-    pub modifier_byte: Option<u8>,
-    pub modifier_char: Option<char>,
-    pub first_char: char,
-    pub value_of: TypeResult<Vec<SupportedTypes>>,
-    pub buff: TypeResult<Uint8Buff>,
-}
+pub struct Array;
 
-impl CoprotoType<Vec<SupportedTypes>> for Array {
+impl Codec for Array {
+    type Value = Vec<SupportedTypes>;
     const FIRST_BYTE: u8 = b'[';
 
-    fn new(value: ValueOrBuffer<Vec<SupportedTypes>>) -> Self {
-        match value {
-            ValueOrBuffer::Value(value) => Self {
-                first_byte: Self::FIRST_BYTE,
-                modifier_byte: None,
-                modifier_char: None,
-                first_char: '[',
-                value_of: Ok(value.clone()),
-                buff: Self::encode(value),
-            },
-            ValueOrBuffer::Buffer(vec) => Self {
-                first_byte: Self::FIRST_BYTE,
-                modifier_byte: None,
-                modifier_char: None,
-                first_char: '[',
-                value_of: Self::decode(vec.clone()),
-                buff: Ok(vec),
-            },
-        }
-    }
-
-    fn encode(values: Vec<SupportedTypes>) -> TypeResult<Uint8Buff> {
-        let mut parts: Vec<BuffPart> = vec![BuffPart::Val(b'['), BuffPart::Val(START_RECORD)];
+    fn write_value<W: Write>(values: &Vec<SupportedTypes>, writer: &mut W) -> TypeResult<()> {
+        write_bytes(writer, &[b'[', START_RECORD])?;
 
         for value in values.iter() {
-            let encoded_value = match value {
-                SupportedTypes::BigInt(bi) => {
-                    let mut bi_encoded = BigInt::encode(*bi)?;
-                    bi_encoded.pop();
-                    BuffPart::Arr(bi_encoded)
-                }
-                SupportedTypes::Boolean(bol) => {
-                    let mut bol_encoded = Boolean::encode(*bol)?;
-                    bol_encoded.pop();
-                    BuffPart::Arr(bol_encoded)
-                }
-                SupportedTypes::Double(db) => {
-                    let mut db_encoded = Double::encode(*db)?;
-                    db_encoded.pop();
-                    BuffPart::Arr(db_encoded)
-                }
-                SupportedTypes::Integer(int) => {
-                    let mut int_encoded = Integer::encode(*int)?;
-                    int_encoded.pop();
-                    BuffPart::Arr(int_encoded)
-                }
-                SupportedTypes::Null(null_val) => {
-                    let mut null_encoded = Null::encode(*null_val)?;
-                    null_encoded.pop();
-                    BuffPart::Arr(null_encoded)
-                }
-                SupportedTypes::String(string) => {
-                    let mut string_encoded = primitive::String::encode(string.clone())?;
-                    string_encoded.pop();
-                    BuffPart::Arr(string_encoded)
-                }
-            };
-
-            parts.push(BuffPart::Val(START_RECORD));
-            parts.push(encoded_value);
-            parts.push(BuffPart::Val(END_RECORD));
-            parts.push(BuffPart::Val(VALUE_DELIMITER));
+            write_bytes(writer, &[START_RECORD])?;
+            write_nested_value(value, writer)?;
+            write_bytes(writer, &[END_RECORD, VALUE_DELIMITER])?;
         }
 
-        parts.push(BuffPart::Val(END_RECORD));
-        parts.push(BuffPart::Val(BUFFER_END));
-
-        Ok(join_parts(parts))
+        write_bytes(writer, &[END_RECORD])
     }
 
-    fn decode(value: Uint8Buff) -> TypeResult<Vec<SupportedTypes>> {
-        let mut array: Vec<SupportedTypes> = vec![];
-
-        let mut m_value = value.clone();
-
-        let first_byte = m_value.remove(0);
-
-        if first_byte != Self::FIRST_BYTE {
-            return Err(decoding_error(DecodingError {
-                from: value,
-                to: "Array".to_string(),
-                cause: DecodingErrors::FirstByteError(
-                    "Array".to_string(),
-                    Self::FIRST_BYTE,
-                    first_byte,
-                ),
-            }));
-        };
-
-        let records = slice_records(m_value.clone());
-
-        for record in records.iter() {
-            if record.is_empty() {
-                continue;
-            }
-
-            array.push(infer_buffer(record.to_vec())?);
-        }
-
-        Ok(array)
+    fn decode_with(value: &[u8], limits: &DecodeLimits) -> TypeResult<Vec<SupportedTypes>> {
+        Ok(array_ref(value, limits)?
+            .into_iter()
+            .map(ValueRef::into_owned)
+            .collect())
     }
 }
 
@@ -127,10 +44,11 @@ impl CoprotoType<Vec<SupportedTypes>> for Array {
 mod tests {
     use crate::{
         commom::{
-            delimiters::{BUFFER_END, END_RECORD, START_RECORD},
-            CoprotoType, ValueOrBuffer,
+            delimiters::{BUFFER_END, END_RECORD, START_RECORD, VALUE_DELIMITER},
+            errors::{DecodingErrors, PathSegment, TypeError},
+            Codec, DecodeLimits, Lazy, Uint8Buff, ValueOrBuffer,
         },
-        types::{Array, SupportedTypes},
+        types::{infer_buffer, Array, SupportedTypes},
     };
 
     #[test]
@@ -144,46 +62,160 @@ mod tests {
             SupportedTypes::String("Hello, fellow rustacean!".to_string()),
         ];
 
-        let filled_encoding = Array::new(ValueOrBuffer::Value(vec_to_test.clone()));
+        let filled_encoding = Lazy::<Array>::new(ValueOrBuffer::Value(vec_to_test.clone()));
 
-        let filled_buff = filled_encoding.buff.unwrap();
-        let filled_decoding = Array::new(ValueOrBuffer::Buffer(filled_buff));
+        let filled_buff = filled_encoding.buff().unwrap().to_vec();
+        let filled_decoding = Lazy::<Array>::new(ValueOrBuffer::Buffer(filled_buff));
 
         assert_eq!(
-            filled_decoding.value_of.unwrap(),
-            filled_encoding.value_of.unwrap()
+            *filled_decoding.value().unwrap(),
+            *filled_encoding.value().unwrap()
         );
     }
 
     #[test]
     fn test_empty_encoding_decoding() {
-        let empty_encoding = Array::new(ValueOrBuffer::Value(vec![]));
-        let empty_buff = empty_encoding.buff.unwrap();
-        let empty_decoding = Array::new(ValueOrBuffer::Buffer(empty_buff));
+        let empty_encoding = Lazy::<Array>::new(ValueOrBuffer::Value(vec![]));
+        let empty_buff = empty_encoding.buff().unwrap().to_vec();
+        let empty_decoding = Lazy::<Array>::new(ValueOrBuffer::Buffer(empty_buff));
 
         assert_eq!(
-            empty_decoding.value_of.unwrap(),
-            empty_encoding.value_of.unwrap()
+            *empty_decoding.value().unwrap(),
+            *empty_encoding.value().unwrap()
         );
     }
 
+    #[test]
+    fn test_nested_encoding_decoding() {
+        let vec_to_test = vec![
+            SupportedTypes::Integer(1),
+            SupportedTypes::Array(vec![
+                SupportedTypes::String("inner".to_string()),
+                SupportedTypes::Array(vec![
+                    SupportedTypes::Double(2.5),
+                    SupportedTypes::Array(vec![]),
+                ]),
+            ]),
+            SupportedTypes::NamedValue(
+                "named".to_string(),
+                Box::new(SupportedTypes::Array(vec![SupportedTypes::Boolean(true)])),
+            ),
+            SupportedTypes::Table(
+                vec!["id".to_string(), "tags".to_string()],
+                vec![vec![
+                    SupportedTypes::Integer(7),
+                    SupportedTypes::Array(vec![SupportedTypes::String("a".to_string())]),
+                ]],
+            ),
+            SupportedTypes::Command("PING".to_string(), vec!["now".to_string()]),
+        ];
+
+        let nested_encoding = Lazy::<Array>::new(ValueOrBuffer::Value(vec_to_test.clone()));
+        let nested_buff = nested_encoding.buff().unwrap().to_vec();
+        let nested_decoding = Lazy::<Array>::new(ValueOrBuffer::Buffer(nested_buff));
+
+        assert_eq!(*nested_decoding.value().unwrap(), vec_to_test);
+    }
+
     #[test]
     fn wrong_buffer() {
         let buff = vec![b'?', START_RECORD, 0, END_RECORD, BUFFER_END];
 
-        let wrong = Array::new(ValueOrBuffer::Buffer(buff));
+        let wrong = Lazy::<Array>::new(ValueOrBuffer::Buffer(buff));
 
-        match wrong.value_of {
+        match wrong.value() {
             Ok(_) => false,
             Err(e) => match e {
                 crate::commom::errors::TypeError::Encoding(_) => false,
                 crate::commom::errors::TypeError::Decoding(decoding_error) => {
                     matches!(
-                        decoding_error.cause,
+                        *decoding_error.cause,
                         crate::commom::errors::DecodingErrors::FirstByteError(_, _, _)
                     )
                 }
             },
         };
     }
+
+    fn nested_arrays(depth: usize) -> Uint8Buff {
+        let mut buff: Uint8Buff = vec![];
+
+        for _ in 0..depth {
+            buff.extend([b'[', START_RECORD, START_RECORD]);
+        }
+
+        buff.extend([b'[', START_RECORD, END_RECORD]);
+
+        for _ in 0..depth {
+            buff.extend([END_RECORD, VALUE_DELIMITER, END_RECORD]);
+        }
+
+        buff.push(BUFFER_END);
+        buff
+    }
+
+    fn exceeded(result: Result<Vec<SupportedTypes>, TypeError>) -> (String, usize, usize) {
+        match result {
+            Err(TypeError::Decoding(e)) => match *e.cause {
+                DecodingErrors::LimitExceeded { what, limit, found } => (what, limit, found),
+                other => panic!("Unexpected cause {:?}", other),
+            },
+            other => panic!("Unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn element_limit() {
+        let buff = Array::encode(&vec![SupportedTypes::Boolean(true); 5]).unwrap();
+
+        let mut limits = DecodeLimits::new();
+        limits.max_elements = 5;
+        assert_eq!(Array::decode_with(&buff, &limits).unwrap().len(), 5);
+
+        limits.max_elements = 4;
+        assert_eq!(
+            exceeded(Array::decode_with(&buff, &limits)),
+            ("Array elements".to_string(), 4, 5)
+        );
+    }
+
+    #[test]
+    fn hostile_nesting_is_rejected() {
+        assert!(infer_buffer(&nested_arrays(63)).is_ok());
+
+        assert_eq!(
+            exceeded(Array::decode(&nested_arrays(64))),
+            ("Nesting depth".to_string(), 64, 65)
+        );
+
+        // Deep enough to overflow the stack if the depth were not bounded.
+        assert!(Array::decode(&nested_arrays(20_000)).is_err());
+    }
+
+    #[test]
+    fn nested_error_has_path() {
+        let mut buff = Array::encode(&vec![
+            SupportedTypes::Boolean(true),
+            SupportedTypes::Array(vec![
+                SupportedTypes::Null(None),
+                SupportedTypes::Boolean(false),
+            ]),
+        ])
+        .unwrap();
+
+        let bad_byte = buff
+            .windows(4)
+            .rposition(|window| window == [b'#', START_RECORD, 0, END_RECORD])
+            .unwrap()
+            + 2;
+        buff[bad_byte] = 2;
+
+        match Array::decode(&buff) {
+            Err(TypeError::Decoding(e)) => {
+                assert_eq!(e.path, vec![PathSegment::Index(1), PathSegment::Index(1)]);
+                assert_eq!(e.offset, Some(bad_byte));
+            }
+            other => panic!("Unexpected {:?}", other),
+        }
+    }
 }
@@ -1,178 +1,45 @@
+use std::io::Write;
+
 use crate::{
     commom::{
-        delimiters::{BUFFER_END, END_RECORD, START_RECORD, VALUE_DELIMITER},
-        errors::{decoding_error, DecodingError, DecodingErrors, TypeResult},
-        join_parts, slice_records, split_values, BuffPart, CoprotoType, Uint8Buff, ValueOrBuffer,
+        delimiters::{END_RECORD, START_RECORD, VALUE_DELIMITER},
+        errors::TypeResult,
+        write_bytes, Codec, DecodeLimits,
+    },
+    types::{
+        borrowed::named_value_ref, encode_value::write_nested_value, primitive::string::write_str,
+        SupportedTypes,
     },
-    types::{infer_buffer, primitive, BigInt, Boolean, Double, Integer, Null, SupportedTypes},
 };
 
 #[derive(Debug)]
-pub struct NamedValue {
-    pub first_byte: u8,
-    pub modifier_byte: Option<u8>,
-    pub modifier_char: Option<char>,
-    pub first_char: char,
-    pub value_of: TypeResult<(String, SupportedTypes)>,
-    pub buff: TypeResult<Uint8Buff>,
-}
+pub struct NamedValue;
 
-impl CoprotoType<(String, SupportedTypes)> for NamedValue {
+impl Codec for NamedValue {
+    type Value = (String, SupportedTypes);
     const FIRST_BYTE: u8 = b'@';
 
-    fn new(value: ValueOrBuffer<(String, SupportedTypes)>) -> Self {
-        match value {
-            ValueOrBuffer::Value(v) => Self {
-                first_byte: Self::FIRST_BYTE,
-                modifier_byte: None,
-                modifier_char: None,
-                first_char: '@',
-                value_of: Ok(v.clone()),
-                buff: Self::encode(v),
-            },
-            ValueOrBuffer::Buffer(vec) => Self {
-                first_byte: Self::FIRST_BYTE,
-                modifier_byte: None,
-                modifier_char: None,
-                first_char: '@',
-                value_of: Self::decode(vec.clone()),
-                buff: Ok(vec),
-            },
-        }
+    fn write_value<W: Write>(value: &(String, SupportedTypes), writer: &mut W) -> TypeResult<()> {
+        write_named_value(&value.0, &value.1, writer)
     }
 
-    fn encode(value: (String, SupportedTypes)) -> TypeResult<Uint8Buff> {
-        let mut parts: Vec<BuffPart> =
-            vec![BuffPart::Val(Self::FIRST_BYTE), BuffPart::Val(START_RECORD)];
-
-        let mut command_name = crate::types::String::encode(value.0)?;
-
-        command_name.pop();
-
-        parts.push(BuffPart::Arr(command_name));
-
-        parts.push(BuffPart::Val(VALUE_DELIMITER));
-
-        match value.1 {
-            SupportedTypes::BigInt(bi) => {
-                let mut bi_encoded = BigInt::encode(bi)?;
-                bi_encoded.pop();
-                parts.push(BuffPart::Arr(bi_encoded));
-            }
-            SupportedTypes::Boolean(bol) => {
-                let mut bol_encoded = Boolean::encode(bol)?;
-                bol_encoded.pop();
-                parts.push(BuffPart::Arr(bol_encoded));
-            }
-            SupportedTypes::Double(db) => {
-                let mut db_encoded = Double::encode(db)?;
-                db_encoded.pop();
-                parts.push(BuffPart::Arr(db_encoded));
-            }
-            SupportedTypes::Integer(int) => {
-                let mut int_encoded = Integer::encode(int)?;
-                int_encoded.pop();
-                parts.push(BuffPart::Arr(int_encoded));
-            }
-            SupportedTypes::Null(null_val) => {
-                let mut null_encoded = Null::encode(null_val)?;
-                null_encoded.pop();
-                parts.push(BuffPart::Arr(null_encoded));
-            }
-            SupportedTypes::String(string) => {
-                let mut string_encoded = primitive::String::encode(string.clone())?;
-                string_encoded.pop();
-                parts.push(BuffPart::Arr(string_encoded));
-            }
-        }
-
-        parts.push(BuffPart::Val(VALUE_DELIMITER));
-        parts.push(BuffPart::Val(END_RECORD));
-        parts.push(BuffPart::Val(BUFFER_END));
+    fn decode_with(value: &[u8], limits: &DecodeLimits) -> TypeResult<(String, SupportedTypes)> {
+        let (name, value) = named_value_ref(value, limits)?;
 
-        Ok(join_parts(parts))
+        Ok((name.into_owned(), value.into_owned()))
     }
+}
 
-    fn decode(value: Uint8Buff) -> TypeResult<(String, SupportedTypes)> {
-        let mut m_value = value.clone();
-
-        let first_byte = m_value.remove(0);
-
-        if first_byte != Self::FIRST_BYTE {
-            return Err(decoding_error(DecodingError {
-                from: value,
-                to: "NamedValue".to_string(),
-                cause: DecodingErrors::FirstByteError(
-                    "NamedValue".to_string(),
-                    Self::FIRST_BYTE,
-                    first_byte,
-                ),
-            }));
-        };
-
-        let records = slice_records(m_value.clone());
-
-        if records.len() > 1 {
-            return Err(decoding_error(DecodingError::new(
-                value,
-                "NamedValue",
-                DecodingErrors::TooMuch(
-                    "Records".to_string(),
-                    1,
-                    records.len().try_into().unwrap(),
-                ),
-            )));
-        };
-
-        let named_value_record = match records.first() {
-            Some(v_arr) => v_arr,
-            None => {
-                return Err(decoding_error(DecodingError {
-                    from: value.clone(),
-                    to: "NamedValue".to_string(),
-                    cause: DecodingErrors::NotEnough(
-                        "Bytes".to_string(),
-                        4,
-                        value.len().try_into().unwrap(),
-                    ),
-                }))
-            }
-        };
-
-        let values = split_values(named_value_record.clone());
-
-        let name = match values.first() {
-            Some(v) => crate::types::String::decode(v.to_vec())?,
-            None => {
-                return Err(decoding_error(DecodingError::new(
-                    value,
-                    "NamedValue",
-                    DecodingErrors::NotEnough(
-                        "Values".to_string(),
-                        2,
-                        values.len().try_into().unwrap(),
-                    ),
-                )))
-            }
-        };
-
-        let value = match values.get(1) {
-            Some(v) => infer_buffer(v.to_vec())?,
-            None => {
-                return Err(decoding_error(DecodingError::new(
-                    value,
-                    "NamedValue",
-                    DecodingErrors::NotEnough(
-                        "Values".to_string(),
-                        2,
-                        values.len().try_into().unwrap(),
-                    ),
-                )))
-            }
-        };
-
-        Ok((name, value))
-    }
+pub(crate) fn write_named_value<W: Write>(
+    name: &str,
+    value: &SupportedTypes,
+    writer: &mut W,
+) -> TypeResult<()> {
+    write_bytes(writer, &[NamedValue::FIRST_BYTE, START_RECORD])?;
+    write_str(name, writer)?;
+    write_bytes(writer, &[VALUE_DELIMITER])?;
+    write_nested_value(value, writer)?;
+    write_bytes(writer, &[VALUE_DELIMITER, END_RECORD])
 }
 
 #[cfg(test)]
@@ -180,7 +47,7 @@ mod tests {
     use crate::{
         commom::{
             delimiters::{BUFFER_END, END_RECORD, START_RECORD},
-            CoprotoType, ValueOrBuffer,
+            Lazy, ValueOrBuffer,
         },
         types::{NamedValue, SupportedTypes},
     };
@@ -197,31 +64,84 @@ mod tests {
         ];
 
         for tp in types_to_test.iter() {
-            let encoding = NamedValue::new(ValueOrBuffer::Value((
+            let encoding = Lazy::<NamedValue>::new(ValueOrBuffer::Value((
+                tp.get_name().to_string(),
+                tp.clone(),
+            )));
+
+            let buff = encoding.buff().unwrap().to_vec();
+            let decoding = Lazy::<NamedValue>::new(ValueOrBuffer::Buffer(buff));
+
+            assert_eq!(*decoding.value().unwrap(), *encoding.value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_nested_encoding_decoding() {
+        let types_to_test = [
+            SupportedTypes::Array(vec![
+                SupportedTypes::Integer(1),
+                SupportedTypes::Array(vec![SupportedTypes::Null(None)]),
+            ]),
+            SupportedTypes::NamedValue(
+                "inner".to_string(),
+                Box::new(SupportedTypes::String("value".to_string())),
+            ),
+            SupportedTypes::Table(
+                vec!["name".to_string()],
+                vec![
+                    vec![SupportedTypes::String("first".to_string())],
+                    vec![SupportedTypes::NamedValue(
+                        "deep".to_string(),
+                        Box::new(SupportedTypes::BigInt(-42)),
+                    )],
+                ],
+            ),
+            SupportedTypes::Command("GET".to_string(), vec!["key".to_string()]),
+        ];
+
+        for tp in types_to_test.iter() {
+            let encoding = Lazy::<NamedValue>::new(ValueOrBuffer::Value((
                 tp.get_name().to_string(),
                 tp.clone(),
             )));
 
-            let buff = encoding.buff.unwrap();
-            let decoding = NamedValue::new(ValueOrBuffer::Buffer(buff));
+            let buff = encoding.buff().unwrap().to_vec();
+            let decoding = Lazy::<NamedValue>::new(ValueOrBuffer::Buffer(buff));
 
-            assert_eq!(decoding.value_of.unwrap(), encoding.value_of.unwrap());
+            assert_eq!(*decoding.value().unwrap(), *encoding.value().unwrap());
         }
     }
 
+    #[test]
+    fn test_every_byte_in_name() {
+        let name: String = (0..=u8::MAX).map(char::from).collect();
+
+        let value = (
+            name.clone(),
+            SupportedTypes::String(name.chars().rev().collect()),
+        );
+
+        let encoding = Lazy::<NamedValue>::new(ValueOrBuffer::Value(value.clone()));
+        let decoding =
+            Lazy::<NamedValue>::new(ValueOrBuffer::Buffer(encoding.buff().unwrap().to_vec()));
+
+        assert_eq!(*decoding.value().unwrap(), value);
+    }
+
     #[test]
     fn wrong_buffer() {
         let buff = vec![b'?', START_RECORD, 0, END_RECORD, BUFFER_END];
 
-        let wrong = NamedValue::new(ValueOrBuffer::Buffer(buff));
+        let wrong = Lazy::<NamedValue>::new(ValueOrBuffer::Buffer(buff));
 
-        match wrong.value_of {
+        match wrong.value() {
             Ok(_) => false,
             Err(e) => match e {
                 crate::commom::errors::TypeError::Encoding(_) => false,
                 crate::commom::errors::TypeError::Decoding(decoding_error) => {
                     matches!(
-                        decoding_error.cause,
+                        *decoding_error.cause,
                         crate::commom::errors::DecodingErrors::FirstByteError(_, _, _)
                     )
                 }
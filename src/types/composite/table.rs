@@ -1,256 +1,105 @@
+use std::{borrow::Cow, io::Write};
+
 use crate::{
     commom::{
         delimiters::{END_RECORD, START_RECORD, VALUE_DELIMITER},
-        errors::{
-            decoding_error, encoding_error, DecodingError, DecodingErrors, EncodingError,
-            EncodingErrors, TypeResult,
-        },
-        join_parts, slice_records, BuffPart, CoprotoType, Uint8Buff, ValueOrBuffer,
+        errors::{encoding_error, EncodingError, EncodingErrors, TypeResult},
+        write_bytes, Codec, DecodeLimits,
+    },
+    types::{
+        borrowed::{table_ref, ValueRef},
+        encode_value::write_nested_value,
+        primitive::string::write_str,
+        Null, SupportedTypes,
     },
-    types::{infer_buffer, BigInt, Boolean, Double, Integer, Null, SupportedTypes},
 };
 
 #[derive(Debug)]
-pub struct Table {
-    pub first_byte: u8,
-    pub modifier_byte: Option<u8>,
-    pub modifier_char: Option<char>,
-    pub first_char: char,
-    pub value_of: TypeResult<(Vec<String>, Vec<Vec<SupportedTypes>>)>,
-    pub buff: TypeResult<Uint8Buff>,
-}
+pub struct Table;
 
-impl CoprotoType<(Vec<String>, Vec<Vec<SupportedTypes>>)> for Table {
+impl Codec for Table {
+    type Value = (Vec<String>, Vec<Vec<SupportedTypes>>);
     const FIRST_BYTE: u8 = b'{';
 
-    fn new(value: ValueOrBuffer<(Vec<String>, Vec<Vec<SupportedTypes>>)>) -> Self {
-        match value {
-            ValueOrBuffer::Value(v) => Self {
-                first_byte: Self::FIRST_BYTE,
-                modifier_byte: None,
-                modifier_char: None,
-                first_char: '{',
-                value_of: Ok(v.clone()),
-                buff: Self::encode(v),
-            },
-            ValueOrBuffer::Buffer(vec) => Self {
-                first_byte: Self::FIRST_BYTE,
-                modifier_byte: None,
-                modifier_char: None,
-                first_char: '{',
-                value_of: Self::decode(vec.clone()),
-                buff: Ok(vec),
-            },
-        }
+    fn write_value<W: Write>(
+        value: &(Vec<String>, Vec<Vec<SupportedTypes>>),
+        writer: &mut W,
+    ) -> TypeResult<()> {
+        write_table(&value.0, &value.1, writer)
     }
 
-    fn encode(value: (Vec<String>, Vec<Vec<SupportedTypes>>)) -> TypeResult<Uint8Buff> {
-        let mut parts: Vec<BuffPart> =
-            vec![BuffPart::Val(Self::FIRST_BYTE), BuffPart::Val(START_RECORD)];
-
-        let headers = value.0;
-
-        if headers.is_empty() {
-            return Err(encoding_error(EncodingError::new(
-                "[]",
-                "Table",
-                EncodingErrors::InvalidValue("Table headers cannot be empty".to_string()),
-            )));
-        }
-
-        let rows = value.1;
-
-        for header in headers.iter() {
-            let mut encoded_str = crate::types::String::encode(header.to_string())?;
-            encoded_str.pop();
-            parts.push(BuffPart::Val(START_RECORD));
-            parts.push(BuffPart::Arr(encoded_str));
-            parts.push(BuffPart::Val(END_RECORD));
-        }
-
-        parts.push(BuffPart::Val(END_RECORD));
-        parts.push(BuffPart::Val(VALUE_DELIMITER));
-
-        parts.push(BuffPart::Val(START_RECORD));
-        parts.push(BuffPart::Arr(Null::encode(None)?));
-        parts.push(BuffPart::Val(END_RECORD));
-
-        for row in rows.iter() {
-            if row.len() != headers.len() {
-                return Err(encoding_error(EncodingError::new(
-                    "(Vec<String>, Vec<Vec<SupportedTypes>>)",
-                    "Table",
-                    EncodingErrors::TableMisfit(headers.len(), row.len()),
-                )));
-            }
-
-            parts.push(BuffPart::Val(START_RECORD));
-
-            for row_data in row.iter() {
-                parts.push(BuffPart::Val(START_RECORD));
-                match row_data {
-                    SupportedTypes::BigInt(bi) => {
-                        let mut encoded = BigInt::encode(*bi)?;
-                        encoded.pop();
-                        parts.push(BuffPart::Arr(encoded));
-                    }
-                    SupportedTypes::Boolean(bol) => {
-                        let mut encoded = Boolean::encode(*bol)?;
-                        encoded.pop();
-                        parts.push(BuffPart::Arr(encoded));
-                    }
-                    SupportedTypes::Double(db) => {
-                        let mut encoded = Double::encode(*db)?;
-                        encoded.pop();
-                        parts.push(BuffPart::Arr(encoded));
-                    }
-                    SupportedTypes::Integer(int) => {
-                        let mut encoded = Integer::encode(*int)?;
-                        encoded.pop();
-                        parts.push(BuffPart::Arr(encoded));
-                    }
-                    SupportedTypes::Null(null) => {
-                        let mut encoded = Null::encode(*null)?;
-                        encoded.pop();
-                        parts.push(BuffPart::Arr(encoded));
-                    }
-                    SupportedTypes::String(str) => {
-                        let mut encoded = crate::types::String::encode(str.to_string())?;
-                        encoded.pop();
-                        parts.push(BuffPart::Arr(encoded));
-                    }
-                }
-                parts.push(BuffPart::Val(END_RECORD));
-            }
-
-            parts.push(BuffPart::Val(END_RECORD));
-            parts.push(BuffPart::Val(VALUE_DELIMITER));
-        }
-
-        Ok(join_parts(parts))
+    fn decode_with(
+        value: &[u8],
+        limits: &DecodeLimits,
+    ) -> TypeResult<(Vec<String>, Vec<Vec<SupportedTypes>>)> {
+        let (headers, rows) = table_ref(value, limits)?;
+
+        Ok((
+            headers.into_iter().map(Cow::into_owned).collect(),
+            rows.into_iter()
+                .map(|row| row.into_iter().map(ValueRef::into_owned).collect())
+                .collect(),
+        ))
     }
+}
 
-    fn decode(value: Uint8Buff) -> TypeResult<(Vec<String>, Vec<Vec<SupportedTypes>>)> {
-        let mut m_value = value.clone();
-
-        let first_byte = m_value.remove(0);
-
-        if first_byte != Self::FIRST_BYTE {
-            return Err(decoding_error(DecodingError {
-                from: value,
-                to: "Table".to_string(),
-                cause: DecodingErrors::FirstByteError(
-                    "Table".to_string(),
-                    Self::FIRST_BYTE,
-                    first_byte,
-                ),
-            }));
-        };
-
-        let records = slice_records(m_value);
-
-        let mut headers: Vec<String> = vec![];
-
-        let mut rows: Vec<Vec<SupportedTypes>> = vec![];
-
-        let mut found_null = false;
-
-        let mut headers_len: usize = 0;
-
-        let mut row: Vec<SupportedTypes> = vec![];
-
-        // This is shitty code
-        fn insert_where_it_belongs(
-            val: SupportedTypes,
-            original_value: Uint8Buff,
-            f_n: &mut bool,
-            r: &mut Vec<SupportedTypes>,
-            h: &mut Vec<String>,
-        ) -> TypeResult<()> {
-            match val {
-                SupportedTypes::Null(_) => {
-                    if *f_n == false {
-                        *f_n = true;
-                    } else {
-                        r.push(val);
-                    }
-                    Ok(())
-                }
-                SupportedTypes::String(ref str) => {
-                    if *f_n == false {
-                        h.push(str.to_string());
-                    } else {
-                        r.push(val);
-                    }
-                    Ok(())
-                }
-                _ => {
-                    if *f_n {
-                        r.push(val);
-                        Ok(())
-                    } else {
-                        Err(decoding_error(DecodingError::new(
-                            original_value.clone(),
-                            "Table",
-                            DecodingErrors::InvalidTypeInCompositeType(
-                                "String or Null".to_string(),
-                                val.get_name().to_string(),
-                            ),
-                        )))
-                    }
-                }
-            }
-        }
+pub(crate) fn write_table<W: Write>(
+    headers: &[String],
+    rows: &[Vec<SupportedTypes>],
+    writer: &mut W,
+) -> TypeResult<()> {
+    if headers.is_empty() {
+        return Err(encoding_error(EncodingError::new(
+            "[]",
+            "Table",
+            EncodingErrors::InvalidValue("Table headers cannot be empty".to_string()),
+        )));
+    }
 
-        for record in records.iter() {
-            let infered = infer_buffer(record.to_vec())?;
+    // Checked up front so a misfit row never leaves half a table in the writer.
+    if let Some(row) = rows.iter().find(|row| row.len() != headers.len()) {
+        return Err(encoding_error(EncodingError::new(
+            "(Vec<String>, Vec<Vec<SupportedTypes>>)",
+            "Table",
+            EncodingErrors::TableMisfit(headers.len(), row.len()),
+        )));
+    }
 
-            insert_where_it_belongs(
-                infered,
-                value.clone(),
-                &mut found_null,
-                &mut row,
-                &mut headers,
-            )?;
+    write_bytes(writer, &[Table::FIRST_BYTE, START_RECORD, START_RECORD])?;
 
-            if found_null && headers_len == 0 {
-                headers_len = headers.len();
-                continue;
-            }
+    for header in headers.iter() {
+        write_bytes(writer, &[START_RECORD])?;
+        write_str(header, writer)?;
+        write_bytes(writer, &[END_RECORD])?;
+    }
 
-            if row.len() == headers_len && headers_len != 0 {
-                rows.extend_from_slice(&[row.to_vec()]);
-                row = vec![];
-            }
-        }
+    write_bytes(writer, &[END_RECORD, VALUE_DELIMITER, START_RECORD])?;
+    Null::encode_to(&None, writer)?;
+    write_bytes(writer, &[END_RECORD])?;
 
-        if !row.is_empty() {
-            return Err(decoding_error(DecodingError::new(
-                value,
-                "Table",
-                DecodingErrors::CantFitValues(format!(
-                    "The table has {} values. But a row was found to have only {} values.",
-                    headers_len,
-                    row.len()
-                )),
-            )));
-        }
+    for row in rows.iter() {
+        write_bytes(writer, &[START_RECORD])?;
 
-        println!("Headers:\n{:?}", headers);
-        println!("Rows:");
-        for r in rows.iter() {
-            println!("{:?}", r);
+        for row_data in row.iter() {
+            write_bytes(writer, &[START_RECORD])?;
+            write_nested_value(row_data, writer)?;
+            write_bytes(writer, &[END_RECORD])?;
         }
 
-        Ok((headers, rows))
+        write_bytes(writer, &[END_RECORD, VALUE_DELIMITER])?;
     }
+
+    write_bytes(writer, &[END_RECORD])
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
-        commom::{CoprotoType, ValueOrBuffer},
-        types::SupportedTypes,
+        commom::{
+            errors::{PathSegment, TypeError},
+            Codec, DecodeLimits, Lazy, ValueOrBuffer,
+        },
+        types::{infer_borrowed, Integer, SupportedTypes},
     };
 
     use super::Table;
@@ -265,17 +114,69 @@ mod tests {
             ]],
         );
 
-        let encoded = Table::new(ValueOrBuffer::Value(original_table.clone()));
+        let encoded = Lazy::<Table>::new(ValueOrBuffer::Value(original_table.clone()));
 
         println!("Encoded:\n{:?}", encoded);
 
-        let buff = encoded.buff.unwrap();
+        let buff = encoded.buff().unwrap().to_vec();
 
-        let decoded = Table::new(ValueOrBuffer::Buffer(buff.clone()));
+        let decoded = Lazy::<Table>::new(ValueOrBuffer::Buffer(buff.clone()));
 
-        assert_eq!(original_table, decoded.value_of.unwrap());
+        assert_eq!(original_table, *decoded.value().unwrap());
 
-        assert_eq!(buff.clone(), decoded.buff.unwrap());
+        assert_eq!(buff.clone(), decoded.buff().unwrap().to_vec());
+    }
+
+    #[test]
+    fn nested_encoding_decoding() {
+        let original_table = (
+            vec!["Id".to_string(), "Children".to_string()],
+            vec![
+                vec![
+                    SupportedTypes::Integer(1),
+                    SupportedTypes::Array(vec![
+                        SupportedTypes::String("a".to_string()),
+                        SupportedTypes::Array(vec![SupportedTypes::Double(0.5)]),
+                    ]),
+                ],
+                vec![
+                    SupportedTypes::Integer(2),
+                    SupportedTypes::Table(
+                        vec!["Inner".to_string()],
+                        vec![vec![SupportedTypes::NamedValue(
+                            "flag".to_string(),
+                            Box::new(SupportedTypes::Boolean(true)),
+                        )]],
+                    ),
+                ],
+            ],
+        );
+
+        let encoded = Lazy::<Table>::new(ValueOrBuffer::Value(original_table.clone()));
+
+        let buff = encoded.buff().unwrap().to_vec();
+
+        let decoded = Lazy::<Table>::new(ValueOrBuffer::Buffer(buff));
+
+        assert_eq!(original_table, *decoded.value().unwrap());
+    }
+
+    #[test]
+    fn every_byte_in_headers() {
+        let headers: Vec<String> = (0..=u8::MAX).map(|b| char::from(b).to_string()).collect();
+
+        let row: Vec<SupportedTypes> = headers
+            .iter()
+            .map(|h| SupportedTypes::String(h.repeat(2)))
+            .collect();
+
+        let original_table = (headers, vec![row]);
+
+        let encoded = Lazy::<Table>::new(ValueOrBuffer::Value(original_table.clone()));
+
+        let decoded = Lazy::<Table>::new(ValueOrBuffer::Buffer(encoded.buff().unwrap().to_vec()));
+
+        assert_eq!(original_table, *decoded.value().unwrap());
     }
 
     #[test]
@@ -285,7 +186,7 @@ mod tests {
             vec![vec![SupportedTypes::Boolean(false)]],
         );
 
-        let err = match Table::encode(table_to_encode) {
+        let err = match Table::encode(&table_to_encode) {
             Ok(_) => false,
             Err(e) => match e {
                 crate::commom::errors::TypeError::Encoding(encoding_error) => {
@@ -311,7 +212,7 @@ mod tests {
             ]],
         );
 
-        let err = match Table::encode(table_to_encode) {
+        let err = match Table::encode(&table_to_encode) {
             Ok(_) => false,
             Err(e) => match e {
                 crate::commom::errors::TypeError::Encoding(encoding_error) => {
@@ -326,4 +227,60 @@ mod tests {
 
         assert!(err);
     }
+
+    #[test]
+    fn row_limit() {
+        let headers = vec!["n".to_string()];
+        let rows = vec![vec![SupportedTypes::Integer(1)]; 3];
+
+        let buff = Table::encode(&(headers, rows)).unwrap();
+
+        let mut limits = DecodeLimits::new();
+        limits.max_table_rows = 3;
+        assert!(Table::decode_with(&buff, &limits).is_ok());
+
+        limits.max_table_rows = 2;
+        assert!(Table::decode_with(&buff, &limits).is_err());
+    }
+
+    #[test]
+    fn bad_cell_is_located() {
+        let headers = vec!["id".to_string(), "count".to_string()];
+        let rows = vec![
+            vec![SupportedTypes::Integer(1), SupportedTypes::Integer(2)],
+            vec![SupportedTypes::Integer(3), SupportedTypes::Integer(456)],
+        ];
+
+        let mut buff = Table::encode(&(headers, rows)).unwrap();
+
+        let mut cell = vec![];
+        Integer::write_value(&456, &mut cell).unwrap();
+
+        let cell_start = buff
+            .windows(cell.len())
+            .position(|window| window == cell.as_slice())
+            .unwrap();
+        // Second digit of the body, after the first byte, sign and START_RECORD.
+        let bad_byte = cell_start + 4;
+        buff[bad_byte] = b'x';
+
+        let expected_path = vec![PathSegment::Cell {
+            row: 1,
+            column: "count".to_string(),
+        }];
+
+        for result in [
+            Table::decode(&buff).map(|_| ()),
+            infer_borrowed(&buff).map(|_| ()),
+        ] {
+            match result {
+                Err(TypeError::Decoding(e)) => {
+                    assert_eq!(e.offset, Some(bad_byte));
+                    assert_eq!(e.path, expected_path);
+                    assert_eq!(e.from, buff);
+                }
+                other => panic!("Unexpected {:?}", other),
+            }
+        }
+    }
 }
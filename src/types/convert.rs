@@ -0,0 +1,651 @@
+//! Conversions between Rust values and [`SupportedTypes`] trees.
+//!
+//! `#[derive(Coproto)]` (the `derive` feature) implements both traits for
+//! structs and enums:
+//! - Structs with named fields become an Array of NamedValues keyed by field
+//!   name, and a `Vec` of them becomes a Table with the field names as headers.
+//! - Newtype structs become their field, other tuple structs an Array and
+//!   unit structs Null.
+//! - Unit variants become a String with their name. Other variants become a
+//!   NamedValue holding what the same fields would give in a struct.
+//!
+//! `#[coproto(rename = "name")]` changes the name of a field or variant, and
+//! `#[coproto(skip)]` leaves a field out, filling it with `Default` when read.
+//!
+//! `Vec<u8>` becomes Bytes. Integers take Integer, BigInt or HugeInt by width,
+//! like the `serde` feature does, and read back from any of them when they fit.
+
+use crate::commom::errors::{
+    decoding_error, DecodingError, DecodingErrors, PathSegment, TypeError, TypeResult,
+};
+
+use super::{BigNum, SupportedTypes};
+
+pub trait ToValue {
+    fn to_value(&self) -> SupportedTypes;
+
+    /// How a `Vec` of `Self` is written. Derived structs write Tables.
+    fn slice_to_value(items: &[Self]) -> SupportedTypes
+    where
+        Self: Sized,
+    {
+        SupportedTypes::Array(items.iter().map(ToValue::to_value).collect())
+    }
+}
+
+pub trait FromValue: Sized {
+    fn from_value(value: SupportedTypes) -> TypeResult<Self>;
+
+    /// Value for a struct field that is not there. Only `Option` has one.
+    fn from_missing(field: &str) -> TypeResult<Self> {
+        Err(decoding_error(DecodingError::new(
+            vec![],
+            std::any::type_name::<Self>(),
+            DecodingErrors::CouldNotFind(0, format!("field '{}'", field)),
+        )))
+    }
+
+    /// How a `Vec` of `Self` is read. Derived structs also read Tables.
+    fn vec_from_value(value: SupportedTypes) -> TypeResult<Vec<Self>> {
+        array_from_value(value)
+    }
+}
+
+/// Error for a `found` value where `T` needed an `expected` one.
+pub fn mismatch<T>(found: &SupportedTypes, expected: &str) -> TypeError {
+    decoding_error(DecodingError::new(
+        vec![],
+        std::any::type_name::<T>(),
+        DecodingErrors::TypeMismatch {
+            expected: expected.to_string(),
+            found: found.get_name().to_string(),
+        },
+    ))
+}
+
+/// `v` as an f64, or `None` when it needs more than the 53 bits of precision
+/// an f64 has.
+pub(crate) fn exact_f64(v: i64) -> Option<f64> {
+    let float = v as f64;
+
+    // Compared as i128, since `i64::MAX` rounds up to 2^63 and `as i64` would
+    // saturate it back.
+    (float as i128 == i128::from(v)).then_some(float)
+}
+
+fn out_of_range<T>(value: impl ToString) -> TypeError {
+    decoding_error(DecodingError::new(
+        vec![],
+        std::any::type_name::<T>(),
+        DecodingErrors::Overflow(value.to_string(), std::any::type_name::<T>().to_string()),
+    ))
+}
+
+pub fn array_from_value<T: FromValue>(value: SupportedTypes) -> TypeResult<Vec<T>> {
+    match value {
+        SupportedTypes::Array(items) => items
+            .into_iter()
+            .enumerate()
+            .map(|(idx, item)| T::from_value(item).map_err(|e| e.under(PathSegment::Index(idx))))
+            .collect(),
+        other => Err(mismatch::<Vec<T>>(&other, "Array")),
+    }
+}
+
+/// Reads a Table one row at a time, as the named fields of a struct. Arrays
+/// of structs are read too.
+pub fn rows_from_value<T: FromValue>(value: SupportedTypes) -> TypeResult<Vec<T>> {
+    match value {
+        SupportedTypes::Table(headers, rows) => rows
+            .into_iter()
+            .enumerate()
+            .map(|(idx, row)| {
+                let fields = headers
+                    .iter()
+                    .zip(row)
+                    .map(|(header, cell)| {
+                        SupportedTypes::NamedValue(header.clone(), Box::new(cell))
+                    })
+                    .collect();
+
+                T::from_value(SupportedTypes::Array(fields))
+                    .map_err(|e| e.under(PathSegment::Index(idx)))
+            })
+            .collect(),
+        other => array_from_value(other),
+    }
+}
+
+/// The named fields of a struct, taken out one by one.
+pub struct Fields(Vec<(String, SupportedTypes)>);
+
+impl Fields {
+    pub fn new<T>(value: SupportedTypes) -> TypeResult<Self> {
+        let items = match value {
+            SupportedTypes::Array(items) => items,
+            SupportedTypes::NamedValue(name, value) => {
+                vec![SupportedTypes::NamedValue(name, value)]
+            }
+            other => return Err(mismatch::<T>(&other, "Array of NamedValues")),
+        };
+
+        let mut fields = Vec::with_capacity(items.len());
+
+        for item in items {
+            match item {
+                SupportedTypes::NamedValue(name, value) => fields.push((name, *value)),
+                other => return Err(mismatch::<T>(&other, "NamedValue")),
+            }
+        }
+
+        Ok(Self(fields))
+    }
+
+    pub fn take<T: FromValue>(&mut self, name: &str) -> TypeResult<T> {
+        match self.0.iter().position(|(field, _)| field == name) {
+            Some(idx) => T::from_value(self.0.swap_remove(idx).1)
+                .map_err(|e| e.under(PathSegment::Value(name.to_string()))),
+            None => T::from_missing(name),
+        }
+    }
+}
+
+/// The items of a tuple struct or variant, taken out in order.
+pub struct Items(std::iter::Enumerate<std::vec::IntoIter<SupportedTypes>>);
+
+impl Items {
+    pub fn new<T>(value: SupportedTypes, len: usize) -> TypeResult<Self> {
+        match value {
+            SupportedTypes::Array(items) if items.len() == len => {
+                Ok(Self(items.into_iter().enumerate()))
+            }
+            SupportedTypes::Array(items) => Err(decoding_error(DecodingError::new(
+                vec![],
+                std::any::type_name::<T>(),
+                DecodingErrors::NotEnough(
+                    "Array items".to_string(),
+                    len.try_into().unwrap_or(u32::MAX),
+                    items.len().try_into().unwrap_or(u32::MAX),
+                ),
+            ))),
+            other => Err(mismatch::<T>(&other, "Array")),
+        }
+    }
+
+    pub fn take<T: FromValue>(&mut self) -> TypeResult<T> {
+        match self.0.next() {
+            Some((idx, item)) => T::from_value(item).map_err(|e| e.under(PathSegment::Index(idx))),
+            None => Err(mismatch::<T>(&SupportedTypes::Null(None), "Array item")),
+        }
+    }
+}
+
+/// The name of an enum variant and what it holds, if anything.
+pub fn variant_of<T>(value: SupportedTypes) -> TypeResult<(String, Option<SupportedTypes>)> {
+    match value {
+        SupportedTypes::String(name) => Ok((name, None)),
+        SupportedTypes::NamedValue(name, value) => Ok((name, Some(*value))),
+        other => Err(mismatch::<T>(&other, "String or NamedValue")),
+    }
+}
+
+pub fn unknown_variant<T>(name: &str) -> TypeError {
+    decoding_error(DecodingError::new(
+        vec![],
+        std::any::type_name::<T>(),
+        DecodingErrors::CantFitValues(format!("Unknown variant '{}'", name)),
+    ))
+}
+
+macro_rules! small_integer {
+    ($($t:ty => $variant:ident),*) => {$(
+        impl ToValue for $t {
+            fn to_value(&self) -> SupportedTypes {
+                SupportedTypes::$variant((*self).into())
+            }
+        }
+
+        impl FromValue for $t {
+            fn from_value(value: SupportedTypes) -> TypeResult<Self> {
+                let wide: i128 = match value {
+                    SupportedTypes::Integer(v) => v.into(),
+                    SupportedTypes::BigInt(v) => v.into(),
+                    SupportedTypes::HugeInt(v) => match i128::try_from(&v) {
+                        Ok(v) => v,
+                        Err(_) => return Err(out_of_range::<Self>(v)),
+                    },
+                    other => return Err(mismatch::<Self>(&other, "Integer, BigInt or HugeInt")),
+                };
+
+                <$t>::try_from(wide).map_err(|_| out_of_range::<Self>(wide))
+            }
+        }
+    )*};
+}
+
+small_integer!(i8 => Integer, i16 => Integer, i32 => Integer, u16 => Integer);
+small_integer!(i64 => BigInt, u32 => BigInt, i128 => HugeInt);
+
+// Bytes have their own wire type, so `Vec<u8>` does not go through Array.
+impl ToValue for u8 {
+    fn to_value(&self) -> SupportedTypes {
+        SupportedTypes::Integer((*self).into())
+    }
+
+    fn slice_to_value(items: &[Self]) -> SupportedTypes {
+        SupportedTypes::Bytes(items.to_vec())
+    }
+}
+
+impl FromValue for u8 {
+    fn from_value(value: SupportedTypes) -> TypeResult<Self> {
+        let wide = i128::from_value(value)?;
+        u8::try_from(wide).map_err(|_| out_of_range::<Self>(wide))
+    }
+
+    fn vec_from_value(value: SupportedTypes) -> TypeResult<Vec<Self>> {
+        match value {
+            SupportedTypes::Bytes(bytes) => Ok(bytes),
+            other => array_from_value(other),
+        }
+    }
+}
+
+impl ToValue for u64 {
+    fn to_value(&self) -> SupportedTypes {
+        match i64::try_from(*self) {
+            Ok(v) => SupportedTypes::BigInt(v),
+            Err(_) => SupportedTypes::HugeInt(BigNum::from(*self)),
+        }
+    }
+}
+
+impl FromValue for u64 {
+    fn from_value(value: SupportedTypes) -> TypeResult<Self> {
+        let wide = i128::from_value(value)?;
+        u64::try_from(wide).map_err(|_| out_of_range::<Self>(wide))
+    }
+}
+
+impl ToValue for usize {
+    fn to_value(&self) -> SupportedTypes {
+        (*self as u64).to_value()
+    }
+}
+
+impl FromValue for usize {
+    fn from_value(value: SupportedTypes) -> TypeResult<Self> {
+        let wide = u64::from_value(value)?;
+        usize::try_from(wide).map_err(|_| out_of_range::<Self>(wide))
+    }
+}
+
+impl ToValue for u128 {
+    fn to_value(&self) -> SupportedTypes {
+        SupportedTypes::HugeInt(BigNum::from(*self))
+    }
+}
+
+impl FromValue for u128 {
+    fn from_value(value: SupportedTypes) -> TypeResult<Self> {
+        let big = BigNum::from_value(value)?;
+        u128::try_from(&big).map_err(|_| out_of_range::<Self>(big))
+    }
+}
+
+impl ToValue for BigNum {
+    fn to_value(&self) -> SupportedTypes {
+        SupportedTypes::HugeInt(self.clone())
+    }
+}
+
+impl FromValue for BigNum {
+    fn from_value(value: SupportedTypes) -> TypeResult<Self> {
+        match value {
+            SupportedTypes::Integer(v) => Ok(BigNum::from(v)),
+            SupportedTypes::BigInt(v) => Ok(BigNum::from(v)),
+            SupportedTypes::HugeInt(v) => Ok(v),
+            other => Err(mismatch::<Self>(&other, "Integer, BigInt or HugeInt")),
+        }
+    }
+}
+
+impl ToValue for f64 {
+    fn to_value(&self) -> SupportedTypes {
+        SupportedTypes::Double(*self)
+    }
+}
+
+impl FromValue for f64 {
+    fn from_value(value: SupportedTypes) -> TypeResult<Self> {
+        match value {
+            SupportedTypes::Double(v) => Ok(v),
+            SupportedTypes::Integer(v) => Ok(v.into()),
+            SupportedTypes::BigInt(v) => exact_f64(v).ok_or_else(|| out_of_range::<Self>(v)),
+            other => Err(mismatch::<Self>(&other, "Double")),
+        }
+    }
+}
+
+impl ToValue for f32 {
+    fn to_value(&self) -> SupportedTypes {
+        SupportedTypes::Double((*self).into())
+    }
+}
+
+impl FromValue for f32 {
+    fn from_value(value: SupportedTypes) -> TypeResult<Self> {
+        let v = f64::from_value(value)?;
+
+        match v as f32 {
+            narrow if v.is_finite() && !narrow.is_finite() => Err(out_of_range::<Self>(v)),
+            narrow => Ok(narrow),
+        }
+    }
+}
+
+impl ToValue for bool {
+    fn to_value(&self) -> SupportedTypes {
+        SupportedTypes::Boolean(*self)
+    }
+}
+
+impl FromValue for bool {
+    fn from_value(value: SupportedTypes) -> TypeResult<Self> {
+        match value {
+            SupportedTypes::Boolean(v) => Ok(v),
+            other => Err(mismatch::<Self>(&other, "Boolean")),
+        }
+    }
+}
+
+impl ToValue for str {
+    fn to_value(&self) -> SupportedTypes {
+        SupportedTypes::String(self.to_string())
+    }
+}
+
+impl ToValue for String {
+    fn to_value(&self) -> SupportedTypes {
+        SupportedTypes::String(self.clone())
+    }
+}
+
+impl FromValue for String {
+    fn from_value(value: SupportedTypes) -> TypeResult<Self> {
+        match value {
+            SupportedTypes::String(v) => Ok(v),
+            other => Err(mismatch::<Self>(&other, "String")),
+        }
+    }
+}
+
+impl ToValue for char {
+    fn to_value(&self) -> SupportedTypes {
+        SupportedTypes::String(self.to_string())
+    }
+}
+
+impl FromValue for char {
+    fn from_value(value: SupportedTypes) -> TypeResult<Self> {
+        let string = String::from_value(value)?;
+        let mut chars = string.chars();
+
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(mismatch::<Self>(
+                &SupportedTypes::String(string),
+                "String of one character",
+            )),
+        }
+    }
+}
+
+impl ToValue for SupportedTypes {
+    fn to_value(&self) -> SupportedTypes {
+        self.clone()
+    }
+}
+
+impl FromValue for SupportedTypes {
+    fn from_value(value: SupportedTypes) -> TypeResult<Self> {
+        Ok(value)
+    }
+}
+
+impl<T: ToValue> ToValue for Option<T> {
+    fn to_value(&self) -> SupportedTypes {
+        match self {
+            Some(v) => v.to_value(),
+            None => SupportedTypes::Null(None),
+        }
+    }
+}
+
+impl<T: FromValue> FromValue for Option<T> {
+    fn from_value(value: SupportedTypes) -> TypeResult<Self> {
+        match value {
+            SupportedTypes::Null(_) => Ok(None),
+            other => T::from_value(other).map(Some),
+        }
+    }
+
+    fn from_missing(_field: &str) -> TypeResult<Self> {
+        Ok(None)
+    }
+}
+
+impl<T: ToValue + ?Sized> ToValue for Box<T> {
+    fn to_value(&self) -> SupportedTypes {
+        (**self).to_value()
+    }
+}
+
+impl<T: FromValue> FromValue for Box<T> {
+    fn from_value(value: SupportedTypes) -> TypeResult<Self> {
+        T::from_value(value).map(Box::new)
+    }
+}
+
+impl<T: ToValue> ToValue for Vec<T> {
+    fn to_value(&self) -> SupportedTypes {
+        T::slice_to_value(self)
+    }
+}
+
+impl<T: FromValue> FromValue for Vec<T> {
+    fn from_value(value: SupportedTypes) -> TypeResult<Self> {
+        T::vec_from_value(value)
+    }
+}
+
+// `From` and `TryFrom` for the scalars, so values can be built with `.into()`
+// and read with `?`. Both follow the mapping above.
+macro_rules! scalar_conversions {
+    ($($t:ty),*) => {$(
+        impl From<$t> for SupportedTypes {
+            fn from(value: $t) -> Self {
+                value.to_value()
+            }
+        }
+
+        impl TryFrom<SupportedTypes> for $t {
+            type Error = TypeError;
+
+            fn try_from(value: SupportedTypes) -> TypeResult<Self> {
+                <$t>::from_value(value)
+            }
+        }
+    )*};
+}
+
+scalar_conversions!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, usize, f32, f64, bool, char);
+
+impl From<&str> for SupportedTypes {
+    fn from(value: &str) -> Self {
+        SupportedTypes::String(value.to_string())
+    }
+}
+
+impl From<String> for SupportedTypes {
+    fn from(value: String) -> Self {
+        SupportedTypes::String(value)
+    }
+}
+
+impl TryFrom<SupportedTypes> for String {
+    type Error = TypeError;
+
+    fn try_from(value: SupportedTypes) -> TypeResult<Self> {
+        String::from_value(value)
+    }
+}
+
+impl From<BigNum> for SupportedTypes {
+    fn from(value: BigNum) -> Self {
+        SupportedTypes::HugeInt(value)
+    }
+}
+
+impl TryFrom<SupportedTypes> for BigNum {
+    type Error = TypeError;
+
+    fn try_from(value: SupportedTypes) -> TypeResult<Self> {
+        BigNum::from_value(value)
+    }
+}
+
+impl From<Vec<u8>> for SupportedTypes {
+    fn from(value: Vec<u8>) -> Self {
+        SupportedTypes::Bytes(value)
+    }
+}
+
+impl TryFrom<SupportedTypes> for Vec<u8> {
+    type Error = TypeError;
+
+    fn try_from(value: SupportedTypes) -> TypeResult<Self> {
+        Vec::<u8>::from_value(value)
+    }
+}
+
+impl From<Vec<SupportedTypes>> for SupportedTypes {
+    fn from(value: Vec<SupportedTypes>) -> Self {
+        SupportedTypes::Array(value)
+    }
+}
+
+impl<T: Into<SupportedTypes>> From<Option<T>> for SupportedTypes {
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(v) => v.into(),
+            None => SupportedTypes::Null(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        commom::errors::{DecodingErrors, PathSegment, TypeError},
+        types::{BigNum, SupportedTypes},
+    };
+
+    use super::{FromValue, ToValue};
+
+    #[test]
+    fn scalars_round_trip() {
+        assert_eq!(5u8.to_value(), SupportedTypes::Integer(5));
+        assert_eq!(5u32.to_value(), SupportedTypes::BigInt(5));
+        assert_eq!(
+            u64::MAX.to_value(),
+            SupportedTypes::HugeInt(BigNum::from(u64::MAX))
+        );
+        assert_eq!(u64::from_value(u64::MAX.to_value()).unwrap(), u64::MAX);
+        assert_eq!(i128::from_value(SupportedTypes::Integer(-3)).unwrap(), -3);
+        assert_eq!(f64::from_value(SupportedTypes::Integer(2)).unwrap(), 2.0);
+        assert_eq!(
+            f64::from_value(SupportedTypes::BigInt(1 << 53)).unwrap(),
+            9007199254740992.0
+        );
+        assert!(f64::from_value(SupportedTypes::BigInt((1 << 53) + 1)).is_err());
+        assert!(f64::from_value(SupportedTypes::BigInt(i64::MAX)).is_err());
+        assert!(f32::from_value(SupportedTypes::Double(1e300)).is_err());
+        assert!(f32::from_value(SupportedTypes::Double(f64::INFINITY))
+            .unwrap()
+            .is_infinite());
+        assert_eq!(char::from_value('é'.to_value()).unwrap(), 'é');
+        assert_eq!(
+            Option::<bool>::from_value(SupportedTypes::Null(None)).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn byte_vectors_are_bytes() {
+        let bytes = vec![0u8, 255];
+
+        assert_eq!(bytes.to_value(), SupportedTypes::Bytes(vec![0, 255]));
+        assert_eq!(Vec::<u8>::from_value(bytes.to_value()).unwrap(), bytes);
+        assert_eq!(
+            vec![1i32, 2].to_value(),
+            SupportedTypes::Array(vec![SupportedTypes::Integer(1), SupportedTypes::Integer(2)])
+        );
+    }
+
+    #[test]
+    fn out_of_range_and_mismatches_are_errors() {
+        match u8::from_value(SupportedTypes::Integer(300)) {
+            Err(TypeError::Decoding(e)) => {
+                assert!(matches!(*e.cause, DecodingErrors::Overflow(_, _)))
+            }
+            other => panic!("Unexpected {:?}", other),
+        }
+
+        match Vec::<bool>::from_value(SupportedTypes::Array(vec![
+            SupportedTypes::Boolean(true),
+            SupportedTypes::Integer(1),
+        ])) {
+            Err(TypeError::Decoding(e)) => {
+                assert_eq!(e.path, vec![PathSegment::Index(1)]);
+                assert!(matches!(*e.cause, DecodingErrors::TypeMismatch { .. }));
+            }
+            other => panic!("Unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn from_and_try_from() {
+        let value = SupportedTypes::Array(vec![
+            7i64.into(),
+            "seven".into(),
+            Some(true).into(),
+            Option::<f64>::None.into(),
+            vec![7u8].into(),
+        ]);
+
+        assert_eq!(
+            value,
+            SupportedTypes::Array(vec![
+                SupportedTypes::BigInt(7),
+                SupportedTypes::String("seven".to_string()),
+                SupportedTypes::Boolean(true),
+                SupportedTypes::Null(None),
+                SupportedTypes::Bytes(vec![7]),
+            ])
+        );
+
+        assert_eq!(i64::try_from(SupportedTypes::Integer(7)).unwrap(), 7);
+        assert_eq!(
+            BigNum::try_from(SupportedTypes::from(u128::MAX)).unwrap(),
+            BigNum::from(u128::MAX)
+        );
+
+        match String::try_from(SupportedTypes::Integer(7)) {
+            Err(TypeError::Decoding(e)) => {
+                assert_eq!(e.cause.to_string(), "Expected String. Found Integer")
+            }
+            other => panic!("Unexpected {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,671 @@
+//! RFC 4180 CSV import and export for Tables.
+//!
+//! The first record holds the headers and every other record is a row. Fields
+//! containing the delimiter, a quote or a line break are quoted, with quotes
+//! doubled inside. Records end in CRLF when written and in CRLF or LF when read.
+//!
+//! Reading a field:
+//! - An unquoted empty field is Null. A quoted one (`""`) is an empty String.
+//! - Any other quoted field is a String.
+//! - Unquoted fields are inferred as `true`/`false`, integers (Integer, BigInt
+//!   or HugeInt, whichever is smallest) and Doubles, falling back to String.
+//!   See [`CsvInference`] for doing this per column instead of per cell.
+//!
+//! Writing a cell:
+//! - Null is an empty field, and numbers and Booleans are written as is.
+//!   When the delimiter shows up in them they are quoted, and so come back as
+//!   Strings.
+//! - Strings that would read back as something else are quoted, so they stay
+//!   Strings.
+//! - Bytes, Arrays, NamedValues, Tables and Commands are written in the text
+//!   notation and come back as Strings.
+//! - NaN and infinite Doubles cannot be written.
+
+use std::io::Write;
+
+use crate::commom::{
+    errors::{
+        decoding_error, encoding_error, DecodingError, DecodingErrors, EncodingError,
+        EncodingErrors, TypeResult,
+    },
+    write_bytes,
+};
+
+use super::{BigNum, SupportedTypes};
+
+/// How the types of unquoted fields are chosen when reading.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CsvInference {
+    /// Every field gets the type that fits it best.
+    PerCell,
+    /// Every field in a column gets the type that fits all of them, and the
+    /// column falls back to Strings when they disagree. Nulls do not count.
+    PerColumn,
+    /// Every non-empty field is a String.
+    Strings,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvOptions {
+    /// An ASCII byte other than `"`, `\r` and `\n`.
+    pub delimiter: u8,
+    pub inference: CsvInference,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            inference: CsvInference::PerColumn,
+        }
+    }
+}
+
+impl CsvOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+struct Field {
+    text: String,
+    quoted: bool,
+}
+
+// What an unquoted field looks like, from the narrowest type to the widest.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+enum Kind {
+    Null,
+    Boolean,
+    Integer,
+    BigInt,
+    HugeInt,
+    Double,
+    String,
+}
+
+fn is_number(text: &str, integer: bool) -> bool {
+    let digits = text.strip_prefix(['-', '+']).unwrap_or(text);
+
+    match integer {
+        true => !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()),
+        // Leaves out `inf` and `NaN`, which f64 would also accept.
+        false => {
+            digits.bytes().any(|b| b.is_ascii_digit())
+                && digits
+                    .bytes()
+                    .all(|b| b.is_ascii_digit() || matches!(b, b'.' | b'e' | b'E' | b'-' | b'+'))
+                && text.parse::<f64>().is_ok()
+        }
+    }
+}
+
+fn kind_of(text: &str) -> Kind {
+    match text {
+        "" => Kind::Null,
+        "true" | "false" => Kind::Boolean,
+        _ if is_number(text, true) => {
+            if text.parse::<i32>().is_ok() {
+                Kind::Integer
+            } else if text.parse::<i64>().is_ok() {
+                Kind::BigInt
+            } else {
+                Kind::HugeInt
+            }
+        }
+        _ if is_number(text, false) => Kind::Double,
+        _ => Kind::String,
+    }
+}
+
+fn field_kind(field: &Field) -> Kind {
+    match field.quoted {
+        true => Kind::String,
+        false => kind_of(&field.text),
+    }
+}
+
+// The narrowest kind that holds both, where Booleans and numbers only mix
+// as Strings.
+fn widen(a: Kind, b: Kind) -> Kind {
+    match (a, b) {
+        (Kind::Null, other) | (other, Kind::Null) => other,
+        (Kind::Boolean, Kind::Boolean) => Kind::Boolean,
+        (Kind::Boolean, _) | (_, Kind::Boolean) => Kind::String,
+        _ if a > b => a,
+        _ => b,
+    }
+}
+
+fn to_value(field: Field, kind: Kind) -> SupportedTypes {
+    // The kind was picked from what the text parses as, so these cannot fail.
+    let text = field.text;
+
+    match kind {
+        Kind::Null if !field.quoted => SupportedTypes::Null(None),
+        Kind::Boolean => SupportedTypes::Boolean(text == "true"),
+        Kind::Integer => SupportedTypes::Integer(text.parse().unwrap_or_default()),
+        Kind::BigInt => SupportedTypes::BigInt(text.parse().unwrap_or_default()),
+        Kind::HugeInt => text
+            .parse::<BigNum>()
+            .map(SupportedTypes::HugeInt)
+            .unwrap_or(SupportedTypes::String(text)),
+        Kind::Double => SupportedTypes::Double(text.parse().unwrap_or_default()),
+        Kind::Null | Kind::String => SupportedTypes::String(text),
+    }
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+    delimiter: u8,
+}
+
+impl Parser<'_> {
+    fn error(&self, cause: DecodingErrors) -> DecodingError {
+        DecodingError::new(self.text.as_bytes().to_vec(), "CSV", cause).at(self.position)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.position).copied()
+    }
+
+    fn end_of_record(&mut self) -> bool {
+        match self.peek() {
+            None => true,
+            Some(b'\n') => {
+                self.position += 1;
+                true
+            }
+            Some(b'\r') if self.text.as_bytes().get(self.position + 1) == Some(&b'\n') => {
+                self.position += 2;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn field(&mut self) -> Result<Field, DecodingError> {
+        let start = self.position;
+
+        if self.peek() != Some(b'"') {
+            while !matches!(self.peek(), None | Some(b'\r' | b'\n'))
+                && self.peek() != Some(self.delimiter)
+            {
+                if self.peek() == Some(b'"') {
+                    return Err(self.error(DecodingErrors::InvalidByte(
+                        b'"',
+                        self.position.try_into().unwrap_or(u32::MAX),
+                        vec![self.delimiter, b'\n'],
+                    )));
+                }
+
+                self.position += 1;
+            }
+
+            return Ok(Field {
+                text: self.text[start..self.position].to_string(),
+                quoted: false,
+            });
+        }
+
+        self.position += 1;
+        let mut text = String::new();
+
+        loop {
+            let rest = &self.text[self.position..];
+
+            match rest.find('"') {
+                None => {
+                    self.position = start;
+                    return Err(self.error(DecodingErrors::CouldNotFind(
+                        b'"',
+                        "closing quote".to_string(),
+                    )));
+                }
+                Some(idx) => {
+                    text.push_str(&rest[..idx]);
+                    self.position += idx + 1;
+
+                    // A doubled quote is a quote inside the field.
+                    if self.peek() == Some(b'"') {
+                        text.push('"');
+                        self.position += 1;
+                    } else {
+                        return Ok(Field { text, quoted: true });
+                    }
+                }
+            }
+        }
+    }
+
+    fn record(&mut self) -> Result<Vec<Field>, DecodingError> {
+        let mut fields = vec![self.field()?];
+
+        loop {
+            if self.end_of_record() {
+                return Ok(fields);
+            }
+
+            match self.peek() {
+                Some(byte) if byte == self.delimiter => {
+                    self.position += 1;
+                    fields.push(self.field()?);
+                }
+                Some(byte) => {
+                    return Err(self.error(DecodingErrors::InvalidByte(
+                        byte,
+                        self.position.try_into().unwrap_or(u32::MAX),
+                        vec![self.delimiter, b'\n'],
+                    )))
+                }
+                None => return Ok(fields),
+            }
+        }
+    }
+}
+
+// Anything else could split a UTF-8 character or a quoted field.
+fn is_valid_delimiter(delimiter: u8) -> bool {
+    delimiter.is_ascii() && !matches!(delimiter, b'"' | b'\r' | b'\n')
+}
+
+fn delimiter_message(delimiter: u8) -> String {
+    format!("{:?} cannot be a CSV delimiter", delimiter as char)
+}
+
+pub fn from_csv(text: &str) -> TypeResult<SupportedTypes> {
+    from_csv_with(text, &CsvOptions::default())
+}
+
+/// Reads `text` as a Table, with the first record as its headers.
+pub fn from_csv_with(text: &str, options: &CsvOptions) -> TypeResult<SupportedTypes> {
+    let mut parser = Parser {
+        text,
+        position: 0,
+        delimiter: options.delimiter,
+    };
+
+    if !is_valid_delimiter(options.delimiter) {
+        return Err(decoding_error(parser.error(DecodingErrors::CantFitValues(
+            delimiter_message(options.delimiter),
+        ))));
+    }
+
+    let headers: Vec<String> = parser
+        .record()
+        .map_err(decoding_error)?
+        .into_iter()
+        .map(|field| field.text)
+        .collect();
+
+    if headers == [""] {
+        return Err(decoding_error(parser.error(DecodingErrors::CantFitValues(
+            "Table headers cannot be empty".to_string(),
+        ))));
+    }
+
+    let mut records = vec![];
+
+    while parser.peek().is_some() {
+        let start = parser.position;
+        let record = parser.record().map_err(decoding_error)?;
+
+        if record.len() != headers.len() {
+            parser.position = start;
+
+            let cause = match record.len() > headers.len() {
+                true => DecodingErrors::TooMuch,
+                false => DecodingErrors::NotEnough,
+            };
+
+            return Err(decoding_error(parser.error(cause(
+                "Fields".to_string(),
+                headers.len().try_into().unwrap_or(u32::MAX),
+                record.len().try_into().unwrap_or(u32::MAX),
+            ))));
+        }
+
+        records.push(record);
+    }
+
+    let kinds: Vec<Option<Kind>> = match options.inference {
+        CsvInference::PerCell => vec![None; headers.len()],
+        CsvInference::PerColumn => (0..headers.len())
+            .map(|column| {
+                Some(records.iter().fold(Kind::Null, |kind, record| {
+                    widen(kind, field_kind(&record[column]))
+                }))
+            })
+            .collect(),
+        CsvInference::Strings => vec![Some(Kind::String); headers.len()],
+    };
+
+    let rows = records
+        .into_iter()
+        .map(|record| {
+            record
+                .into_iter()
+                .zip(&kinds)
+                .map(|(field, kind)| {
+                    let kind = match (field.quoted || field.text.is_empty(), kind) {
+                        (true, _) | (false, None) => field_kind(&field),
+                        (false, Some(kind)) => *kind,
+                    };
+
+                    to_value(field, kind)
+                })
+                .collect()
+        })
+        .collect();
+
+    Ok(SupportedTypes::Table(headers, rows))
+}
+
+// `as_string` also quotes text that would otherwise read back as another type.
+fn write_field<W: Write>(
+    writer: &mut W,
+    text: &str,
+    delimiter: u8,
+    as_string: bool,
+) -> TypeResult<()> {
+    let needs_quotes = (as_string && kind_of(text) != Kind::String)
+        || text
+            .bytes()
+            .any(|b| matches!(b, b'"' | b'\r' | b'\n') || b == delimiter);
+
+    match needs_quotes {
+        true => {
+            write_bytes(writer, b"\"")?;
+            write_bytes(writer, text.replace('"', "\"\"").as_bytes())?;
+            write_bytes(writer, b"\"")
+        }
+        false => write_bytes(writer, text.as_bytes()),
+    }
+}
+
+fn write_cell<W: Write>(writer: &mut W, cell: &SupportedTypes, delimiter: u8) -> TypeResult<()> {
+    match cell {
+        SupportedTypes::Null(_) => Ok(()),
+        SupportedTypes::Boolean(b) => write_field(writer, &b.to_string(), delimiter, false),
+        SupportedTypes::Integer(int) => write_field(writer, &int.to_string(), delimiter, false),
+        SupportedTypes::BigInt(big) => write_field(writer, &big.to_string(), delimiter, false),
+        SupportedTypes::HugeInt(huge) => write_field(writer, &huge.to_string(), delimiter, false),
+        // Debug keeps the decimal point, so the field reads back as a Double.
+        SupportedTypes::Double(double) => {
+            write_field(writer, &format!("{:?}", double), delimiter, false)
+        }
+        SupportedTypes::String(string) => write_field(writer, string, delimiter, true),
+        other => write_field(writer, &other.to_string(), delimiter, true),
+    }
+}
+
+pub fn write_csv<W: Write>(
+    headers: &[String],
+    rows: &[Vec<SupportedTypes>],
+    writer: &mut W,
+) -> TypeResult<()> {
+    write_csv_with(headers, rows, writer, &CsvOptions::default())
+}
+
+/// Writes a Table as CSV, headers first.
+pub fn write_csv_with<W: Write>(
+    headers: &[String],
+    rows: &[Vec<SupportedTypes>],
+    writer: &mut W,
+    options: &CsvOptions,
+) -> TypeResult<()> {
+    if !is_valid_delimiter(options.delimiter) {
+        return Err(encoding_error(EncodingError::new(
+            &format!("{:?}", options.delimiter as char),
+            "CSV",
+            EncodingErrors::InvalidValue(delimiter_message(options.delimiter)),
+        )));
+    }
+
+    if headers.is_empty() {
+        return Err(encoding_error(EncodingError::new(
+            "[]",
+            "CSV",
+            EncodingErrors::InvalidValue("Table headers cannot be empty".to_string()),
+        )));
+    }
+
+    // Checked up front so a bad cell never leaves half a file in the writer.
+    if let Some(row) = rows.iter().find(|row| row.len() != headers.len()) {
+        return Err(encoding_error(EncodingError::new(
+            "(Vec<String>, Vec<Vec<SupportedTypes>>)",
+            "CSV",
+            EncodingErrors::TableMisfit(headers.len(), row.len()),
+        )));
+    }
+
+    if let Some(double) = rows.iter().flatten().find_map(|cell| match cell {
+        SupportedTypes::Double(double) if !double.is_finite() => Some(double),
+        _ => None,
+    }) {
+        return Err(encoding_error(EncodingError::new(
+            &double.to_string(),
+            "CSV",
+            EncodingErrors::InvalidValue("CSV has no NaN or infinity".to_string()),
+        )));
+    }
+
+    for (idx, header) in headers.iter().enumerate() {
+        if idx > 0 {
+            write_bytes(writer, &[options.delimiter])?;
+        }
+
+        write_field(writer, header, options.delimiter, true)?;
+    }
+
+    write_bytes(writer, b"\r\n")?;
+
+    for row in rows {
+        for (idx, cell) in row.iter().enumerate() {
+            if idx > 0 {
+                write_bytes(writer, &[options.delimiter])?;
+            }
+
+            write_cell(writer, cell, options.delimiter)?;
+        }
+
+        write_bytes(writer, b"\r\n")?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        commom::errors::{DecodingErrors, TypeError},
+        types::SupportedTypes,
+    };
+
+    use super::{from_csv, from_csv_with, write_csv, write_csv_with, CsvInference, CsvOptions};
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn round_trips_through_a_writer() {
+        let headers = strings(&["id", "name, full", "note"]);
+        let rows = vec![
+            vec![
+                SupportedTypes::Integer(1),
+                SupportedTypes::String("Ann \"the\" first".to_string()),
+                SupportedTypes::String("two\r\nlines".to_string()),
+            ],
+            vec![
+                SupportedTypes::Integer(2),
+                SupportedTypes::String("".to_string()),
+                SupportedTypes::Null(None),
+            ],
+            vec![
+                SupportedTypes::Integer(3),
+                SupportedTypes::String("42".to_string()),
+                SupportedTypes::String("true".to_string()),
+            ],
+        ];
+
+        let mut written = vec![];
+        write_csv(&headers, &rows, &mut written).unwrap();
+
+        let text = String::from_utf8(written).unwrap();
+        assert_eq!(
+            text,
+            "id,\"name, full\",note\r\n\
+            1,\"Ann \"\"the\"\" first\",\"two\r\nlines\"\r\n\
+            2,\"\",\r\n\
+            3,\"42\",\"true\"\r\n"
+        );
+
+        assert_eq!(
+            from_csv(&text).unwrap(),
+            SupportedTypes::Table(headers, rows)
+        );
+    }
+
+    #[test]
+    fn inference_per_column_and_per_cell() {
+        let text = "a;b;c\n1;x;true\n3000000000;2;\n0.5;;false\n";
+
+        let mut options = CsvOptions::new();
+        options.delimiter = b';';
+
+        assert_eq!(
+            from_csv_with(text, &options).unwrap(),
+            SupportedTypes::Table(
+                strings(&["a", "b", "c"]),
+                vec![
+                    vec![
+                        SupportedTypes::Double(1.0),
+                        SupportedTypes::String("x".to_string()),
+                        SupportedTypes::Boolean(true),
+                    ],
+                    vec![
+                        SupportedTypes::Double(3e9),
+                        SupportedTypes::String("2".to_string()),
+                        SupportedTypes::Null(None),
+                    ],
+                    vec![
+                        SupportedTypes::Double(0.5),
+                        SupportedTypes::Null(None),
+                        SupportedTypes::Boolean(false),
+                    ],
+                ]
+            )
+        );
+
+        options.inference = CsvInference::PerCell;
+        let SupportedTypes::Table(_, rows) = from_csv_with(text, &options).unwrap() else {
+            panic!("Not a table");
+        };
+        assert_eq!(rows[0][0], SupportedTypes::Integer(1));
+        assert_eq!(rows[1][0], SupportedTypes::BigInt(3000000000));
+        assert_eq!(rows[1][1], SupportedTypes::Integer(2));
+
+        options.inference = CsvInference::Strings;
+        let SupportedTypes::Table(_, rows) = from_csv_with(text, &options).unwrap() else {
+            panic!("Not a table");
+        };
+        assert_eq!(rows[0][2], SupportedTypes::String("true".to_string()));
+        assert_eq!(rows[1][2], SupportedTypes::Null(None));
+    }
+
+    #[test]
+    fn nested_cells_use_the_notation() {
+        let mut options = CsvOptions::new();
+        options.delimiter = b'\t';
+
+        let mut written = vec![];
+        write_csv_with(
+            &strings(&["v"]),
+            &[vec![SupportedTypes::Array(vec![
+                SupportedTypes::Integer(1),
+                SupportedTypes::Bytes(vec![255]),
+            ])]],
+            &mut written,
+            &options,
+        )
+        .unwrap();
+
+        assert_eq!(written, b"v\r\n\"[1, x\"\"ff\"\"]\"\r\n");
+
+        assert!(write_csv(
+            &strings(&["v"]),
+            &[vec![SupportedTypes::Double(f64::INFINITY)]],
+            &mut vec![]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn malformed_input_is_located() {
+        for (text, offset) in [
+            ("a,b\n1,2,3\n", 4),
+            ("a,b\n1\n", 4),
+            ("a\n\"open\n", 2),
+            ("a\n\"x\"y\n", 5),
+            ("a\nx\"y\n", 3),
+        ] {
+            match from_csv(text) {
+                Err(TypeError::Decoding(e)) => assert_eq!(e.offset, Some(offset), "{:?}", text),
+                other => panic!("Unexpected {:?} for {:?}", other, text),
+            }
+        }
+
+        match from_csv("") {
+            Err(TypeError::Decoding(e)) => {
+                assert!(matches!(*e.cause, DecodingErrors::CantFitValues(_)))
+            }
+            other => panic!("Unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn numbers_holding_the_delimiter_are_quoted() {
+        let headers = strings(&["a", "b", "c"]);
+        let rows = vec![vec![
+            SupportedTypes::Double(-1.5),
+            SupportedTypes::Integer(-12),
+            SupportedTypes::Boolean(true),
+        ]];
+
+        for delimiter in [b'.', b'-', b'1', b'e'] {
+            let mut options = CsvOptions::new();
+            options.delimiter = delimiter;
+            options.inference = CsvInference::PerCell;
+
+            let mut written = vec![];
+            write_csv_with(&headers, &rows, &mut written, &options).unwrap();
+
+            let text = String::from_utf8(written).unwrap();
+            let expected: Vec<SupportedTypes> = rows[0]
+                .iter()
+                .map(|cell| match cell.to_string() {
+                    text if text.contains(delimiter as char) => SupportedTypes::String(text),
+                    _ => cell.clone(),
+                })
+                .collect();
+
+            assert_eq!(
+                from_csv_with(&text, &options).unwrap(),
+                SupportedTypes::Table(headers.clone(), vec![expected]),
+                "{:?}",
+                text
+            );
+        }
+
+        for delimiter in [b'"', b'\n', b'\r', 0xe9] {
+            let mut options = CsvOptions::new();
+            options.delimiter = delimiter;
+
+            let mut written = vec![];
+            assert!(write_csv_with(&headers, &rows, &mut written, &options).is_err());
+            assert!(written.is_empty());
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use crate::commom::errors::{decoding_error, TypeResult};
+use crate::commom::{decode_limits::check_limit, DecodeLimits, FrameScanner, Uint8Buff};
+use crate::types::{infer_buffer_with, SupportedTypes};
+
+#[derive(Debug, PartialEq)]
+pub enum Decoded {
+    Value(SupportedTypes),
+    NeedMoreData,
+}
+
+/// Incremental decoder for byte streams that arrive in arbitrary chunks.
+///
+/// Bytes are buffered with [`Decoder::feed`] and complete frames are handed
+/// out by [`Decoder::decode_next`]. A frame that is complete but does not
+/// decode is dropped, so the following frames can still be read. When no
+/// frame boundary can ever be found the bytes are kept and every call keeps
+/// failing until [`Decoder::clear`] is called; iterating reports that error
+/// once and clears the buffer.
+///
+/// Each byte is scanned once however it is chunked: the scan resumes where
+/// the previous call stopped, and consumed frames only move a read cursor.
+/// The buffer is compacted when the consumed part outgrows the rest.
+#[derive(Debug, Default)]
+pub struct Decoder {
+    buffer: Uint8Buff,
+    start: usize,
+    scanner: FrameScanner,
+    limits: DecodeLimits,
+}
+
+impl Decoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decodes with `limits` instead of the defaults. Bytes buffered while
+    /// waiting for a frame to end also count against `max_message_size`.
+    pub fn with_limits(limits: DecodeLimits) -> Self {
+        Self {
+            limits,
+            ..Self::default()
+        }
+    }
+
+    pub fn feed(&mut self, bytes: &[u8]) {
+        if self.start > 0 && self.start >= self.buffered() {
+            self.buffer.drain(..self.start);
+            self.start = 0;
+        }
+
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    pub fn buffered(&self) -> usize {
+        self.buffer.len() - self.start
+    }
+
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+        self.start = 0;
+        self.scanner.reset();
+    }
+
+    fn scan(&mut self) -> TypeResult<Option<usize>> {
+        self.scanner
+            .resume(&self.buffer[self.start..])
+            .map_err(decoding_error)
+    }
+
+    pub fn decode_next(&mut self) -> TypeResult<Decoded> {
+        let frame_len = match self.scan()? {
+            Some(len) => len,
+            None => {
+                let buffered = self.buffered();
+
+                if buffered > self.limits.max_message_size {
+                    self.clear();
+
+                    check_limit(
+                        &[],
+                        "Decoder",
+                        "Message size",
+                        self.limits.max_message_size,
+                        buffered,
+                    )
+                    .map_err(decoding_error)?;
+                }
+
+                return Ok(Decoded::NeedMoreData);
+            }
+        };
+
+        let frame_start = self.start;
+        self.start += frame_len;
+
+        let frame = &self.buffer[frame_start..self.start];
+        let value = infer_buffer_with(frame, &self.limits);
+
+        if self.start == self.buffer.len() {
+            self.clear();
+        }
+
+        Ok(Decoded::Value(value?))
+    }
+}
+
+impl Iterator for Decoder {
+    type Item = TypeResult<SupportedTypes>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.decode_next() {
+            Ok(Decoded::Value(value)) => Some(Ok(value)),
+            Ok(Decoded::NeedMoreData) => None,
+            Err(e) => {
+                // Broken framing would fail forever, so the iterator reports
+                // it once and drops the bytes instead of looping.
+                if self.scan().is_err() {
+                    self.clear();
+                }
+
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        commom::{
+            delimiters::{BUFFER_END, END_RECORD, START_RECORD},
+            DecodeLimits,
+        },
+        types::{encode_value, Decoded, Decoder, SupportedTypes},
+    };
+
+    fn sample_values() -> Vec<SupportedTypes> {
+        vec![
+            SupportedTypes::Integer(-12),
+            SupportedTypes::String("chunked\u{1e}".to_string()),
+            SupportedTypes::Array(vec![
+                SupportedTypes::Null(None),
+                SupportedTypes::Array(vec![SupportedTypes::Bytes(vec![0x1e, 0x1d])]),
+            ]),
+            SupportedTypes::Table(
+                vec!["a".to_string()],
+                vec![vec![SupportedTypes::Double(0.25)]],
+            ),
+        ]
+    }
+
+    #[test]
+    fn byte_by_byte() {
+        let values = sample_values();
+
+        let mut stream = vec![];
+        for value in values.iter() {
+            stream.extend(encode_value(value.clone()).unwrap());
+        }
+
+        let mut decoder = Decoder::new();
+        let mut decoded = vec![];
+
+        for byte in stream.iter() {
+            decoder.feed(&[*byte]);
+
+            while let Decoded::Value(value) = decoder.decode_next().unwrap() {
+                decoded.push(value);
+            }
+        }
+
+        assert_eq!(decoded, values);
+        assert_eq!(decoder.buffered(), 0);
+    }
+
+    #[test]
+    fn many_frames_in_one_chunk() {
+        let values = sample_values();
+
+        let mut decoder = Decoder::new();
+
+        for value in values.iter() {
+            decoder.feed(&encode_value(value.clone()).unwrap());
+        }
+
+        let decoded: Vec<SupportedTypes> = decoder.by_ref().map(|v| v.unwrap()).collect();
+
+        assert_eq!(decoded, values);
+        assert_eq!(decoder.decode_next().unwrap(), Decoded::NeedMoreData);
+    }
+
+    #[test]
+    fn malformed_input() {
+        let mut decoder = Decoder::new();
+
+        decoder.feed(&[b'#', START_RECORD, 7, END_RECORD, BUFFER_END]);
+        decoder.feed(&encode_value(SupportedTypes::Boolean(true)).unwrap());
+
+        assert!(decoder.decode_next().is_err());
+        assert_eq!(
+            decoder.decode_next().unwrap(),
+            Decoded::Value(SupportedTypes::Boolean(true))
+        );
+
+        decoder.feed(b"?");
+
+        assert!(decoder.decode_next().is_err());
+
+        decoder.clear();
+
+        assert_eq!(decoder.decode_next().unwrap(), Decoded::NeedMoreData);
+    }
+
+    #[test]
+    fn iteration_stops_on_broken_framing() {
+        let mut decoder = Decoder::new();
+
+        decoder.feed(&[b'?', START_RECORD, END_RECORD, BUFFER_END]);
+
+        assert!(decoder.next().unwrap().is_err());
+        assert!(decoder.next().is_none());
+        assert_eq!(decoder.buffered(), 0);
+    }
+
+    #[test]
+    fn unfinished_frames_cannot_grow_forever() {
+        let mut limits = DecodeLimits::new();
+        limits.max_message_size = 16;
+
+        let mut decoder = Decoder::with_limits(limits);
+
+        decoder.feed(&[b'+', START_RECORD]);
+        assert_eq!(decoder.decode_next().unwrap(), Decoded::NeedMoreData);
+
+        decoder.feed(&[b'a'; 32]);
+        assert!(decoder.decode_next().is_err());
+        assert_eq!(decoder.buffered(), 0);
+    }
+
+    #[test]
+    fn large_frame_in_small_chunks() {
+        let value = SupportedTypes::Bytes((0..=255).cycle().take(3 << 20).collect());
+        let mut stream = encode_value(value.clone()).unwrap();
+        stream.extend(encode_value(SupportedTypes::Integer(7)).unwrap());
+
+        let mut decoder = Decoder::new();
+        let mut decoded = vec![];
+
+        for chunk in stream.chunks(1460) {
+            decoder.feed(chunk);
+
+            while let Decoded::Value(value) = decoder.decode_next().unwrap() {
+                decoded.push(value);
+            }
+        }
+
+        assert_eq!(decoded, vec![value, SupportedTypes::Integer(7)]);
+        assert_eq!(decoder.buffered(), 0);
+    }
+}
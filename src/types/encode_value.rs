@@ -0,0 +1,139 @@
+use std::io::Write;
+
+use crate::commom::diagnostics::{emit, Event};
+use crate::commom::errors::TypeResult;
+use crate::commom::{delimiters::BUFFER_END, write_bytes, ByteCounter, Codec, Uint8Buff};
+use crate::types::SupportedTypes;
+
+use super::composite::{
+    command::write_command, named_value::write_named_value, table::write_table,
+};
+use super::{registry, Array, BigInt, Boolean, Bytes, Double, HugeInt, Integer, Null};
+
+pub fn encode_value(value: SupportedTypes) -> TypeResult<Uint8Buff> {
+    let mut buff: Uint8Buff = vec![];
+    encode_value_to(&value, &mut buff)?;
+    Ok(buff)
+}
+
+pub fn encode_value_to<W: Write>(value: &SupportedTypes, writer: &mut W) -> TypeResult<()> {
+    write_nested_value(value, writer)?;
+    write_bytes(writer, &[BUFFER_END])
+}
+
+pub fn encoded_len(value: &SupportedTypes) -> TypeResult<usize> {
+    let mut counter = ByteCounter::default();
+    encode_value_to(value, &mut counter)?;
+    Ok(counter.count())
+}
+
+/// Writes `value` the way composites embed it: without the trailing `BUFFER_END`.
+pub(crate) fn write_nested_value<W: Write>(
+    value: &SupportedTypes,
+    writer: &mut W,
+) -> TypeResult<()> {
+    emit(Event::ValueWritten {
+        name: value.get_name(),
+    });
+
+    match value {
+        SupportedTypes::BigInt(bi) => BigInt::write_value(bi, writer),
+        SupportedTypes::Boolean(bol) => Boolean::write_value(bol, writer),
+        SupportedTypes::Double(db) => Double::write_value(db, writer),
+        SupportedTypes::Integer(int) => Integer::write_value(int, writer),
+        SupportedTypes::Null(null_val) => Null::write_value(null_val, writer),
+        SupportedTypes::String(string) => super::String::write_value(string, writer),
+        SupportedTypes::HugeInt(huge) => HugeInt::write_value(huge, writer),
+        SupportedTypes::Bytes(bytes) => Bytes::write_value(bytes, writer),
+        SupportedTypes::Array(values) => Array::write_value(values, writer),
+        SupportedTypes::NamedValue(name, value) => write_named_value(name, value, writer),
+        SupportedTypes::Table(headers, rows) => write_table(headers, rows, writer),
+        SupportedTypes::Command(name, args) => write_command(name, args, writer),
+        SupportedTypes::Extension(first_byte, value) => {
+            registry::current(|r| r.write_extension(*first_byte, value, writer))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        commom::{Codec, Uint8Buff},
+        types::{encode_value, encode_value_to, encoded_len, BigNum, Integer, SupportedTypes},
+    };
+
+    fn nested_value() -> SupportedTypes {
+        SupportedTypes::NamedValue(
+            "report".to_string(),
+            Box::new(SupportedTypes::Table(
+                vec!["id".to_string(), "payload".to_string()],
+                vec![
+                    vec![
+                        SupportedTypes::HugeInt(BigNum::from(u128::MAX)),
+                        SupportedTypes::Array(vec![
+                            SupportedTypes::Double(-1.5e-7),
+                            SupportedTypes::Bytes(vec![0x1c, 0x1e, 0xff]),
+                        ]),
+                    ],
+                    vec![
+                        SupportedTypes::Integer(-42),
+                        SupportedTypes::Command("GET".to_string(), vec!["k".to_string()]),
+                    ],
+                ],
+            )),
+        )
+    }
+
+    #[test]
+    fn encode_to_matches_encode() {
+        let value = nested_value();
+
+        let mut written: Uint8Buff = vec![];
+        encode_value_to(&value, &mut written).unwrap();
+
+        assert_eq!(written, encode_value(value).unwrap());
+    }
+
+    #[test]
+    fn encoded_len_matches_encoding() {
+        let values = [
+            nested_value(),
+            SupportedTypes::String("\u{1b}\u{1f}".to_string()),
+            SupportedTypes::Null(None),
+            SupportedTypes::Array(vec![]),
+        ];
+
+        for value in values {
+            assert_eq!(
+                encoded_len(&value).unwrap(),
+                encode_value(value).unwrap().len()
+            );
+        }
+
+        assert_eq!(
+            Integer::encoded_len(&i32::MIN).unwrap(),
+            Integer::encode(&i32::MIN).unwrap().len()
+        );
+    }
+
+    #[test]
+    fn misfit_table_writes_nothing() {
+        let table = SupportedTypes::Table(
+            vec!["a".to_string()],
+            vec![vec![SupportedTypes::Integer(1)], vec![]],
+        );
+
+        let mut written: Uint8Buff = vec![];
+
+        assert!(encode_value_to(&table, &mut written).is_err());
+        assert!(written.is_empty());
+    }
+
+    #[test]
+    fn writer_errors_are_reported() {
+        let mut full = [0u8; 4];
+        let mut writer: &mut [u8] = &mut full;
+
+        assert!(encode_value_to(&nested_value(), &mut writer).is_err());
+    }
+}
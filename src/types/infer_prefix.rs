@@ -0,0 +1,143 @@
+use crate::commom::delimiters::BUFFER_END;
+use crate::commom::errors::{decoding_error, DecodingError, DecodingErrors, TypeResult};
+use crate::commom::{scan_frame, DecodeLimits};
+use crate::types::{infer_buffer_with, SupportedTypes};
+
+/// Decodes the first frame of `buff` and returns it with the number of bytes
+/// it took, so the caller can continue from `&buff[consumed..]`.
+pub fn infer_prefix(buff: &[u8]) -> TypeResult<(SupportedTypes, usize)> {
+    infer_prefix_with(buff, &DecodeLimits::default())
+}
+
+pub fn infer_prefix_with(
+    buff: &[u8],
+    limits: &DecodeLimits,
+) -> TypeResult<(SupportedTypes, usize)> {
+    match scan_frame(buff).map_err(decoding_error)? {
+        Some(consumed) => Ok((infer_buffer_with(&buff[..consumed], limits)?, consumed)),
+        None => Err(decoding_error(DecodingError::new(
+            buff.to_vec(),
+            "Infer",
+            DecodingErrors::CouldNotFind(BUFFER_END, "BUFFER_END".to_string()),
+        ))),
+    }
+}
+
+/// Iterator over a buffer holding many concatenated frames.
+///
+/// A frame that fails to decode is reported and skipped. When the frame
+/// boundaries themselves are broken the error is reported once and the
+/// iteration stops.
+pub struct Frames<'a> {
+    buff: &'a [u8],
+    offset: usize,
+    limits: DecodeLimits,
+}
+
+pub fn frames(buff: &[u8]) -> Frames<'_> {
+    frames_with(buff, DecodeLimits::default())
+}
+
+pub fn frames_with(buff: &[u8], limits: DecodeLimits) -> Frames<'_> {
+    Frames {
+        buff,
+        offset: 0,
+        limits,
+    }
+}
+
+impl Frames<'_> {
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl Iterator for Frames<'_> {
+    type Item = TypeResult<SupportedTypes>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = &self.buff[self.offset..];
+
+        if rest.is_empty() {
+            return None;
+        }
+
+        let consumed = match scan_frame(rest) {
+            Ok(Some(consumed)) => consumed,
+            Ok(None) | Err(_) => {
+                self.offset = self.buff.len();
+                return Some(infer_prefix_with(rest, &self.limits).map(|(value, _)| value));
+            }
+        };
+
+        self.offset += consumed;
+
+        Some(infer_buffer_with(&rest[..consumed], &self.limits))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        commom::delimiters::{BUFFER_END, END_RECORD, START_RECORD},
+        types::{encode_value, frames, infer_prefix, SupportedTypes},
+    };
+
+    #[test]
+    fn reports_consumed_length() {
+        let first = encode_value(SupportedTypes::Integer(1)).unwrap();
+        let second = encode_value(SupportedTypes::String("two".to_string())).unwrap();
+
+        let mut buff = first.clone();
+        buff.extend(second.iter());
+
+        let (value, consumed) = infer_prefix(&buff).unwrap();
+
+        assert_eq!(value, SupportedTypes::Integer(1));
+        assert_eq!(consumed, first.len());
+
+        let (value, consumed) = infer_prefix(&buff[consumed..]).unwrap();
+
+        assert_eq!(value, SupportedTypes::String("two".to_string()));
+        assert_eq!(consumed, second.len());
+    }
+
+    #[test]
+    fn walks_concatenated_frames() {
+        let values = vec![
+            SupportedTypes::Boolean(true),
+            SupportedTypes::Array(vec![SupportedTypes::Integer(3), SupportedTypes::Null(None)]),
+            SupportedTypes::NamedValue(
+                "nested".to_string(),
+                Box::new(SupportedTypes::Table(
+                    vec!["x".to_string()],
+                    vec![vec![SupportedTypes::Double(-1.5)]],
+                )),
+            ),
+            SupportedTypes::Bytes(vec![]),
+        ];
+
+        let mut buff = vec![];
+        for value in values.iter() {
+            buff.extend(encode_value(value.clone()).unwrap());
+        }
+
+        let mut iter = frames(&buff);
+        let decoded: Vec<SupportedTypes> = iter.by_ref().map(|v| v.unwrap()).collect();
+
+        assert_eq!(decoded, values);
+        assert_eq!(iter.offset(), buff.len());
+    }
+
+    #[test]
+    fn truncated_and_invalid_frames() {
+        let mut buff = vec![b'#', START_RECORD, 9, END_RECORD, BUFFER_END];
+        buff.extend(encode_value(SupportedTypes::Integer(5)).unwrap());
+        buff.extend([b':', b'+', START_RECORD, 1]);
+
+        let results: Vec<bool> = frames(&buff).map(|r| r.is_ok()).collect();
+
+        assert_eq!(results, vec![false, true, false]);
+        assert!(infer_prefix(&buff[..3]).is_err());
+    }
+}
@@ -0,0 +1,831 @@
+//! Conversion between JSON text and [`SupportedTypes`], without dependencies.
+//!
+//! | coproto    | JSON                                                    |
+//! |------------|---------------------------------------------------------|
+//! | Null       | `null`                                                  |
+//! | Boolean    | `true`, `false`                                         |
+//! | Integer    | number                                                  |
+//! | BigInt     | number                                                  |
+//! | HugeInt    | number, with every digit                                |
+//! | Double     | number, always with a `.` or an exponent                |
+//! | String     | string                                                  |
+//! | Bytes      | array of numbers from 0 to 255                          |
+//! | Array      | array                                                   |
+//! | NamedValue | object with a single key                                |
+//! | Table      | array of objects, one per row, keyed by the headers     |
+//! | Command    | `{"command": name, "args": [...]}`                      |
+//! | Extension  | its decoded value                                       |
+//!
+//! Reading JSON back:
+//! - Numbers with a fraction or an exponent become Doubles. Integers follow
+//!   [`JsonOptions::integers`], except `-0`, which becomes a Double to keep
+//!   its sign. Numbers past the f64 range are rejected.
+//! - A non-empty array of objects that all have the same keys, in the same
+//!   order, becomes a Table. Turn [`JsonOptions::tables`] off to keep it an
+//!   Array of NamedValues instead.
+//! - Any other object becomes a NamedValue when it has one key, and an Array
+//!   of NamedValues otherwise.
+//!
+//! So Null, Boolean, String, Double and NamedValue round-trip. Integer, BigInt
+//! and HugeInt come back as whatever the options pick, which keeps a HugeInt
+//! only past the i64 range. Arrays and Tables round-trip when their contents
+//! do, but an Array of objects sharing their keys comes back as a Table and a
+//! Table without rows as an empty Array. Bytes come back as an Array of
+//! Integers, Commands as an Array of NamedValues and Extensions as the value
+//! they hold. JSON has no NaN or
+//! infinity, so those Doubles cannot be converted.
+
+use std::fmt::Write;
+
+use crate::commom::errors::{
+    decoding_error, encoding_error, DecodingError, DecodingErrors, EncodingError, EncodingErrors,
+    TypeError, TypeResult,
+};
+
+use super::{BigNum, SupportedTypes};
+
+/// How JSON integers are turned into coproto numbers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JsonIntegers {
+    /// The smallest of Integer, BigInt and HugeInt that holds the value.
+    Smallest,
+    /// BigInt, or HugeInt past the i64 range.
+    BigInt,
+    /// Double, even when that loses precision.
+    Double,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonOptions {
+    pub integers: JsonIntegers,
+    /// When false, integers past the i64 range become Doubles instead of
+    /// HugeInts.
+    pub huge_integers: bool,
+    /// Whether uniform arrays of objects become Tables.
+    pub tables: bool,
+    /// How many arrays and objects may be nested inside each other.
+    pub max_depth: usize,
+}
+
+impl Default for JsonOptions {
+    fn default() -> Self {
+        Self {
+            integers: JsonIntegers::Smallest,
+            huge_integers: true,
+            tables: true,
+            max_depth: 64,
+        }
+    }
+}
+
+impl JsonOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+// Parsed JSON, kept until arrays can be checked for the Table shape.
+enum Json {
+    Null,
+    Bool(bool),
+    Number(SupportedTypes),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+    options: &'a JsonOptions,
+}
+
+impl Parser<'_> {
+    fn error(&self, cause: DecodingErrors) -> TypeError {
+        decoding_error(
+            DecodingError::new(self.text.as_bytes().to_vec(), "JSON", cause).at(self.position),
+        )
+    }
+
+    fn unexpected(&self, expected: &str) -> TypeError {
+        match self.text.as_bytes().get(self.position) {
+            Some(byte) => self.error(DecodingErrors::InvalidByte(
+                *byte,
+                self.position.try_into().unwrap_or(u32::MAX),
+                expected.bytes().collect(),
+            )),
+            None => self.error(DecodingErrors::NotEnough(
+                format!("Characters, expected one of {:?}", expected),
+                1,
+                0,
+            )),
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.position += 1;
+        }
+    }
+
+    fn eat(&mut self, expected: u8) -> TypeResult<()> {
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some(byte) if byte == expected => {
+                self.position += 1;
+                Ok(())
+            }
+            _ => Err(self.unexpected(&(expected as char).to_string())),
+        }
+    }
+
+    fn word(&mut self, word: &str, json: Json) -> TypeResult<Json> {
+        match self.text[self.position..].starts_with(word) {
+            true => {
+                self.position += word.len();
+                Ok(json)
+            }
+            false => Err(self.unexpected(&word[..1])),
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> TypeResult<Json> {
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some(b'n') => self.word("null", Json::Null),
+            Some(b't') => self.word("true", Json::Bool(true)),
+            Some(b'f') => self.word("false", Json::Bool(false)),
+            Some(b'"') => Ok(Json::String(self.string()?)),
+            Some(b'[' | b'{') if depth >= self.options.max_depth => {
+                Err(self.error(DecodingErrors::LimitExceeded {
+                    what: "JSON depth".to_string(),
+                    limit: self.options.max_depth,
+                    found: depth + 1,
+                }))
+            }
+            Some(b'[') => {
+                self.position += 1;
+                Ok(Json::Array(self.list(b']', |p| p.value(depth + 1))?))
+            }
+            Some(b'{') => {
+                self.position += 1;
+                Ok(Json::Object(self.list(b'}', |p| {
+                    let key = p.string()?;
+                    p.eat(b':')?;
+                    Ok((key, p.value(depth + 1)?))
+                })?))
+            }
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            _ => Err(self.unexpected("nft\"[{-0123456789")),
+        }
+    }
+
+    fn list<T>(
+        &mut self,
+        close: u8,
+        mut item: impl FnMut(&mut Self) -> TypeResult<T>,
+    ) -> TypeResult<Vec<T>> {
+        let mut items = vec![];
+
+        self.skip_whitespace();
+
+        if self.peek() == Some(close) {
+            self.position += 1;
+            return Ok(items);
+        }
+
+        loop {
+            self.skip_whitespace();
+            items.push(item(self)?);
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(byte) if byte == close => {
+                    self.position += 1;
+                    return Ok(items);
+                }
+                _ => return Err(self.unexpected(&format!(",{}", close as char))),
+            }
+        }
+    }
+
+    // At least one digit, or an error pointing at what came instead.
+    fn digits(&mut self) -> TypeResult<()> {
+        if !matches!(self.peek(), Some(b'0'..=b'9')) {
+            return Err(self.unexpected("0123456789"));
+        }
+
+        while matches!(self.peek(), Some(b'0'..=b'9')) {
+            self.position += 1;
+        }
+
+        Ok(())
+    }
+
+    // RFC 8259: `-? (0 | [1-9][0-9]*) (.[0-9]+)? ([eE][+-]?[0-9]+)?`.
+    fn number(&mut self) -> TypeResult<Json> {
+        let start = self.position;
+
+        if self.peek() == Some(b'-') {
+            self.position += 1;
+        }
+
+        match self.peek() {
+            Some(b'0') => self.position += 1,
+            _ => self.digits()?,
+        }
+
+        if self.peek() == Some(b'.') {
+            self.position += 1;
+            self.digits()?;
+        }
+
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            self.position += 1;
+
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.position += 1;
+            }
+
+            self.digits()?;
+        }
+
+        let number = &self.text[start..self.position];
+
+        match number_to_value(number, self.options) {
+            Some(value) => Ok(Json::Number(value)),
+            None => {
+                self.position = start;
+                Err(self.error(DecodingErrors::SizeConversionError(
+                    number.to_string(),
+                    "Double".to_string(),
+                )))
+            }
+        }
+    }
+
+    fn hex_escape(&mut self) -> TypeResult<u32> {
+        // `from_str_radix` alone would also take a sign.
+        let digits = self
+            .text
+            .get(self.position..self.position + 4)
+            .filter(|digits| digits.bytes().all(|b| b.is_ascii_hexdigit()));
+
+        match digits.and_then(|digits| u32::from_str_radix(digits, 16).ok()) {
+            Some(code) => {
+                self.position += 4;
+                Ok(code)
+            }
+            None => Err(self.unexpected("0123456789abcdef")),
+        }
+    }
+
+    fn string(&mut self) -> TypeResult<String> {
+        self.eat(b'"')?;
+
+        let mut string = String::new();
+
+        loop {
+            let rest = &self.text[self.position..];
+
+            // Copy everything up to the next quote or escape in one go. JSON
+            // strings cannot hold raw control characters, so stop at those too.
+            let run = rest
+                .find(|c: char| c == '"' || c == '\\' || c < ' ')
+                .unwrap_or(rest.len());
+            string.push_str(&rest[..run]);
+            self.position += run;
+
+            match self.peek() {
+                Some(b'"') => {
+                    self.position += 1;
+                    return Ok(string);
+                }
+                Some(b'\\') => {
+                    self.position += 1;
+
+                    let escaped = match self.peek() {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => {
+                            self.position += 1;
+                            let escape_at = self.position;
+                            let mut code = self.hex_escape()?;
+
+                            // A high surrogate must be followed by its low half.
+                            if (0xd800..0xdc00).contains(&code) {
+                                let low = match self.text[self.position..].starts_with("\\u") {
+                                    true => {
+                                        self.position += 2;
+                                        self.hex_escape()?
+                                    }
+                                    false => 0,
+                                };
+
+                                code = match low {
+                                    0xdc00..=0xdfff => {
+                                        0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00)
+                                    }
+                                    _ => u32::MAX,
+                                };
+                            }
+
+                            match char::from_u32(code) {
+                                Some(c) => {
+                                    string.push(c);
+                                    continue;
+                                }
+                                None => {
+                                    self.position = escape_at;
+                                    return Err(self.error(DecodingErrors::SizeConversionError(
+                                        "Unicode escape".to_string(),
+                                        "char".to_string(),
+                                    )));
+                                }
+                            }
+                        }
+                        _ => return Err(self.unexpected("\"\\/bfnrtu")),
+                    };
+
+                    self.position += 1;
+                    string.push(escaped);
+                }
+                _ => return Err(self.unexpected("\"")),
+            }
+        }
+    }
+}
+
+// None when the number only fits a Double and overflows it.
+fn number_to_value(number: &str, options: &JsonOptions) -> Option<SupportedTypes> {
+    // No integer type has a negative zero.
+    let is_integer = !number.contains(['.', 'e', 'E']) && number != "-0";
+
+    let integer = match (is_integer, options.integers) {
+        (false, _) | (true, JsonIntegers::Double) => None,
+        (true, JsonIntegers::Smallest) => match number.parse::<i32>() {
+            Ok(int) => Some(SupportedTypes::Integer(int)),
+            Err(_) => number.parse::<i64>().ok().map(SupportedTypes::BigInt),
+        },
+        (true, JsonIntegers::BigInt) => number.parse::<i64>().ok().map(SupportedTypes::BigInt),
+    };
+
+    let huge = match is_integer && options.huge_integers && integer.is_none() {
+        true => match options.integers {
+            JsonIntegers::Double => None,
+            _ => number.parse::<BigNum>().ok().map(SupportedTypes::HugeInt),
+        },
+        false => None,
+    };
+
+    integer.or(huge).or_else(|| {
+        number
+            .parse::<f64>()
+            .ok()
+            .filter(|double| double.is_finite())
+            .map(SupportedTypes::Double)
+    })
+}
+
+// The shared keys when `items` is a non-empty array of objects with the same
+// keys in the same order.
+fn table_headers(items: &[Json]) -> Option<Vec<String>> {
+    let headers: Vec<String> = match items.first()? {
+        Json::Object(members) if !members.is_empty() => {
+            members.iter().map(|(key, _)| key.clone()).collect()
+        }
+        _ => return None,
+    };
+
+    let uniform = items.iter().all(|item| match item {
+        Json::Object(members) => {
+            members.len() == headers.len()
+                && members.iter().zip(&headers).all(|((key, _), h)| key == h)
+        }
+        _ => false,
+    });
+
+    uniform.then_some(headers)
+}
+
+fn to_value(json: Json, options: &JsonOptions) -> TypeResult<SupportedTypes> {
+    match json {
+        Json::Null => Ok(SupportedTypes::Null(None)),
+        Json::Bool(b) => Ok(SupportedTypes::Boolean(b)),
+        Json::Number(number) => Ok(number),
+        Json::String(string) => Ok(SupportedTypes::String(string)),
+        Json::Array(items) => match table_headers(&items).filter(|_| options.tables) {
+            Some(headers) => {
+                let mut rows = vec![];
+
+                for item in items {
+                    if let Json::Object(members) = item {
+                        rows.push(
+                            members
+                                .into_iter()
+                                .map(|(_, cell)| to_value(cell, options))
+                                .collect::<TypeResult<_>>()?,
+                        );
+                    }
+                }
+
+                Ok(SupportedTypes::Table(headers, rows))
+            }
+            None => Ok(SupportedTypes::Array(
+                items
+                    .into_iter()
+                    .map(|item| to_value(item, options))
+                    .collect::<TypeResult<_>>()?,
+            )),
+        },
+        Json::Object(mut members) if members.len() == 1 => {
+            let (name, value) = members.remove(0);
+            Ok(SupportedTypes::NamedValue(
+                name,
+                Box::new(to_value(value, options)?),
+            ))
+        }
+        Json::Object(members) => Ok(SupportedTypes::Array(
+            members
+                .into_iter()
+                .map(|(name, value)| {
+                    Ok(SupportedTypes::NamedValue(
+                        name,
+                        Box::new(to_value(value, options)?),
+                    ))
+                })
+                .collect::<TypeResult<_>>()?,
+        )),
+    }
+}
+
+pub fn from_json(text: &str) -> TypeResult<SupportedTypes> {
+    from_json_with(text, &JsonOptions::default())
+}
+
+pub fn from_json_with(text: &str, options: &JsonOptions) -> TypeResult<SupportedTypes> {
+    let mut parser = Parser {
+        text,
+        position: 0,
+        options,
+    };
+
+    let json = parser.value(0)?;
+
+    parser.skip_whitespace();
+
+    if parser.peek().is_some() {
+        return Err(parser.unexpected(""));
+    }
+
+    to_value(json, options)
+}
+
+fn write_string(out: &mut String, value: &str) {
+    out.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+}
+
+fn write_json(out: &mut String, value: &SupportedTypes) -> TypeResult<()> {
+    // Writing into a String cannot fail, so only the conversions can.
+    match value {
+        SupportedTypes::Null(_) => out.push_str("null"),
+        SupportedTypes::Boolean(b) => {
+            let _ = write!(out, "{}", b);
+        }
+        SupportedTypes::Integer(int) => {
+            let _ = write!(out, "{}", int);
+        }
+        SupportedTypes::BigInt(big) => {
+            let _ = write!(out, "{}", big);
+        }
+        SupportedTypes::HugeInt(huge) => {
+            let _ = write!(out, "{}", huge);
+        }
+        SupportedTypes::Double(double) if !double.is_finite() => {
+            return Err(encoding_error(EncodingError::new(
+                &double.to_string(),
+                "JSON",
+                EncodingErrors::InvalidValue("JSON has no NaN or infinity".to_string()),
+            )))
+        }
+        // Debug keeps the decimal point, so the number reads back as a Double.
+        SupportedTypes::Double(double) => {
+            let _ = write!(out, "{:?}", double);
+        }
+        SupportedTypes::String(string) => write_string(out, string),
+        SupportedTypes::Bytes(bytes) => {
+            let numbers: Vec<String> = bytes.iter().map(u8::to_string).collect();
+            let _ = write!(out, "[{}]", numbers.join(","));
+        }
+        SupportedTypes::Array(values) => {
+            out.push('[');
+
+            for (idx, value) in values.iter().enumerate() {
+                if idx > 0 {
+                    out.push(',');
+                }
+
+                write_json(out, value)?;
+            }
+
+            out.push(']');
+        }
+        SupportedTypes::NamedValue(name, value) => {
+            out.push('{');
+            write_string(out, name);
+            out.push(':');
+            write_json(out, value)?;
+            out.push('}');
+        }
+        SupportedTypes::Table(headers, rows) => {
+            out.push('[');
+
+            for (row_idx, row) in rows.iter().enumerate() {
+                if row.len() != headers.len() {
+                    return Err(encoding_error(EncodingError::new(
+                        "Table",
+                        "JSON",
+                        EncodingErrors::TableMisfit(headers.len(), row.len()),
+                    )));
+                }
+
+                if row_idx > 0 {
+                    out.push(',');
+                }
+
+                out.push('{');
+
+                for (idx, (header, cell)) in headers.iter().zip(row).enumerate() {
+                    if idx > 0 {
+                        out.push(',');
+                    }
+
+                    write_string(out, header);
+                    out.push(':');
+                    write_json(out, cell)?;
+                }
+
+                out.push('}');
+            }
+
+            out.push(']');
+        }
+        SupportedTypes::Extension(_, value) => write_json(out, value)?,
+        SupportedTypes::Command(name, args) => {
+            out.push_str("{\"command\":");
+            write_string(out, name);
+            out.push_str(",\"args\":[");
+
+            for (idx, arg) in args.iter().enumerate() {
+                if idx > 0 {
+                    out.push(',');
+                }
+
+                write_string(out, arg);
+            }
+
+            out.push_str("]}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes `value` as compact JSON, following the mapping in the module docs.
+pub fn to_json(value: &SupportedTypes) -> TypeResult<String> {
+    let mut out = String::new();
+    write_json(&mut out, value)?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        commom::errors::{DecodingErrors, TypeError},
+        types::{BigNum, SupportedTypes},
+    };
+
+    use super::{from_json, from_json_with, to_json, JsonIntegers, JsonOptions};
+
+    #[test]
+    fn round_trips_what_json_can_hold() {
+        let values = vec![
+            SupportedTypes::Null(None),
+            SupportedTypes::Boolean(true),
+            SupportedTypes::Integer(-7),
+            SupportedTypes::HugeInt("-123456789012345678901234567890".parse().unwrap()),
+            SupportedTypes::Double(1.0),
+            SupportedTypes::Double(-2.5e-300),
+            SupportedTypes::String("quote \" tab \t nul \u{0} é😀".to_string()),
+            SupportedTypes::Array(vec![]),
+            SupportedTypes::NamedValue(
+                "outer".to_string(),
+                Box::new(SupportedTypes::Array(vec![SupportedTypes::Integer(1)])),
+            ),
+            SupportedTypes::Table(
+                vec!["id".to_string(), "name".to_string()],
+                vec![
+                    vec![
+                        SupportedTypes::Integer(1),
+                        SupportedTypes::String("one".to_string()),
+                    ],
+                    vec![SupportedTypes::Integer(2), SupportedTypes::Null(None)],
+                ],
+            ),
+        ];
+
+        for value in values {
+            let json = to_json(&value).unwrap();
+
+            assert_eq!(from_json(&json).unwrap(), value, "{}", json);
+        }
+    }
+
+    #[test]
+    fn lossy_types_follow_the_mapping() {
+        let value = SupportedTypes::Array(vec![
+            SupportedTypes::BigInt(5),
+            SupportedTypes::Bytes(vec![0, 255]),
+            SupportedTypes::Command("SET".to_string(), vec!["k".to_string()]),
+        ]);
+
+        let json = to_json(&value).unwrap();
+        assert_eq!(json, r#"[5,[0,255],{"command":"SET","args":["k"]}]"#);
+
+        assert_eq!(
+            from_json(&json).unwrap(),
+            SupportedTypes::Array(vec![
+                SupportedTypes::Integer(5),
+                SupportedTypes::Array(vec![
+                    SupportedTypes::Integer(0),
+                    SupportedTypes::Integer(255)
+                ]),
+                SupportedTypes::Array(vec![
+                    SupportedTypes::NamedValue(
+                        "command".to_string(),
+                        Box::new(SupportedTypes::String("SET".to_string()))
+                    ),
+                    SupportedTypes::NamedValue(
+                        "args".to_string(),
+                        Box::new(SupportedTypes::Array(vec![SupportedTypes::String(
+                            "k".to_string()
+                        )]))
+                    ),
+                ]),
+            ])
+        );
+
+        assert!(to_json(&SupportedTypes::Double(f64::NAN)).is_err());
+    }
+
+    #[test]
+    fn options_pick_number_types() {
+        let json = "[1, 3000000000, 99999999999999999999, 1.5]";
+
+        let mut options = JsonOptions::new();
+        assert_eq!(
+            from_json_with(json, &options).unwrap(),
+            SupportedTypes::Array(vec![
+                SupportedTypes::Integer(1),
+                SupportedTypes::BigInt(3000000000),
+                SupportedTypes::HugeInt("99999999999999999999".parse::<BigNum>().unwrap()),
+                SupportedTypes::Double(1.5),
+            ])
+        );
+
+        options.integers = JsonIntegers::BigInt;
+        options.huge_integers = false;
+        assert_eq!(
+            from_json_with(json, &options).unwrap(),
+            SupportedTypes::Array(vec![
+                SupportedTypes::BigInt(1),
+                SupportedTypes::BigInt(3000000000),
+                SupportedTypes::Double(1e20),
+                SupportedTypes::Double(1.5),
+            ])
+        );
+
+        options.integers = JsonIntegers::Double;
+        assert_eq!(
+            from_json_with("[1]", &options).unwrap(),
+            SupportedTypes::Array(vec![SupportedTypes::Double(1.0)])
+        );
+    }
+
+    #[test]
+    fn tables_can_be_turned_off() {
+        let json = r#"[{"a": 1}, {"a": 2}]"#;
+
+        assert!(matches!(
+            from_json(json).unwrap(),
+            SupportedTypes::Table(_, _)
+        ));
+
+        let mut options = JsonOptions::new();
+        options.tables = false;
+
+        assert_eq!(
+            from_json_with(json, &options).unwrap(),
+            SupportedTypes::Array(vec![
+                SupportedTypes::NamedValue("a".to_string(), Box::new(SupportedTypes::Integer(1))),
+                SupportedTypes::NamedValue("a".to_string(), Box::new(SupportedTypes::Integer(2))),
+            ])
+        );
+    }
+
+    #[test]
+    fn malformed_and_hostile_input_is_rejected() {
+        for (text, offset) in [
+            ("[1, 2", 5),
+            ("{\"a\" 1}", 5),
+            ("1 2", 2),
+            ("\"\\ud800\"", 3),
+            ("\"\\u+041\"", 3),
+            ("1.", 2),
+            ("-.5", 1),
+            ("01", 1),
+            ("1e", 2),
+            ("1e400", 0),
+            ("[-1e400]", 1),
+        ] {
+            match from_json(text) {
+                Err(TypeError::Decoding(e)) => assert_eq!(e.offset, Some(offset), "{}", text),
+                other => panic!("Unexpected {:?} for {}", other, text),
+            }
+        }
+
+        let deep = "[".repeat(100_000);
+
+        match from_json(&deep) {
+            Err(TypeError::Decoding(e)) => {
+                assert!(matches!(*e.cause, DecodingErrors::LimitExceeded { .. }))
+            }
+            other => panic!("Unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn raw_control_characters_are_rejected() {
+        match from_json("\"one\ntwo\"") {
+            Err(TypeError::Decoding(e)) => {
+                assert_eq!(e.offset, Some(4));
+                assert!(matches!(*e.cause, DecodingErrors::InvalidByte(b'\n', 4, _)));
+            }
+            other => panic!("Unexpected {:?}", other),
+        }
+
+        assert_eq!(
+            from_json("\"one\\ntwo\"").unwrap(),
+            SupportedTypes::String("one\ntwo".to_string())
+        );
+    }
+
+    #[test]
+    fn negative_zero_keeps_its_sign() {
+        for text in ["-0", "-0.0", "-0e0"] {
+            match from_json(text).unwrap() {
+                SupportedTypes::Double(zero) => {
+                    assert!(zero == 0.0 && zero.is_sign_negative(), "{}", text)
+                }
+                other => panic!("Unexpected {:?} for {}", other, text),
+            }
+        }
+
+        assert_eq!(from_json("0").unwrap(), SupportedTypes::Integer(0));
+        assert_eq!(to_json(&SupportedTypes::Double(-0.0)).unwrap(), "-0.0");
+
+        let mut options = JsonOptions::new();
+        options.huge_integers = false;
+
+        assert!(from_json_with(&"9".repeat(400), &options).is_err());
+    }
+}
@@ -1,6 +1,25 @@
+pub mod borrowed;
 pub mod composite;
+pub mod convert;
+pub mod csv;
+pub mod decoder;
+pub mod encode_value;
 pub mod infer_buffer;
+pub mod infer_prefix;
+pub mod json;
+#[cfg(test)]
+mod mutation;
+pub mod notation;
 pub mod primitive;
+pub mod registry;
+pub use borrowed::{infer_borrowed, infer_borrowed_with, ValueRef};
 pub use composite::*;
-pub use infer_buffer::infer_buffer;
+pub use convert::{FromValue, ToValue};
+pub use csv::{from_csv, from_csv_with, write_csv, write_csv_with, CsvInference, CsvOptions};
+pub use decoder::{Decoded, Decoder};
+pub use encode_value::{encode_value, encode_value_to, encoded_len};
+pub use infer_buffer::{infer_buffer, infer_buffer_with};
+pub use infer_prefix::{frames, frames_with, infer_prefix, infer_prefix_with, Frames};
+pub use json::{from_json, from_json_with, to_json, JsonIntegers, JsonOptions};
 pub use primitive::*;
+pub use registry::{with_registry, DecodeFn, EncodeFn, TypeRegistry, EXTENSION_FIRST_BYTES};
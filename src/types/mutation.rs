@@ -0,0 +1,221 @@
+//! Randomized harness feeding arbitrary and mutated bytes to every decoding
+//! entry point, and mutated text to the notation parser. Both may reject
+//! anything, but they must never panic.
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::{
+    commom::{
+        delimiters::{BUFFER_END, END_RECORD, ESCAPE, START_RECORD, VALUE_DELIMITER},
+        known_first_bytes,
+        modifiers::{MINUS, PLUS},
+        Codec, Lazy, Uint8Buff,
+    },
+    types::{
+        encode_value, frames, infer_borrowed, infer_buffer, infer_prefix, Array, BigInt, BigNum,
+        Boolean, Bytes, Command, Decoder, Double, HugeInt, Integer, NamedValue, Null,
+        SupportedTypes, Table,
+    },
+};
+
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound.max(1) as u64) as usize
+    }
+
+    // Structural bytes are far more likely to reach odd paths than noise.
+    fn interesting_byte(&mut self) -> u8 {
+        let mut interesting = vec![
+            START_RECORD,
+            END_RECORD,
+            BUFFER_END,
+            VALUE_DELIMITER,
+            ESCAPE,
+            PLUS,
+            MINUS,
+            b'.',
+            b'e',
+            b'N',
+            b'I',
+            b'Z',
+            0,
+            9,
+            10,
+        ];
+        interesting.extend(known_first_bytes());
+
+        match self.below(3) {
+            0 => self.next() as u8,
+            _ => interesting[self.below(interesting.len())],
+        }
+    }
+}
+
+fn corpus() -> Vec<Uint8Buff> {
+    let values = vec![
+        SupportedTypes::Integer(i32::MIN),
+        SupportedTypes::BigInt(i64::MAX),
+        SupportedTypes::Double(-1.25e-300),
+        SupportedTypes::Double(f64::NAN),
+        SupportedTypes::Boolean(true),
+        SupportedTypes::Null(None),
+        SupportedTypes::String("h\u{1c}llo".to_string()),
+        SupportedTypes::HugeInt(BigNum::from(i128::MIN)),
+        SupportedTypes::Bytes(vec![0x1b, 0x1e, 0xff]),
+        SupportedTypes::Array(vec![
+            SupportedTypes::Integer(7),
+            SupportedTypes::Array(vec![SupportedTypes::Boolean(false)]),
+        ]),
+        SupportedTypes::NamedValue(
+            "n".to_string(),
+            Box::new(SupportedTypes::Table(
+                vec!["a".to_string(), "b".to_string()],
+                vec![vec![
+                    SupportedTypes::Double(2.5),
+                    SupportedTypes::Command("c".to_string(), vec!["d".to_string()]),
+                ]],
+            )),
+        ),
+        SupportedTypes::Command("GET".to_string(), vec!["key".to_string()]),
+    ];
+
+    values
+        .into_iter()
+        .map(|value| encode_value(value).unwrap())
+        .collect()
+}
+
+fn mutate(rng: &mut XorShift, buff: &mut Uint8Buff) {
+    for _ in 0..=rng.below(4) {
+        let position = rng.below(buff.len() + 1);
+
+        match rng.below(6) {
+            0 if position < buff.len() => buff[position] = rng.interesting_byte(),
+            1 => buff.insert(position, rng.interesting_byte()),
+            2 if position < buff.len() => {
+                buff.remove(position);
+            }
+            3 => buff.truncate(position),
+            4 => {
+                let end = (position + rng.below(8)).min(buff.len());
+                let chunk = buff[position..end].to_vec();
+                let at = rng.below(buff.len() + 1);
+                buff.splice(at..at, chunk);
+            }
+            _ => {
+                let bit = 1 << rng.below(8);
+                if let Some(byte) = buff.get_mut(position) {
+                    *byte ^= bit;
+                }
+            }
+        }
+    }
+}
+
+fn decode_everything(buff: &[u8]) {
+    let _ = infer_buffer(buff);
+    let _ = infer_borrowed(buff);
+    let _ = infer_prefix(buff);
+    let _ = frames(buff).count();
+
+    let mut decoder = Decoder::new();
+    decoder.feed(buff);
+    let _ = decoder.by_ref().count();
+
+    let _ = Integer::decode(buff);
+    let _ = BigInt::decode(buff);
+    let _ = Double::decode(buff);
+    let _ = HugeInt::decode(buff);
+    let _ = Boolean::decode(buff);
+    let _ = Null::decode(buff);
+    let _ = crate::types::String::decode(buff);
+    let _ = Bytes::decode(buff);
+    let _ = Array::decode(buff);
+    let _ = NamedValue::decode(buff);
+    let _ = Table::decode(buff);
+    let _ = Lazy::<Command>::from_buff(buff.to_vec()).into_value();
+}
+
+fn assert_no_panic(buff: &[u8]) {
+    let outcome = catch_unwind(AssertUnwindSafe(|| decode_everything(buff)));
+
+    assert!(outcome.is_ok(), "decoding panicked on {:?}", buff);
+}
+
+#[test]
+fn empty_and_tiny_buffers() {
+    assert_no_panic(&[]);
+
+    for first in 0..=u8::MAX {
+        assert_no_panic(&[first]);
+
+        for second in [START_RECORD, END_RECORD, BUFFER_END, PLUS, MINUS] {
+            assert_no_panic(&[first, second]);
+            assert_no_panic(&[first, second, END_RECORD, BUFFER_END]);
+        }
+    }
+}
+
+#[test]
+fn arbitrary_bytes() {
+    let mut rng = XorShift(0x5851_f42d_4c95_7f2d);
+
+    for _ in 0..5_000 {
+        let first_bytes = known_first_bytes();
+        let mut buff: Uint8Buff = vec![first_bytes[rng.below(first_bytes.len())]];
+
+        for _ in 0..rng.below(48) {
+            buff.push(rng.interesting_byte());
+        }
+
+        assert_no_panic(&buff);
+    }
+}
+
+#[test]
+fn mutated_encodings() {
+    let mut rng = XorShift(0x1405_7b7e_f767_814f);
+    let corpus = corpus();
+
+    for _ in 0..20_000 {
+        let mut buff = corpus[rng.below(corpus.len())].clone();
+
+        mutate(&mut rng, &mut buff);
+
+        assert_no_panic(&buff);
+    }
+}
+
+#[test]
+fn mutated_notation() {
+    let mut rng = XorShift(0x2545_f491_4f6c_dd1d);
+    let mut texts: Vec<String> = corpus()
+        .into_iter()
+        .map(|buff| infer_buffer(&buff).unwrap().to_string())
+        .collect();
+
+    // Nesting far past the depth limit must fail instead of overflowing.
+    for opener in ["[", "{[\"a\"], [", "\"a\": "] {
+        texts.push(opener.repeat(100_000));
+    }
+
+    for _ in 0..20_000 {
+        let mut bytes = texts[rng.below(texts.len())].clone().into_bytes();
+
+        mutate(&mut rng, &mut bytes);
+
+        let text = std::string::String::from_utf8_lossy(&bytes).into_owned();
+        let outcome = catch_unwind(|| text.parse::<SupportedTypes>());
+
+        assert!(outcome.is_ok(), "parsing panicked on {:?}", text);
+    }
+}
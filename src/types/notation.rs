@@ -0,0 +1,643 @@
+//! Text notation for [`SupportedTypes`], printed by `Display` and read back by
+//! `FromStr`. Printing a value and parsing the text gives back a value that
+//! encodes to the same bytes.
+//!
+//! | Type       | Notation                                      |
+//! |------------|-----------------------------------------------|
+//! | Null       | `null`                                        |
+//! | Boolean    | `true`, `false`                               |
+//! | Integer    | `-42`                                         |
+//! | BigInt     | `-42L`                                        |
+//! | HugeInt    | `-42N`                                        |
+//! | Double     | `1.5`, `-0.0`, `1e300`, `inf`, `-inf`, `NaN`  |
+//! | String     | `"text"`                                      |
+//! | Bytes      | `x"00ff"`                                     |
+//! | Array      | `[1, "two", null]`                            |
+//! | NamedValue | `"name": value`                               |
+//! | Table      | `{["id", "name"], [1, "one"], [2, "two"]}`    |
+//! | Command    | `$"SET"("key", "value")`                      |
+//! | Extension  | `Ext(0x80, value)`                            |
+//!
+//! Doubles always carry a `.` or an exponent, so `1.0` is a Double and `1` an
+//! Integer. NaNs other than `f64::NAN` keep their bits as `NaN(0x7ff8000000000001)`.
+//! Strings escape `"`, `\`, `\n`, `\r` and `\t`, and write other control
+//! characters as `\u{1c}`. Whitespace between tokens is ignored. Arrays,
+//! tables and named values nest at most `DecodeLimits::max_depth` deep.
+
+use std::{fmt, str::FromStr};
+
+use crate::commom::{
+    errors::{decoding_error, DecodingError, DecodingErrors, TypeError},
+    DecodeLimits,
+};
+
+use super::{SupportedTypes, EXTENSION_FIRST_BYTES};
+
+fn write_string(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    write!(f, "\"")?;
+
+    for c in value.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if c.is_control() => write!(f, "\\u{{{:x}}}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+
+    write!(f, "\"")
+}
+
+fn write_list<T>(
+    f: &mut fmt::Formatter<'_>,
+    items: &[T],
+    mut write_item: impl FnMut(&mut fmt::Formatter<'_>, &T) -> fmt::Result,
+) -> fmt::Result {
+    for (idx, item) in items.iter().enumerate() {
+        if idx > 0 {
+            write!(f, ", ")?;
+        }
+
+        write_item(f, item)?;
+    }
+
+    Ok(())
+}
+
+pub(crate) fn write_notation(f: &mut fmt::Formatter<'_>, value: &SupportedTypes) -> fmt::Result {
+    match value {
+        SupportedTypes::BigInt(v) => write!(f, "{}L", v),
+        SupportedTypes::Boolean(v) => write!(f, "{}", v),
+        SupportedTypes::Double(v) if v.is_nan() && v.to_bits() != f64::NAN.to_bits() => {
+            write!(f, "NaN(0x{:016x})", v.to_bits())
+        }
+        // Debug keeps the decimal point and prints the shortest exact digits.
+        SupportedTypes::Double(v) => write!(f, "{:?}", v),
+        SupportedTypes::Integer(v) => write!(f, "{}", v),
+        SupportedTypes::Null(_) => write!(f, "null"),
+        SupportedTypes::String(v) => write_string(f, v),
+        SupportedTypes::HugeInt(v) => write!(f, "{}N", v),
+        SupportedTypes::Bytes(v) => {
+            write!(f, "x\"")?;
+
+            for byte in v {
+                write!(f, "{:02x}", byte)?;
+            }
+
+            write!(f, "\"")
+        }
+        SupportedTypes::Array(values) => {
+            write!(f, "[")?;
+            write_list(f, values, write_notation)?;
+            write!(f, "]")
+        }
+        SupportedTypes::NamedValue(name, value) => {
+            write_string(f, name)?;
+            write!(f, ": ")?;
+            write_notation(f, value)
+        }
+        SupportedTypes::Table(headers, rows) => {
+            write!(f, "{{[")?;
+            write_list(f, headers, |f, header| write_string(f, header))?;
+            write!(f, "]")?;
+
+            for row in rows {
+                write!(f, ", [")?;
+                write_list(f, row, write_notation)?;
+                write!(f, "]")?;
+            }
+
+            write!(f, "}}")
+        }
+        SupportedTypes::Extension(first_byte, value) => {
+            write!(f, "Ext(0x{:02x}, ", first_byte)?;
+            write_notation(f, value)?;
+            write!(f, ")")
+        }
+        SupportedTypes::Command(name, args) => {
+            write!(f, "$")?;
+            write_string(f, name)?;
+            write!(f, "(")?;
+            write_list(f, args, |f, arg| write_string(f, arg))?;
+            write!(f, ")")
+        }
+    }
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+    max_depth: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, cause: DecodingErrors) -> TypeError {
+        decoding_error(
+            DecodingError::new(self.text.as_bytes().to_vec(), "Notation", cause).at(self.position),
+        )
+    }
+
+    fn unexpected(&self, expected: &str) -> TypeError {
+        match self.text.as_bytes().get(self.position) {
+            Some(byte) => self.error(DecodingErrors::InvalidByte(
+                *byte,
+                self.position.try_into().unwrap_or(u32::MAX),
+                expected.bytes().collect(),
+            )),
+            None => self.error(DecodingErrors::NotEnough(
+                format!("Characters, expected one of {:?}", expected),
+                1,
+                0,
+            )),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.position..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    fn eat(&mut self, expected: char) -> Result<(), TypeError> {
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.bump();
+                Ok(())
+            }
+            _ => Err(self.unexpected(&expected.to_string())),
+        }
+    }
+
+    fn eat_word(&mut self, word: &str) -> bool {
+        if self.text[self.position..].starts_with(word) {
+            self.position += word.len();
+            return true;
+        }
+
+        false
+    }
+
+    // Items separated by commas up to `close`, which is consumed.
+    fn list<T>(
+        &mut self,
+        close: char,
+        mut item: impl FnMut(&mut Self) -> Result<T, TypeError>,
+    ) -> Result<Vec<T>, TypeError> {
+        let mut items = vec![];
+
+        self.skip_whitespace();
+
+        if self.peek() == Some(close) {
+            self.bump();
+            return Ok(items);
+        }
+
+        loop {
+            items.push(item(self)?);
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(',') => {
+                    self.bump();
+                }
+                Some(c) if c == close => {
+                    self.bump();
+                    return Ok(items);
+                }
+                _ => return Err(self.unexpected(&format!(",{}", close))),
+            }
+        }
+    }
+
+    // Fails when opening a composite at `depth` would go past `max_depth`.
+    fn nested(&self, depth: usize) -> Result<usize, TypeError> {
+        if depth >= self.max_depth {
+            return Err(self.error(DecodingErrors::LimitExceeded {
+                what: "Nesting depth".to_string(),
+                limit: self.max_depth,
+                found: depth + 1,
+            }));
+        }
+
+        Ok(depth + 1)
+    }
+
+    fn value(&mut self, depth: usize) -> Result<SupportedTypes, TypeError> {
+        self.skip_whitespace();
+
+        let value = match self.peek() {
+            Some('"') => {
+                let string = self.string()?;
+
+                self.skip_whitespace();
+
+                if self.peek() == Some(':') {
+                    let depth = self.nested(depth)?;
+                    self.bump();
+                    let value = self.value(depth)?;
+                    return Ok(SupportedTypes::NamedValue(string, Box::new(value)));
+                }
+
+                SupportedTypes::String(string)
+            }
+            Some('[') => {
+                let depth = self.nested(depth)?;
+                self.bump();
+                SupportedTypes::Array(self.list(']', |p| p.value(depth))?)
+            }
+            Some('{') => {
+                let depth = self.nested(depth)?;
+                self.bump();
+                self.eat('[')?;
+                let headers = self.list(']', Self::string)?;
+                let mut rows = vec![];
+
+                self.skip_whitespace();
+
+                while self.peek() == Some(',') {
+                    self.bump();
+                    self.eat('[')?;
+                    rows.push(self.list(']', |p| p.value(depth))?);
+                    self.skip_whitespace();
+                }
+
+                self.eat('}')?;
+                SupportedTypes::Table(headers, rows)
+            }
+            Some('$') => {
+                self.bump();
+                let name = self.string()?;
+                self.eat('(')?;
+                SupportedTypes::Command(name, self.list(')', Self::string)?)
+            }
+            Some('x') if self.text[self.position..].starts_with("x\"") => self.bytes()?,
+            _ if self.eat_word("null") => SupportedTypes::Null(None),
+            _ if self.eat_word("true") => SupportedTypes::Boolean(true),
+            _ if self.eat_word("false") => SupportedTypes::Boolean(false),
+            _ if self.eat_word("Ext(") => self.extension(depth)?,
+            _ if self.eat_word("NaN(") => self.nan_bits()?,
+            _ if self.eat_word("NaN") => SupportedTypes::Double(f64::NAN),
+            _ if self.eat_word("inf") => SupportedTypes::Double(f64::INFINITY),
+            _ if self.eat_word("-inf") => SupportedTypes::Double(f64::NEG_INFINITY),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number()?,
+            _ => return Err(self.unexpected("\"[{$x-0123456789tfnNi")),
+        };
+
+        Ok(value)
+    }
+
+    fn string(&mut self) -> Result<String, TypeError> {
+        self.eat('"')?;
+
+        let mut string = String::new();
+
+        loop {
+            match self.bump() {
+                Some('"') => return Ok(string),
+                Some('\\') => {
+                    let escape_at = self.position;
+
+                    let escaped = match self.bump() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => self.unicode_escape()?,
+                        _ => {
+                            self.position = escape_at;
+                            return Err(self.unexpected("\"\\nrtu"));
+                        }
+                    };
+
+                    string.push(escaped);
+                }
+                Some(c) => string.push(c),
+                None => return Err(self.unexpected("\"")),
+            }
+        }
+    }
+
+    fn unicode_escape(&mut self) -> Result<char, TypeError> {
+        self.eat('{')?;
+
+        let start = self.position;
+
+        while self.peek().is_some_and(|c| c.is_ascii_hexdigit()) {
+            self.bump();
+        }
+
+        let code = u32::from_str_radix(&self.text[start..self.position], 16).ok();
+
+        match code.and_then(char::from_u32) {
+            Some(c) => {
+                self.eat('}')?;
+                Ok(c)
+            }
+            None => {
+                self.position = start;
+                Err(self.error(DecodingErrors::SizeConversionError(
+                    "Unicode escape".to_string(),
+                    "char".to_string(),
+                )))
+            }
+        }
+    }
+
+    fn bytes(&mut self) -> Result<SupportedTypes, TypeError> {
+        self.position += 2;
+
+        let mut bytes = vec![];
+
+        loop {
+            let start = self.position;
+
+            match self.peek() {
+                Some('"') => {
+                    self.bump();
+                    return Ok(SupportedTypes::Bytes(bytes));
+                }
+                Some(_) => {
+                    let pair = self.text.get(start..start + 2);
+
+                    match pair.and_then(|pair| u8::from_str_radix(pair, 16).ok()) {
+                        Some(byte) => {
+                            bytes.push(byte);
+                            self.position += 2;
+                        }
+                        None => return Err(self.unexpected("0123456789abcdef\"")),
+                    }
+                }
+                None => return Err(self.unexpected("\"")),
+            }
+        }
+    }
+
+    fn extension(&mut self, depth: usize) -> Result<SupportedTypes, TypeError> {
+        let depth = self.nested(depth)?;
+
+        self.skip_whitespace();
+
+        let start = self.position;
+        let digits = self
+            .text
+            .get(start..start + 4)
+            .and_then(|digits| digits.strip_prefix("0x"));
+
+        match digits.and_then(|digits| u8::from_str_radix(digits, 16).ok()) {
+            Some(first_byte) if EXTENSION_FIRST_BYTES.contains(&first_byte) => {
+                self.position += 4;
+                self.eat(',')?;
+                let value = self.value(depth)?;
+                self.eat(')')?;
+                Ok(SupportedTypes::Extension(first_byte, Box::new(value)))
+            }
+            _ => Err(self.error(DecodingErrors::SizeConversionError(
+                self.text[start..].chars().take(4).collect(),
+                "Extension first byte".to_string(),
+            ))),
+        }
+    }
+
+    fn nan_bits(&mut self) -> Result<SupportedTypes, TypeError> {
+        let start = self.position;
+
+        self.eat_word("0x");
+
+        while self.peek().is_some_and(|c| c.is_ascii_hexdigit()) {
+            self.bump();
+        }
+
+        let digits = self.text[start..self.position].trim_start_matches("0x");
+
+        match u64::from_str_radix(digits, 16).map(f64::from_bits) {
+            Ok(nan) if nan.is_nan() => {
+                self.eat(')')?;
+                Ok(SupportedTypes::Double(nan))
+            }
+            _ => {
+                self.position = start;
+                Err(self.error(DecodingErrors::SizeConversionError(
+                    format!("0x{}", digits),
+                    "NaN".to_string(),
+                )))
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<SupportedTypes, TypeError> {
+        let start = self.position;
+
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+        {
+            self.bump();
+        }
+
+        let number = &self.text[start..self.position];
+        let is_double = number.contains(['.', 'e', 'E']);
+
+        let (target, value) = match self.peek() {
+            Some('L') if !is_double => {
+                self.bump();
+                ("BigInt", number.parse().ok().map(SupportedTypes::BigInt))
+            }
+            Some('N') if !is_double => {
+                self.bump();
+                ("HugeInt", number.parse().ok().map(SupportedTypes::HugeInt))
+            }
+            _ if is_double => ("Double", number.parse().ok().map(SupportedTypes::Double)),
+            _ => ("Integer", number.parse().ok().map(SupportedTypes::Integer)),
+        };
+
+        match value {
+            Some(value) => Ok(value),
+            None => {
+                self.position = start;
+                Err(self.error(DecodingErrors::SizeConversionError(
+                    number.to_string(),
+                    target.to_string(),
+                )))
+            }
+        }
+    }
+}
+
+impl FromStr for SupportedTypes {
+    type Err = TypeError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            text,
+            position: 0,
+            max_depth: DecodeLimits::default().max_depth,
+        };
+
+        let value = parser.value(0)?;
+
+        parser.skip_whitespace();
+
+        match parser.peek() {
+            None => Ok(value),
+            Some(_) => Err(parser.unexpected("")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        commom::{
+            errors::{DecodingErrors, TypeError},
+            DecodeLimits,
+        },
+        encode,
+        types::{BigNum, SupportedTypes},
+    };
+
+    fn corpus() -> Vec<SupportedTypes> {
+        vec![
+            SupportedTypes::Null(None),
+            SupportedTypes::Boolean(false),
+            SupportedTypes::Integer(i32::MIN),
+            SupportedTypes::BigInt(i64::MAX),
+            SupportedTypes::HugeInt(BigNum::from(i128::MIN)),
+            SupportedTypes::Double(-0.0),
+            SupportedTypes::Double(0.1),
+            SupportedTypes::Double(1e300),
+            SupportedTypes::Double(f64::MIN_POSITIVE / 3.0),
+            SupportedTypes::Double(f64::NEG_INFINITY),
+            SupportedTypes::Double(f64::NAN),
+            SupportedTypes::Double(f64::from_bits(0xfff0_0000_0000_0001)),
+            SupportedTypes::String("quote \" slash \\ tab \t delim \u{1c} é".to_string()),
+            SupportedTypes::Bytes(vec![0x00, 0x1c, 0xff]),
+            SupportedTypes::Array(vec![]),
+            SupportedTypes::NamedValue(
+                "outer".to_string(),
+                Box::new(SupportedTypes::NamedValue(
+                    "inner: \"x\"".to_string(),
+                    Box::new(SupportedTypes::Array(vec![SupportedTypes::Integer(1)])),
+                )),
+            ),
+            SupportedTypes::Table(vec!["empty".to_string()], vec![]),
+            SupportedTypes::Table(
+                vec!["id".to_string(), "tags".to_string()],
+                vec![
+                    vec![
+                        SupportedTypes::BigInt(1),
+                        SupportedTypes::Array(vec![SupportedTypes::String("a".to_string())]),
+                    ],
+                    vec![SupportedTypes::BigInt(2), SupportedTypes::Null(None)],
+                ],
+            ),
+            SupportedTypes::Command("PING".to_string(), vec![]),
+            SupportedTypes::Command("SET".to_string(), vec!["k".to_string(), "v".to_string()]),
+        ]
+    }
+
+    #[test]
+    fn round_trips_through_the_encoding() {
+        for value in corpus() {
+            let text = value.to_string();
+            let parsed: SupportedTypes = text.parse().unwrap();
+
+            assert_eq!(
+                encode(&parsed).unwrap(),
+                encode(&value).unwrap(),
+                "{}",
+                text
+            );
+            assert_eq!(parsed.to_string(), text);
+        }
+    }
+
+    #[test]
+    fn prints_the_documented_notation() {
+        let value = SupportedTypes::Array(vec![
+            SupportedTypes::Integer(-42),
+            SupportedTypes::BigInt(-42),
+            SupportedTypes::HugeInt(BigNum::from(-42)),
+            SupportedTypes::Double(1.0),
+            SupportedTypes::Bytes(vec![0, 255]),
+            SupportedTypes::NamedValue("n".to_string(), Box::new(SupportedTypes::Null(None))),
+            SupportedTypes::Table(
+                vec!["id".to_string()],
+                vec![vec![SupportedTypes::Boolean(true)]],
+            ),
+            SupportedTypes::Command("SET".to_string(), vec!["k".to_string()]),
+        ]);
+
+        assert_eq!(
+            value.to_string(),
+            r#"[-42, -42L, -42N, 1.0, x"00ff", "n": null, {["id"], [true]}, $"SET"("k")]"#
+        );
+    }
+
+    #[test]
+    fn accepts_free_whitespace() {
+        let parsed: SupportedTypes = " { [ \"a\" ] ,\n [ 1 ] } ".parse().unwrap();
+
+        assert_eq!(
+            parsed,
+            SupportedTypes::Table(
+                vec!["a".to_string()],
+                vec![vec![SupportedTypes::Integer(1)]]
+            )
+        );
+    }
+
+    #[test]
+    fn errors_point_at_the_text() {
+        for (text, offset) in [
+            ("[1, ?]", 4),
+            ("3000000000", 0),
+            ("[1, 2", 5),
+            ("\"a\" 1", 4),
+            ("x\"0g\"", 2),
+        ] {
+            match text.parse::<SupportedTypes>() {
+                Err(TypeError::Decoding(e)) => assert_eq!(e.offset, Some(offset), "{}", text),
+                other => panic!("Unexpected {:?} for {}", other, text),
+            }
+        }
+    }
+
+    #[test]
+    fn deep_nesting_is_limited() {
+        let depth = DecodeLimits::default().max_depth;
+        let nested = |n: usize| format!("{}1{}", "[".repeat(n), "]".repeat(n));
+
+        assert!(nested(depth).parse::<SupportedTypes>().is_ok());
+
+        for text in [
+            nested(depth + 1),
+            "[".repeat(1_000_000),
+            "\"a\": ".repeat(depth + 1) + "1",
+        ] {
+            match text.parse::<SupportedTypes>() {
+                Err(TypeError::Decoding(e)) => assert!(
+                    matches!(*e.cause, DecodingErrors::LimitExceeded { .. }),
+                    "{:?}",
+                    e.cause
+                ),
+                other => panic!("Unexpected {:?}", other),
+            }
+        }
+    }
+}
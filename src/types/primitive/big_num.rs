@@ -0,0 +1,509 @@
+use core::fmt;
+use std::cmp::Ordering;
+use std::error::Error;
+use std::io::Write;
+use std::ops::{Add, Mul, Neg, Sub};
+use std::str::FromStr;
+
+use crate::commom::{errors::TypeResult, write_bytes};
+
+const LIMB_BASE: u64 = 1_000_000_000;
+const LIMB_DIGITS: usize = 9;
+
+#[derive(Debug, PartialEq)]
+pub enum BigNumError {
+    Empty,
+    InvalidDigit(char),
+    OutOfRange(String, String), // (value, target)
+}
+
+impl fmt::Display for BigNumError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BigNumError::Empty => write!(f, "Cannot parse a number without digits"),
+            BigNumError::InvalidDigit(c) => write!(f, "{:?} is not a decimal digit", c),
+            BigNumError::OutOfRange(value, target) => {
+                write!(f, "{} does not fit in {}", value, target)
+            }
+        }
+    }
+}
+
+impl Error for BigNumError {}
+
+/// Signed integer of unbounded size, stored as base 10^9 limbs (least
+/// significant first) so decimal conversion never needs a division.
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigNum {
+    negative: bool,
+    limbs: Vec<u32>,
+}
+
+impl BigNum {
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn abs(&self) -> Self {
+        Self {
+            negative: false,
+            limbs: self.limbs.clone(),
+        }
+    }
+
+    /// Builds a number from decimal digits (values 0 to 9, most significant
+    /// first), the layout used on the wire. Returns `None` on any other byte.
+    pub fn from_digits(negative: bool, digits: &[u8]) -> Option<Self> {
+        if digits.iter().any(|d| *d > 9) {
+            return None;
+        }
+
+        let mut limbs: Vec<u32> = Vec::with_capacity(digits.len() / LIMB_DIGITS + 1);
+
+        for chunk in digits.rchunks(LIMB_DIGITS) {
+            limbs.push(chunk.iter().fold(0u32, |acc, d| acc * 10 + u32::from(*d)));
+        }
+
+        Some(Self { negative, limbs }.normalize())
+    }
+
+    /// Decimal digits of the magnitude, most significant first. Zero has no
+    /// digits, matching how `Integer` and `BigInt` encode it.
+    pub fn to_digits(&self) -> Vec<u8> {
+        if self.is_zero() {
+            return vec![];
+        }
+
+        self.abs().to_string().bytes().map(|b| b - b'0').collect()
+    }
+
+    /// Writes the same digits `to_digits` returns, without building them first.
+    pub fn write_digits<W: Write>(&self, writer: &mut W) -> TypeResult<()> {
+        let mut limbs = self.limbs.iter().rev();
+
+        if let Some(most_significant) = limbs.next() {
+            crate::commom::write_digits(writer, u64::from(*most_significant))?;
+        }
+
+        for limb in limbs {
+            let mut digits = [0u8; LIMB_DIGITS];
+            let mut rest = *limb;
+
+            for digit in digits.iter_mut().rev() {
+                *digit = (rest % 10) as u8;
+                rest /= 10;
+            }
+
+            write_bytes(writer, &digits)?;
+        }
+
+        Ok(())
+    }
+
+    fn from_magnitude(negative: bool, mut magnitude: u128) -> Self {
+        let mut limbs: Vec<u32> = vec![];
+
+        while magnitude != 0 {
+            limbs.push((magnitude % u128::from(LIMB_BASE)) as u32);
+            magnitude /= u128::from(LIMB_BASE);
+        }
+
+        Self { negative, limbs }.normalize()
+    }
+
+    fn to_magnitude(&self) -> Option<u128> {
+        self.limbs.iter().rev().try_fold(0u128, |acc, limb| {
+            acc.checked_mul(u128::from(LIMB_BASE))?
+                .checked_add(u128::from(*limb))
+        })
+    }
+
+    fn normalize(mut self) -> Self {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+
+        if self.limbs.is_empty() {
+            self.negative = false;
+        }
+
+        self
+    }
+}
+
+fn cmp_magnitude(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut sum: Vec<u32> = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry: u64 = 0;
+
+    for i in 0..a.len().max(b.len()) {
+        let limb_sum =
+            u64::from(*a.get(i).unwrap_or(&0)) + u64::from(*b.get(i).unwrap_or(&0)) + carry;
+        sum.push((limb_sum % LIMB_BASE) as u32);
+        carry = limb_sum / LIMB_BASE;
+    }
+
+    if carry != 0 {
+        sum.push(carry as u32);
+    }
+
+    sum
+}
+
+// Expects |a| >= |b|.
+fn sub_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut difference: Vec<u32> = Vec::with_capacity(a.len());
+    let mut borrow: i64 = 0;
+
+    for (i, a_limb) in a.iter().enumerate() {
+        let mut limb = i64::from(*a_limb) - i64::from(*b.get(i).unwrap_or(&0)) - borrow;
+
+        borrow = if limb < 0 {
+            limb += LIMB_BASE as i64;
+            1
+        } else {
+            0
+        };
+
+        difference.push(limb as u32);
+    }
+
+    difference
+}
+
+fn mul_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+
+    let mut product: Vec<u64> = vec![0; a.len() + b.len()];
+
+    for (i, a_limb) in a.iter().enumerate() {
+        let mut carry: u64 = 0;
+
+        for (j, b_limb) in b.iter().enumerate() {
+            let current = product[i + j] + u64::from(*a_limb) * u64::from(*b_limb) + carry;
+            product[i + j] = current % LIMB_BASE;
+            carry = current / LIMB_BASE;
+        }
+
+        product[i + b.len()] += carry;
+    }
+
+    product.into_iter().map(|limb| limb as u32).collect()
+}
+
+impl Add for &BigNum {
+    type Output = BigNum;
+
+    fn add(self, rhs: Self) -> BigNum {
+        if self.negative == rhs.negative {
+            return BigNum {
+                negative: self.negative,
+                limbs: add_magnitude(&self.limbs, &rhs.limbs),
+            }
+            .normalize();
+        }
+
+        match cmp_magnitude(&self.limbs, &rhs.limbs) {
+            Ordering::Less => BigNum {
+                negative: rhs.negative,
+                limbs: sub_magnitude(&rhs.limbs, &self.limbs),
+            },
+            _ => BigNum {
+                negative: self.negative,
+                limbs: sub_magnitude(&self.limbs, &rhs.limbs),
+            },
+        }
+        .normalize()
+    }
+}
+
+impl Sub for &BigNum {
+    type Output = BigNum;
+
+    fn sub(self, rhs: Self) -> BigNum {
+        self + &(-rhs)
+    }
+}
+
+impl Mul for &BigNum {
+    type Output = BigNum;
+
+    fn mul(self, rhs: Self) -> BigNum {
+        BigNum {
+            negative: self.negative != rhs.negative,
+            limbs: mul_magnitude(&self.limbs, &rhs.limbs),
+        }
+        .normalize()
+    }
+}
+
+impl Neg for &BigNum {
+    type Output = BigNum;
+
+    fn neg(self) -> BigNum {
+        BigNum {
+            negative: !self.negative,
+            limbs: self.limbs.clone(),
+        }
+        .normalize()
+    }
+}
+
+impl Add for BigNum {
+    type Output = BigNum;
+
+    fn add(self, rhs: Self) -> BigNum {
+        &self + &rhs
+    }
+}
+
+impl Sub for BigNum {
+    type Output = BigNum;
+
+    fn sub(self, rhs: Self) -> BigNum {
+        &self - &rhs
+    }
+}
+
+impl Mul for BigNum {
+    type Output = BigNum;
+
+    fn mul(self, rhs: Self) -> BigNum {
+        &self * &rhs
+    }
+}
+
+impl Neg for BigNum {
+    type Output = BigNum;
+
+    fn neg(self) -> BigNum {
+        -&self
+    }
+}
+
+impl Ord for BigNum {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_magnitude(&self.limbs, &other.limbs),
+            (true, true) => cmp_magnitude(&other.limbs, &self.limbs),
+        }
+    }
+}
+
+impl PartialOrd for BigNum {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigNum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut limbs = self.limbs.iter().rev();
+
+        match limbs.next() {
+            Some(most_significant) => {
+                if self.negative {
+                    write!(f, "-")?;
+                }
+
+                write!(f, "{}", most_significant)?;
+
+                for limb in limbs {
+                    write!(f, "{:09}", limb)?;
+                }
+
+                Ok(())
+            }
+            None => write!(f, "0"),
+        }
+    }
+}
+
+impl fmt::Debug for BigNum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl FromStr for BigNum {
+    type Err = BigNumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+
+        if digits.is_empty() {
+            return Err(BigNumError::Empty);
+        }
+
+        if let Some(c) = digits.chars().find(|c| !c.is_ascii_digit()) {
+            return Err(BigNumError::InvalidDigit(c));
+        }
+
+        let digits: Vec<u8> = digits.bytes().map(|b| b - b'0').collect();
+
+        BigNum::from_digits(negative, &digits).ok_or(BigNumError::Empty)
+    }
+}
+
+macro_rules! big_num_from_signed {
+    ($($t:ty),*) => {$(
+        impl From<$t> for BigNum {
+            fn from(value: $t) -> Self {
+                BigNum::from_magnitude(value < 0, value.unsigned_abs().into())
+            }
+        }
+
+        impl TryFrom<&BigNum> for $t {
+            type Error = BigNumError;
+
+            fn try_from(value: &BigNum) -> Result<Self, Self::Error> {
+                let out_of_range = || BigNumError::OutOfRange(value.to_string(), stringify!($t).to_string());
+
+                let magnitude = value.to_magnitude().ok_or_else(out_of_range)?;
+
+                if value.negative {
+                    0i128
+                        .checked_sub_unsigned(magnitude)
+                        .and_then(|v| <$t>::try_from(v).ok())
+                        .ok_or_else(out_of_range)
+                } else {
+                    <$t>::try_from(magnitude).map_err(|_| out_of_range())
+                }
+            }
+        }
+    )*};
+}
+
+macro_rules! big_num_from_unsigned {
+    ($($t:ty),*) => {$(
+        impl From<$t> for BigNum {
+            fn from(value: $t) -> Self {
+                BigNum::from_magnitude(false, value.into())
+            }
+        }
+
+        impl TryFrom<&BigNum> for $t {
+            type Error = BigNumError;
+
+            fn try_from(value: &BigNum) -> Result<Self, Self::Error> {
+                let out_of_range = || BigNumError::OutOfRange(value.to_string(), stringify!($t).to_string());
+
+                if value.negative {
+                    return Err(out_of_range());
+                }
+
+                value
+                    .to_magnitude()
+                    .and_then(|m| <$t>::try_from(m).ok())
+                    .ok_or_else(out_of_range)
+            }
+        }
+    )*};
+}
+
+big_num_from_signed!(i32, i64, i128);
+big_num_from_unsigned!(u32, u64, u128);
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::{BigNum, BigNumError};
+
+    #[test]
+    fn decimal_round_trip() {
+        let cases = [
+            "0",
+            "1",
+            "-1",
+            "999999999",
+            "1000000000",
+            "-1000000000000000000",
+            "340282366920938463463374607431768211456",
+            "-123456789012345678901234567890123456789012345678901234567890",
+        ];
+
+        for case in cases {
+            assert_eq!(BigNum::from_str(case).unwrap().to_string(), case);
+        }
+
+        assert_eq!(BigNum::from_str("-0").unwrap(), BigNum::zero());
+        assert_eq!(BigNum::from_str("+0042").unwrap().to_string(), "42");
+        assert_eq!(BigNum::from_str(""), Err(BigNumError::Empty));
+        assert_eq!(BigNum::from_str("12a"), Err(BigNumError::InvalidDigit('a')));
+    }
+
+    #[test]
+    fn arithmetic_matches_i128() {
+        let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state as i64 >> (state % 40)) as i128
+        };
+
+        for _ in 0..10_000 {
+            let (a, b) = (next(), next());
+            let (big_a, big_b) = (BigNum::from(a), BigNum::from(b));
+
+            assert_eq!(&big_a + &big_b, BigNum::from(a + b));
+            assert_eq!(&big_a - &big_b, BigNum::from(a - b));
+            assert_eq!(&big_a * &big_b, BigNum::from(a * b));
+            assert_eq!(big_a.cmp(&big_b), a.cmp(&b));
+            assert_eq!(i128::try_from(&(-&big_a)).unwrap(), -a);
+        }
+    }
+
+    #[test]
+    fn conversions_at_the_limits() {
+        for value in [i128::MIN, i128::MAX, i64::MIN.into(), 0] {
+            assert_eq!(i128::try_from(&BigNum::from(value)).unwrap(), value);
+        }
+
+        assert_eq!(u128::try_from(&BigNum::from(u128::MAX)).unwrap(), u128::MAX);
+
+        let beyond = &BigNum::from(u128::MAX) + &BigNum::from(1);
+
+        assert_eq!(
+            beyond.to_string(),
+            "340282366920938463463374607431768211456"
+        );
+        assert!(u128::try_from(&beyond).is_err());
+        assert!(i64::try_from(&BigNum::from(i64::MAX as i128 + 1)).is_err());
+        assert!(u64::try_from(&BigNum::from(-1)).is_err());
+    }
+
+    #[test]
+    fn digits_round_trip() {
+        let value = BigNum::from_str("-1000000000000000000000000000001").unwrap();
+
+        assert_eq!(
+            BigNum::from_digits(true, &value.to_digits()).unwrap(),
+            value
+        );
+        assert!(BigNum::zero().to_digits().is_empty());
+        assert!(BigNum::from_digits(false, &[1, 10]).is_none());
+    }
+}
@@ -1,119 +1,108 @@
+use std::io::Write;
+
+use crate::commom::decode_limits::check_limit;
 use crate::commom::{
-    delimiters::{BUFFER_END, END_RECORD, START_RECORD},
+    delimiters::{END_RECORD, START_RECORD},
     errors::{decoding_error, DecodingError, DecodingErrors, TypeResult},
-    join_parts, slice_records, BuffPart, CoprotoType, Uint8Buff,
+    slice_records, take_first_byte, write_bytes, Codec, DecodeLimits,
 };
 
 #[derive(Debug)]
-pub struct Boolean {
-    pub first_byte: u8,
-    pub modifier_byte: Option<u8>,
-    pub modifier_char: Option<char>,
-    pub first_char: char,
-    pub value_of: TypeResult<bool>,
-    pub buff: TypeResult<Uint8Buff>,
-}
+pub struct Boolean;
 
-impl CoprotoType<bool> for Boolean {
+impl Codec for Boolean {
+    type Value = bool;
     const FIRST_BYTE: u8 = b'#';
-    fn new(value: crate::commom::ValueOrBuffer<bool>) -> Self {
-        match value {
-            crate::commom::ValueOrBuffer::Value(v) => Self {
-                first_byte: b'#',
-                modifier_byte: None,
-                modifier_char: None,
-                first_char: '#',
-                value_of: Ok(v),
-                buff: Self::encode(v),
-            },
-            crate::commom::ValueOrBuffer::Buffer(vec) => Self {
-                first_byte: b'#',
-                modifier_byte: None,
-                modifier_char: None,
-                first_char: '#',
-                value_of: Self::decode(vec.clone()),
-                buff: Ok(vec),
-            },
-        }
-    }
 
-    fn encode(value: bool) -> TypeResult<Uint8Buff> {
-        let val: u8 = if value { 1 } else { 0 };
+    fn write_value<W: Write>(value: &bool, writer: &mut W) -> TypeResult<()> {
+        let val: u8 = if *value { 1 } else { 0 };
 
-        let parts: Vec<BuffPart> = vec![
-            BuffPart::Val(b'#'),
-            BuffPart::Val(START_RECORD),
-            BuffPart::Val(val),
-            BuffPart::Val(END_RECORD),
-            BuffPart::Val(BUFFER_END),
-        ];
-
-        Ok(join_parts(parts))
+        write_bytes(writer, &[b'#', START_RECORD, val, END_RECORD])
     }
 
-    fn decode(value: Uint8Buff) -> TypeResult<bool> {
-        let mut m_value = value.clone();
+    fn decode_with(value: &[u8], limits: &DecodeLimits) -> TypeResult<bool> {
+        check_limit(
+            value,
+            "Boolean",
+            "Message size",
+            limits.max_message_size,
+            value.len(),
+        )
+        .map_err(decoding_error)?;
 
-        let first_byte = m_value.remove(0);
+        let mut m_value = value.to_vec();
+
+        let first_byte = take_first_byte(&mut m_value, "Boolean").map_err(decoding_error)?;
 
         if first_byte != b'#' {
-            return Err(decoding_error(DecodingError {
-                from: value,
-                to: "Boolean".to_string(),
-                cause: DecodingErrors::FirstByteError("Boolean".to_string(), b'#', first_byte),
-            }));
+            return Err(decoding_error(
+                DecodingError::new(
+                    value.to_vec(),
+                    "Boolean",
+                    DecodingErrors::FirstByteError("Boolean".to_string(), b'#', first_byte),
+                )
+                .at(0),
+            ));
         };
 
-        let records = slice_records(m_value.clone());
+        let prefix_len = value.len() - m_value.len();
+
+        let records = slice_records(&m_value)
+            .map_err(|e| decoding_error(e.inside(prefix_len).within(value)))?;
 
         if records.len() > 1 {
             return Err(decoding_error(DecodingError::new(
-                value,
+                value.to_vec(),
                 "Double",
                 DecodingErrors::TooMuch(
                     "Records".to_string(),
                     1,
-                    records.len().try_into().unwrap(),
+                    records.len().try_into().unwrap_or(u32::MAX),
                 ),
             )));
         };
 
-        let val = match records.first() {
+        let record_start = prefix_len + records.first().map_or(0, |range| range.start);
+
+        let val = match records.first().map(|range| &m_value[range.clone()]) {
             Some(v_arr) => match v_arr.first() {
                 Some(v) => v,
                 None => {
-                    return Err(decoding_error(DecodingError {
-                        from: value.clone(),
-                        to: "Boolean".to_string(),
-                        cause: DecodingErrors::NotEnough(
+                    return Err(decoding_error(DecodingError::new(
+                        value.to_vec(),
+                        "Boolean",
+                        DecodingErrors::NotEnough(
                             "Bytes".to_string(),
                             4,
-                            value.len().try_into().unwrap(),
+                            value.len().try_into().unwrap_or(u32::MAX),
                         ),
-                    }))
+                    )))
                 }
             },
             None => {
-                return Err(decoding_error(DecodingError {
-                    from: value.clone(),
-                    to: "Boolean".to_string(),
-                    cause: DecodingErrors::NotEnough(
+                return Err(decoding_error(DecodingError::new(
+                    value.to_vec(),
+                    "Boolean",
+                    DecodingErrors::NotEnough(
                         "Bytes".to_string(),
                         4,
-                        value.len().try_into().unwrap(),
+                        value.len().try_into().unwrap_or(u32::MAX),
                     ),
-                }))
+                )))
             }
         };
 
         match val {
             0 => Ok(false),
             1 => Ok(true),
-            _ => Err(decoding_error(DecodingError::new(
-                value,
-                "Double",
-                DecodingErrors::InvalidByte(*val, 0, vec![1, 0]),
-            ))),
+            _ => Err(decoding_error(
+                DecodingError::new(
+                    value.to_vec(),
+                    "Double",
+                    DecodingErrors::InvalidByte(*val, 0, vec![1, 0]),
+                )
+                .at(record_start),
+            )),
         }
     }
 }
@@ -123,29 +112,29 @@ mod tests {
     use crate::{
         commom::{
             delimiters::{BUFFER_END, END_RECORD, START_RECORD},
-            join_parts, BuffPart, CoprotoType, ValueOrBuffer,
+            join_parts, BuffPart, Lazy, ValueOrBuffer,
         },
         types::Boolean,
     };
 
     #[test]
     fn test_encoding_decoding() {
-        let true_encoding = Boolean::new(ValueOrBuffer::Value(true));
-        let true_buff = true_encoding.buff.unwrap();
-        let true_decoding = Boolean::new(ValueOrBuffer::Buffer(true_buff));
+        let true_encoding = Lazy::<Boolean>::new(ValueOrBuffer::Value(true));
+        let true_buff = true_encoding.buff().unwrap().to_vec();
+        let true_decoding = Lazy::<Boolean>::new(ValueOrBuffer::Buffer(true_buff));
 
         assert_eq!(
-            true_decoding.value_of.unwrap(),
-            true_encoding.value_of.unwrap()
+            *true_decoding.value().unwrap(),
+            *true_encoding.value().unwrap()
         );
 
-        let false_encoding = Boolean::new(ValueOrBuffer::Value(false));
-        let false_buff = false_encoding.buff.unwrap();
-        let false_decoding = Boolean::new(ValueOrBuffer::Buffer(false_buff));
+        let false_encoding = Lazy::<Boolean>::new(ValueOrBuffer::Value(false));
+        let false_buff = false_encoding.buff().unwrap().to_vec();
+        let false_decoding = Lazy::<Boolean>::new(ValueOrBuffer::Buffer(false_buff));
 
         assert_eq!(
-            false_decoding.value_of.unwrap(),
-            false_encoding.value_of.unwrap()
+            *false_decoding.value().unwrap(),
+            *false_encoding.value().unwrap()
         );
     }
 
@@ -159,15 +148,15 @@ mod tests {
             BuffPart::Val(BUFFER_END),
         ];
 
-        let invalid_byte = Boolean::new(ValueOrBuffer::Buffer(join_parts(parts)));
+        let invalid_byte = Lazy::<Boolean>::new(ValueOrBuffer::Buffer(join_parts(parts)));
 
-        match invalid_byte.value_of {
+        match invalid_byte.value() {
             Ok(_) => false,
             Err(e) => match e {
                 crate::commom::errors::TypeError::Encoding(_) => false,
                 crate::commom::errors::TypeError::Decoding(decoding_error) => {
                     matches!(
-                        decoding_error.cause,
+                        *decoding_error.cause,
                         crate::commom::errors::DecodingErrors::InvalidByte(_, _, _)
                     )
                 }
@@ -179,15 +168,15 @@ mod tests {
     fn wrong_buffer() {
         let buff = vec![b'?', START_RECORD, 0, END_RECORD, BUFFER_END];
 
-        let wrong = Boolean::new(ValueOrBuffer::Buffer(buff));
+        let wrong = Lazy::<Boolean>::new(ValueOrBuffer::Buffer(buff));
 
-        match wrong.value_of {
+        match wrong.value() {
             Ok(_) => false,
             Err(e) => match e {
                 crate::commom::errors::TypeError::Encoding(_) => false,
                 crate::commom::errors::TypeError::Decoding(decoding_error) => {
                     matches!(
-                        decoding_error.cause,
+                        *decoding_error.cause,
                         crate::commom::errors::DecodingErrors::FirstByteError(_, _, _)
                     )
                 }
@@ -0,0 +1,109 @@
+use std::io::Write;
+
+use crate::commom::{
+    delimiters::{END_RECORD, START_RECORD},
+    errors::TypeResult,
+    write_bytes, write_escaped, Codec, DecodeLimits,
+};
+use crate::types::borrowed::bytes_ref;
+
+#[derive(Debug)]
+pub struct Bytes;
+
+impl Codec for Bytes {
+    type Value = Vec<u8>;
+    const FIRST_BYTE: u8 = b'%';
+
+    fn write_value<W: Write>(value: &Vec<u8>, writer: &mut W) -> TypeResult<()> {
+        write_bytes(writer, &[Self::FIRST_BYTE, START_RECORD])?;
+        write_escaped(writer, value)?;
+        write_bytes(writer, &[END_RECORD])
+    }
+
+    fn decode_with(value: &[u8], limits: &DecodeLimits) -> TypeResult<Vec<u8>> {
+        Ok(bytes_ref(value, limits)?.into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        commom::{
+            delimiters::{BUFFER_END, END_RECORD, START_RECORD},
+            Codec, Lazy, ValueOrBuffer,
+        },
+        types::{Array, Bytes, NamedValue, SupportedTypes, Table},
+    };
+
+    #[test]
+    fn test_every_byte_value() {
+        for byte in 0..=u8::MAX {
+            let encoding = Lazy::<Bytes>::new(ValueOrBuffer::Value(vec![byte, byte]));
+            let decoding =
+                Lazy::<Bytes>::new(ValueOrBuffer::Buffer(encoding.buff().unwrap().to_vec()));
+
+            assert_eq!(*decoding.value().unwrap(), vec![byte, byte]);
+        }
+
+        let all_bytes: Vec<u8> = (0..=u8::MAX).rev().collect();
+
+        let encoding = Lazy::<Bytes>::new(ValueOrBuffer::Value(all_bytes.clone()));
+        let decoding = Lazy::<Bytes>::new(ValueOrBuffer::Buffer(encoding.buff().unwrap().to_vec()));
+
+        assert_eq!(*decoding.value().unwrap(), all_bytes);
+    }
+
+    #[test]
+    fn test_empty() {
+        let encoded = Bytes::encode(&vec![]).unwrap();
+
+        assert_eq!(encoded, vec![b'%', START_RECORD, END_RECORD, BUFFER_END]);
+        assert_eq!(Bytes::decode(&encoded).unwrap(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn test_inside_composites() {
+        let chunk: Vec<u8> = (0..=u8::MAX).collect();
+
+        let array = vec![
+            SupportedTypes::Bytes(chunk.clone()),
+            SupportedTypes::Bytes(vec![]),
+        ];
+        let encoded_array = Array::encode(&array).unwrap();
+        assert_eq!(Array::decode(&encoded_array).unwrap(), array);
+
+        let named = ("hash".to_string(), SupportedTypes::Bytes(chunk.clone()));
+        let encoded_named = NamedValue::encode(&named).unwrap();
+        assert_eq!(NamedValue::decode(&encoded_named).unwrap(), named);
+
+        let table = (
+            vec!["name".to_string(), "chunk".to_string()],
+            vec![vec![
+                SupportedTypes::String("file.bin".to_string()),
+                SupportedTypes::Bytes(chunk),
+            ]],
+        );
+        let encoded_table = Table::encode(&table).unwrap();
+        assert_eq!(Table::decode(&encoded_table).unwrap(), table);
+    }
+
+    #[test]
+    fn wrong_buffer() {
+        let buff = vec![b'?', START_RECORD, 0, END_RECORD, BUFFER_END];
+
+        let wrong = Lazy::<Bytes>::new(ValueOrBuffer::Buffer(buff));
+
+        match wrong.value() {
+            Ok(_) => false,
+            Err(e) => match e {
+                crate::commom::errors::TypeError::Encoding(_) => false,
+                crate::commom::errors::TypeError::Decoding(decoding_error) => {
+                    matches!(
+                        *decoding_error.cause,
+                        crate::commom::errors::DecodingErrors::FirstByteError(_, _, _)
+                    )
+                }
+            },
+        };
+    }
+}
@@ -1,91 +1,77 @@
+use std::fmt::{self, Write as _};
+use std::io::Write;
+
+use crate::commom::{decode_limits::check_limit, DecodeLimits};
 use crate::commom::{
-    delimiters::{BUFFER_END, END_RECORD, START_RECORD},
+    delimiters::{END_RECORD, START_RECORD},
     errors::{
         decoding_error, encoding_error, DecodingError, DecodingErrors, EncodingError,
         EncodingErrors, TypeResult,
     },
-    join_parts, slice_records, to_ascii_code, BuffPart, CoprotoType, Uint8Buff,
+    modifiers::{MINUS, PLUS},
+    slice_records, take_first_byte, write_bytes, write_digits, Codec,
 };
 
+const EXPONENT_MARKER: u8 = b'e';
+const NAN_MARKER: u8 = b'N';
+const INFINITY_MARKER: u8 = b'I';
+const ZERO_MARKER: u8 = b'Z';
+
 #[derive(Debug)]
-pub struct Double {
-    pub first_byte: u8,
-    pub modifier_byte: Option<u8>,
-    pub modifier_char: Option<char>,
-    pub first_char: char,
-    pub value_of: TypeResult<f64>,
-    pub buff: TypeResult<Uint8Buff>,
-}
+pub struct Double;
 
-impl CoprotoType<f64> for Double {
+impl Codec for Double {
+    type Value = f64;
     const FIRST_BYTE: u8 = b';';
-    fn new(value: crate::commom::ValueOrBuffer<f64>) -> Self {
-        match value {
-            crate::commom::ValueOrBuffer::Value(value) => Self {
-                first_byte: b';',
-                modifier_byte: Some(if value < 0f64 { b'-' } else { b'+' }),
-                modifier_char: Some(if value < 0f64 { '-' } else { '+' }),
-                first_char: ';',
-                value_of: Ok(value),
-                buff: Self::encode(value),
-            },
-            crate::commom::ValueOrBuffer::Buffer(vec) => {
-                let value = Self::decode(vec.clone());
-                let modifier_char = match value {
-                    Ok(value) => Some(if value < 0f64 { '-' } else { '+' }),
-                    Err(_) => None,
-                };
-
-                let modifier_byte = match modifier_char {
-                    Some(char) => match to_ascii_code(char) {
-                        Ok(c) => Some(c),
-                        Err(_) => todo!(),
-                    },
-                    None => None,
-                };
-
-                Self {
-                    first_byte: b';',
-                    first_char: ';',
-                    modifier_char,
-                    modifier_byte,
-                    value_of: value,
-                    buff: Ok(vec.clone()),
-                }
-            }
-        }
-    }
 
-    fn encode(value: f64) -> TypeResult<Uint8Buff> {
-        let signal = if value > 0f64 { b'+' } else { b'-' };
+    fn write_value<W: Write>(value: &f64, writer: &mut W) -> TypeResult<()> {
+        let value = *value;
 
-        let binding = value.abs().to_string();
+        let signal = if value.is_sign_negative() {
+            MINUS
+        } else {
+            PLUS
+        };
 
-        let mut str_values = binding.split(".");
+        write_bytes(writer, &[b';', signal, START_RECORD])?;
 
-        let mut integer_part: i32 = match str_values.next() {
-            Some(int_str) => match int_str.parse() {
-                Ok(int) => int,
-                Err(e) => {
-                    return Err(encoding_error(EncodingError::new(
-                        "String",
-                        "Integer 32",
-                        EncodingErrors::InternalError(Box::new(e)),
-                    )))
-                }
-            },
-            None => {
+        if value.is_nan() {
+            write_bytes(writer, &[NAN_MARKER])?;
+            write_digits(writer, value.to_bits())?;
+        } else if value.is_infinite() {
+            write_bytes(writer, &[INFINITY_MARKER])?;
+        } else if value == 0f64 {
+            write_bytes(writer, &[ZERO_MARKER])?;
+        } else {
+            let mut binding = FormatBuffer::default();
+
+            if write!(binding, "{:e}", value.abs()).is_err() {
                 return Err(encoding_error(EncodingError::new(
                     "Double",
-                    "Integer Part",
-                    EncodingErrors::InvalidValue(format!("Expected: double, received: {}", value)),
-                )))
+                    "Mantissa and Exponent",
+                    EncodingErrors::InvalidValue(format!(
+                        "Scientific notation of {} is too long",
+                        value
+                    )),
+                )));
             }
-        };
 
-        let mut decimal_part: i32 = match str_values.next() {
-            Some(dec_str) => match dec_str.parse() {
-                Ok(dec) => dec,
+            let (mantissa, exponent) = match binding.as_str().split_once('e') {
+                Some(parts) => parts,
+                None => {
+                    return Err(encoding_error(EncodingError::new(
+                        "Double",
+                        "Mantissa and Exponent",
+                        EncodingErrors::InvalidValue(format!(
+                            "Expected: double, received: {}",
+                            value
+                        )),
+                    )))
+                }
+            };
+
+            let exponent: i32 = match exponent.parse() {
+                Ok(exp) => exp,
                 Err(e) => {
                     return Err(encoding_error(EncodingError::new(
                         "String",
@@ -93,211 +79,247 @@ impl CoprotoType<f64> for Double {
                         EncodingErrors::InternalError(Box::new(e)),
                     )))
                 }
-            },
-            None => {
-                return Err(encoding_error(EncodingError::new(
-                    "Double",
-                    "Decimal Part",
-                    EncodingErrors::InvalidValue(format!("Expected: double, received: {}", value)),
-                )))
-            }
-        };
-
-        let mut integer_buff: Vec<u8> = vec![];
-
-        let mut integer_digit: u8 = match (integer_part % 10).try_into() {
-            Ok(digit) => digit,
-            Err(e) => {
-                return Err(encoding_error(EncodingError {
-                    from: value.to_string(),
-                    to: "u8 digit".to_string(),
-                    origin: EncodingErrors::InternalError(Box::new(e)),
-                }))
-            }
-        };
-
-        while integer_part != 0 {
-            integer_buff.insert(0, integer_digit);
+            };
 
-            integer_part /= 10;
+            let (integer_part, decimal_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
 
-            integer_digit = match (integer_part % 10).try_into() {
-                Ok(digit) => digit,
+            let decimal_len: i32 = match decimal_part.len().try_into() {
+                Ok(len) => len,
                 Err(e) => {
-                    return Err(encoding_error(EncodingError {
-                        from: value.to_string(),
-                        to: "u8 digit".to_string(),
-                        origin: EncodingErrors::InternalError(Box::new(e)),
-                    }))
+                    return Err(encoding_error(EncodingError::new(
+                        "usize",
+                        "Integer 32",
+                        EncodingErrors::InternalError(Box::new(e)),
+                    )))
                 }
             };
-        }
 
-        let mut decimal_buff: Vec<u8> = vec![];
+            let exponent = exponent - decimal_len;
 
-        let mut decimal_digit: u8 = match (decimal_part % 10).try_into() {
-            Ok(digit) => digit,
-            Err(e) => {
-                return Err(encoding_error(EncodingError {
-                    from: value.to_string(),
-                    to: "u8 digit".to_string(),
-                    origin: EncodingErrors::InternalError(Box::new(e)),
-                }))
+            for part in [integer_part, decimal_part] {
+                for digit in part.bytes() {
+                    write_bytes(writer, &[digit - b'0'])?;
+                }
             }
-        };
 
-        while decimal_part != 0 {
-            decimal_buff.insert(0, decimal_digit);
-
-            decimal_part /= 10;
-
-            decimal_digit = match (decimal_part % 10).try_into() {
-                Ok(digit) => digit,
-                Err(e) => {
-                    return Err(encoding_error(EncodingError {
-                        from: value.to_string(),
-                        to: "u8 digit".to_string(),
-                        origin: EncodingErrors::InternalError(Box::new(e)),
-                    }))
-                }
-            };
+            write_bytes(
+                writer,
+                &[EXPONENT_MARKER, if exponent < 0 { MINUS } else { PLUS }],
+            )?;
+            // Unlike integers, a zero exponent keeps its digit.
+            match exponent {
+                0 => write_bytes(writer, &[0])?,
+                _ => write_digits(writer, exponent.unsigned_abs().into())?,
+            }
         }
 
-        let parts: Vec<BuffPart> = vec![
-            BuffPart::Val(b';'),
-            BuffPart::Val(signal),
-            BuffPart::Val(START_RECORD),
-            BuffPart::Arr(integer_buff),
-            BuffPart::Val(b'.'),
-            BuffPart::Arr(decimal_buff),
-            BuffPart::Val(END_RECORD),
-            BuffPart::Val(BUFFER_END),
-        ];
-
-        Ok(join_parts(parts))
+        write_bytes(writer, &[END_RECORD])
     }
 
-    fn decode(value: Uint8Buff) -> TypeResult<f64> {
-        let mut m_value = value.clone();
+    fn decode_with(value: &[u8], limits: &DecodeLimits) -> TypeResult<f64> {
+        check_limit(
+            value,
+            "Double",
+            "Message size",
+            limits.max_message_size,
+            value.len(),
+        )
+        .map_err(decoding_error)?;
+
+        let mut m_value = value.to_vec();
 
-        let first_byte = m_value.remove(0);
+        let first_byte = take_first_byte(&mut m_value, "Double").map_err(decoding_error)?;
 
         if first_byte != b';' {
-            return Err(decoding_error(DecodingError::new(
-                value,
-                "Double",
-                decoding_error::DecodingErrors::FirstByteError(
-                    "Double".to_string(),
-                    b';',
-                    first_byte,
-                ),
-            )));
+            return Err(decoding_error(
+                DecodingError::new(
+                    value.to_vec(),
+                    "Double",
+                    decoding_error::DecodingErrors::FirstByteError(
+                        "Double".to_string(),
+                        b';',
+                        first_byte,
+                    ),
+                )
+                .at(0),
+            ));
         };
 
-        let signal = match m_value.first() {
-            Some(b) => match b {
-                b'-' => {
+        let negative = match m_value.first() {
+            Some(b) => match *b {
+                MINUS => {
                     m_value.remove(0);
-                    -1f64
+                    true
                 }
-                b'+' => {
+                PLUS => {
                     m_value.remove(0);
-                    1f64
+                    false
                 }
-                _ => 1f64,
+                _ => false,
             },
             None => {
-                return Err(decoding_error(DecodingError {
-                    from: value.clone(),
-                    to: "Integer".to_string(),
-                    cause: DecodingErrors::NotEnough(
+                return Err(decoding_error(DecodingError::new(
+                    value.to_vec(),
+                    "Double",
+                    DecodingErrors::NotEnough(
                         "Bytes".to_string(),
                         4,
-                        value.len().try_into().unwrap(),
+                        value.len().try_into().unwrap_or(u32::MAX),
                     ),
-                }))
+                )))
             }
         };
 
-        let records = slice_records(m_value.clone());
+        let prefix_len = value.len() - m_value.len();
+
+        let records = slice_records(&m_value)
+            .map_err(|e| decoding_error(e.inside(prefix_len).within(value)))?;
 
         if records.len() > 1 {
-            return Err(decoding_error(DecodingError {
-                from: value,
-                to: "Double".to_string(),
-                cause: DecodingErrors::TooMuch(
+            return Err(decoding_error(DecodingError::new(
+                value.to_vec(),
+                "Double",
+                DecodingErrors::TooMuch(
                     "Records".to_string(),
                     1,
-                    records.len().try_into().unwrap(),
+                    records.len().try_into().unwrap_or(u32::MAX),
                 ),
-            }));
+            )));
         }
 
-        let digits: Vec<u8> = match records.first() {
+        let record_start = prefix_len + records.first().map_or(0, |range| range.start);
+
+        let digits: Vec<u8> = match records.first().map(|range| &m_value[range.clone()]) {
             Some(entries) => entries.to_vec(),
             None => {
-                return Err(decoding_error(DecodingError {
-                    from: value.clone(),
-                    to: "Integer".to_string(),
-                    cause: DecodingErrors::NotEnough(
+                return Err(decoding_error(DecodingError::new(
+                    value.to_vec(),
+                    "Double",
+                    DecodingErrors::NotEnough(
                         "Bytes".to_string(),
                         4,
-                        value.len().try_into().unwrap(),
+                        value.len().try_into().unwrap_or(u32::MAX),
                     ),
-                }))
+                )))
             }
         };
 
-        let mut number = String::new();
+        let sign = if negative { -1f64 } else { 1f64 };
+
+        match digits.as_slice() {
+            [ZERO_MARKER] => return Ok(sign * 0f64),
+            [INFINITY_MARKER] => return Ok(sign * f64::INFINITY),
+            [NAN_MARKER, bits @ ..] => {
+                let mut nan_bits: u64 = 0;
+
+                for (position, digit) in bits.iter().enumerate() {
+                    let next_bits = match *digit {
+                        0..=9 => nan_bits
+                            .checked_mul(10)
+                            .and_then(|b| b.checked_add((*digit).into())),
+                        _ => None,
+                    };
+
+                    nan_bits = match next_bits {
+                        Some(b) => b,
+                        None => {
+                            return Err(decoding_error(
+                                DecodingError::new(
+                                    value.to_vec(),
+                                    "Double",
+                                    DecodingErrors::InvalidByte(
+                                        *digit,
+                                        (position + 1).try_into().unwrap_or(u32::MAX),
+                                        (0..=9).collect(),
+                                    ),
+                                )
+                                .at(record_start + position + 1),
+                            ))
+                        }
+                    };
+                }
 
-        let digits_count: i64 = match digits.len().try_into() {
-            Ok(digits_count) => digits_count,
-            Err(_) => {
-                return Err(decoding_error(DecodingError {
-                    cause: DecodingErrors::SizeConversionError(
-                        digits.len().to_string(),
-                        "i64".to_string(),
-                    ),
-                    from: value,
-                    to: "Integer".to_string(),
-                }))
+                let nan = f64::from_bits(nan_bits);
+
+                if !nan.is_nan() {
+                    return Err(decoding_error(DecodingError::new(
+                        value.to_vec(),
+                        "Double",
+                        DecodingErrors::CantFitValues(format!(
+                            "{} is not the bit pattern of a NaN",
+                            nan_bits
+                        )),
+                    )));
+                }
+
+                return Ok(nan);
             }
-        };
+            _ => {}
+        }
 
-        for i in 0..digits_count {
-            let index: usize = match (i).try_into() {
-                Ok(index) => index,
-                Err(_) => {
-                    return Err(decoding_error(DecodingError {
-                        from: value,
-                        to: "Integer".to_string(),
-                        cause: DecodingErrors::SizeConversionError(
-                            i.to_string(),
-                            "usize".to_string(),
-                        ),
-                    }))
-                }
-            };
+        let mut number = std::string::String::new();
 
-            if digits[index] != b'.' {
-                number += (digits[index]).to_string().as_str()
-            } else {
-                number += "."
-            };
+        if negative {
+            number.push('-');
         }
 
-        let abs_value: f64 = match number.parse() {
-            Ok(value) => value,
-            Err(e) => {
-                return Err(decoding_error(DecodingError::new(
-                    value,
-                    "Double",
-                    DecodingErrors::InternalError(Box::new(e)),
-                )))
+        for (position, digit) in digits.iter().enumerate() {
+            match *digit {
+                0..=9 => number.push(char::from(b'0' + digit)),
+                b'.' | EXPONENT_MARKER | PLUS | MINUS => number.push(char::from(*digit)),
+                _ => {
+                    return Err(decoding_error(
+                        DecodingError::new(
+                            value.to_vec(),
+                            "Double",
+                            DecodingErrors::InvalidByte(
+                                *digit,
+                                position.try_into().unwrap_or(u32::MAX),
+                                vec![b'.', EXPONENT_MARKER, PLUS, MINUS],
+                            ),
+                        )
+                        .at(record_start + position),
+                    ))
+                }
             }
-        };
+        }
+
+        match number.parse() {
+            Ok(value) => Ok(value),
+            Err(e) => Err(decoding_error(DecodingError::new(
+                value.to_vec(),
+                "Double",
+                DecodingErrors::InternalError(Box::new(e)),
+            ))),
+        }
+    }
+}
+
+/// Fixed stack buffer for `{:e}`, which never needs more than 24 bytes for an f64.
+#[derive(Default)]
+struct FormatBuffer {
+    bytes: [u8; 32],
+    len: usize,
+}
+
+impl FormatBuffer {
+    fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.bytes[..self.len]).unwrap_or_default()
+    }
+}
+
+impl fmt::Write for FormatBuffer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+
+        if end > self.bytes.len() {
+            return Err(fmt::Error);
+        }
 
-        Ok(abs_value * signal)
+        self.bytes[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+
+        Ok(())
     }
 }
 
@@ -306,20 +328,21 @@ mod tests {
     use crate::{
         commom::{
             delimiters::{BUFFER_END, END_RECORD, START_RECORD},
-            CoprotoType, ValueOrBuffer,
+            Codec, Lazy, ValueOrBuffer,
         },
         types::Double,
     };
 
     #[test]
     fn test_positive() {
-        let positive_encoding = Double::new(ValueOrBuffer::Value(123.456));
+        let positive_encoding = Lazy::<Double>::new(ValueOrBuffer::Value(123.456));
 
-        let positive_encoding_buff = positive_encoding.buff.unwrap();
+        let positive_encoding_buff = positive_encoding.buff().unwrap().to_vec();
 
-        let positive_decoding = Double::new(ValueOrBuffer::Buffer(positive_encoding_buff.clone()));
+        let positive_decoding =
+            Lazy::<Double>::new(ValueOrBuffer::Buffer(positive_encoding_buff.clone()));
 
-        let positive_decoding_buff = positive_decoding.buff.unwrap();
+        let positive_decoding_buff = positive_decoding.buff().unwrap().to_vec();
 
         assert_eq!(
             positive_encoding_buff,
@@ -330,16 +353,18 @@ mod tests {
                 1,
                 2,
                 3,
-                b'.',
                 4,
                 5,
                 6,
+                b'e',
+                b'-',
+                3,
                 END_RECORD,
                 BUFFER_END
             ]
         );
 
-        assert_eq!(positive_encoding.value_of.unwrap(), 123.456);
+        assert_eq!(*positive_encoding.value().unwrap(), 123.456);
 
         assert_eq!(
             positive_decoding_buff,
@@ -350,21 +375,23 @@ mod tests {
                 1,
                 2,
                 3,
-                b'.',
                 4,
                 5,
                 6,
+                b'e',
+                b'-',
+                3,
                 END_RECORD,
                 BUFFER_END
             ]
         );
 
-        assert_eq!(positive_decoding.value_of.unwrap(), 123.456);
+        assert_eq!(*positive_decoding.value().unwrap(), 123.456);
     }
 
     #[test]
     fn no_signal() {
-        let positive_decoding = Double::new(ValueOrBuffer::Buffer(vec![
+        let positive_decoding = Lazy::<Double>::new(ValueOrBuffer::Buffer(vec![
             b';',
             START_RECORD,
             1,
@@ -377,18 +404,19 @@ mod tests {
             END_RECORD,
             BUFFER_END,
         ]));
-        assert_eq!(positive_decoding.value_of.unwrap(), 123.456);
+        assert_eq!(*positive_decoding.value().unwrap(), 123.456);
     }
 
     #[test]
     fn test_negative() {
-        let negative_encoding = Double::new(ValueOrBuffer::Value(-123.456));
+        let negative_encoding = Lazy::<Double>::new(ValueOrBuffer::Value(-123.456));
 
-        let negative_encoding_buff = negative_encoding.buff.unwrap();
+        let negative_encoding_buff = negative_encoding.buff().unwrap().to_vec();
 
-        let negative_decoding = Double::new(ValueOrBuffer::Buffer(negative_encoding_buff.clone()));
+        let negative_decoding =
+            Lazy::<Double>::new(ValueOrBuffer::Buffer(negative_encoding_buff.clone()));
 
-        let negative_decoding_buff = negative_decoding.buff.unwrap();
+        let negative_decoding_buff = negative_decoding.buff().unwrap().to_vec();
 
         assert_eq!(
             negative_encoding_buff,
@@ -399,16 +427,18 @@ mod tests {
                 1,
                 2,
                 3,
-                b'.',
                 4,
                 5,
                 6,
+                b'e',
+                b'-',
+                3,
                 END_RECORD,
                 BUFFER_END
             ]
         );
 
-        assert_eq!(negative_encoding.value_of.unwrap(), -123.456);
+        assert_eq!(*negative_encoding.value().unwrap(), -123.456);
 
         assert_eq!(
             negative_decoding_buff,
@@ -419,39 +449,145 @@ mod tests {
                 1,
                 2,
                 3,
-                b'.',
                 4,
                 5,
                 6,
+                b'e',
+                b'-',
+                3,
                 END_RECORD,
                 BUFFER_END
             ]
         );
 
-        assert_eq!(negative_decoding.value_of.unwrap(), -123.456);
+        assert_eq!(*negative_decoding.value().unwrap(), -123.456);
     }
 
     #[test]
     fn test_encoding_decoding() {
-        let positive_encoding = Double::new(ValueOrBuffer::Value(123.456));
-        let positive_buff = positive_encoding.buff.unwrap();
-        let positive_decoding = Double::new(ValueOrBuffer::Buffer(positive_buff));
+        let positive_encoding = Lazy::<Double>::new(ValueOrBuffer::Value(123.456));
+        let positive_buff = positive_encoding.buff().unwrap().to_vec();
+        let positive_decoding = Lazy::<Double>::new(ValueOrBuffer::Buffer(positive_buff));
 
         assert_eq!(
-            positive_decoding.value_of.unwrap(),
-            positive_encoding.value_of.unwrap()
+            *positive_decoding.value().unwrap(),
+            *positive_encoding.value().unwrap()
         );
 
-        let negative_encoding = Double::new(ValueOrBuffer::Value(-123.456));
-        let negative_buff = negative_encoding.buff.unwrap();
-        let negative_decoding = Double::new(ValueOrBuffer::Buffer(negative_buff));
+        let negative_encoding = Lazy::<Double>::new(ValueOrBuffer::Value(-123.456));
+        let negative_buff = negative_encoding.buff().unwrap().to_vec();
+        let negative_decoding = Lazy::<Double>::new(ValueOrBuffer::Buffer(negative_buff));
 
         assert_eq!(
-            negative_decoding.value_of.unwrap(),
-            negative_encoding.value_of.unwrap()
+            *negative_decoding.value().unwrap(),
+            *negative_encoding.value().unwrap()
         );
     }
 
+    fn assert_round_trip(value: f64) {
+        let encoded = Double::encode(&value).unwrap();
+        let decoded = Double::decode(&encoded).unwrap();
+
+        assert_eq!(
+            decoded.to_bits(),
+            value.to_bits(),
+            "{:e} came back as {:e} from {:?}",
+            value,
+            decoded,
+            encoded
+        );
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        let edge_cases = [
+            0f64,
+            -0f64,
+            1.05,
+            -1.05,
+            0.1,
+            0.000123,
+            1e-7,
+            1e21,
+            1e300,
+            -1e300,
+            i32::MAX as f64 * 10f64,
+            i64::MAX as f64,
+            u64::MAX as f64,
+            f64::MAX,
+            f64::MIN,
+            f64::MIN_POSITIVE,
+            -f64::MIN_POSITIVE,
+            f64::EPSILON,
+            f64::from_bits(1),
+            -f64::from_bits(1),
+            f64::from_bits(0x000f_ffff_ffff_ffff),
+            std::f64::consts::PI,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NAN,
+            -f64::NAN,
+            f64::from_bits(0x7ff0_0000_0000_0001),
+            f64::from_bits(0xfff8_dead_beef_0001),
+        ];
+
+        for value in edge_cases {
+            assert_round_trip(value);
+        }
+    }
+
+    #[test]
+    fn test_zero_signs() {
+        let positive_zero = Double::encode(&0f64).unwrap();
+        let negative_zero = Double::encode(&-0f64).unwrap();
+
+        assert_eq!(
+            positive_zero,
+            vec![b';', b'+', START_RECORD, b'Z', END_RECORD, BUFFER_END]
+        );
+        assert_eq!(
+            negative_zero,
+            vec![b';', b'-', START_RECORD, b'Z', END_RECORD, BUFFER_END]
+        );
+    }
+
+    #[test]
+    fn test_random_bit_patterns() {
+        let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+
+        for _ in 0..100_000 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+
+            assert_round_trip(f64::from_bits(state));
+        }
+    }
+
+    #[test]
+    fn test_random_decimals() {
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+
+        for _ in 0..100_000 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+
+            let digits = (state % 1_000_000_000) as f64;
+            let scale = 10f64.powi((state >> 40) as i32 % 40 - 20);
+
+            assert_round_trip(digits * scale);
+            assert_round_trip(-digits / scale);
+        }
+    }
+
+    #[test]
+    fn test_invalid_nan_payload() {
+        let buff = vec![b';', b'+', START_RECORD, b'N', 0, END_RECORD, BUFFER_END];
+
+        assert!(Double::decode(&buff).is_err());
+    }
+
     #[test]
     fn wrong_buffer() {
         let buff = vec![
@@ -468,15 +604,15 @@ mod tests {
             BUFFER_END,
         ];
 
-        let wrong = Double::new(ValueOrBuffer::Buffer(buff));
+        let wrong = Lazy::<Double>::new(ValueOrBuffer::Buffer(buff));
 
-        match wrong.value_of {
+        match wrong.value() {
             Ok(_) => false,
             Err(e) => match e {
                 crate::commom::errors::TypeError::Encoding(_) => false,
                 crate::commom::errors::TypeError::Decoding(decoding_error) => {
                     matches!(
-                        decoding_error.cause,
+                        *decoding_error.cause,
                         crate::commom::errors::DecodingErrors::FirstByteError(_, _, _)
                     )
                 }
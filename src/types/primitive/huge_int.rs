@@ -0,0 +1,229 @@
+use std::io::Write;
+
+use super::BigNum;
+use crate::commom::decode_limits::check_limit;
+use crate::commom::{
+    delimiters::{END_RECORD, START_RECORD},
+    errors::{decoding_error, DecodingError, DecodingErrors, TypeResult},
+    modifiers::{MINUS, PLUS},
+    slice_records, take_first_byte, write_bytes, Codec, DecodeLimits,
+};
+
+#[derive(Debug)]
+pub struct HugeInt;
+
+impl Codec for HugeInt {
+    type Value = BigNum;
+    const FIRST_BYTE: u8 = b'!';
+
+    fn write_value<W: Write>(value: &BigNum, writer: &mut W) -> TypeResult<()> {
+        let signal: u8 = if value.is_negative() { MINUS } else { PLUS };
+
+        write_bytes(writer, &[Self::FIRST_BYTE, signal, START_RECORD])?;
+        value.write_digits(writer)?;
+        write_bytes(writer, &[END_RECORD])
+    }
+
+    fn decode_with(value: &[u8], limits: &DecodeLimits) -> TypeResult<BigNum> {
+        check_limit(
+            value,
+            "HugeInt",
+            "Message size",
+            limits.max_message_size,
+            value.len(),
+        )
+        .map_err(decoding_error)?;
+
+        let mut m_value = value.to_vec();
+
+        let first_byte = take_first_byte(&mut m_value, "HugeInt").map_err(decoding_error)?;
+
+        if first_byte != Self::FIRST_BYTE {
+            return Err(decoding_error(
+                DecodingError::new(
+                    value.to_vec(),
+                    "HugeInt",
+                    DecodingErrors::FirstByteError(
+                        "HugeInt".to_string(),
+                        Self::FIRST_BYTE,
+                        first_byte,
+                    ),
+                )
+                .at(0),
+            ));
+        };
+
+        let negative = match m_value.first() {
+            Some(b) => match *b {
+                MINUS => {
+                    m_value.remove(0);
+                    true
+                }
+                PLUS => {
+                    m_value.remove(0);
+                    false
+                }
+                _ => false,
+            },
+            None => {
+                return Err(decoding_error(DecodingError::new(
+                    value.to_vec(),
+                    "HugeInt",
+                    DecodingErrors::NotEnough(
+                        "Bytes".to_string(),
+                        4,
+                        value.len().try_into().unwrap_or(u32::MAX),
+                    ),
+                )))
+            }
+        };
+
+        let prefix_len = value.len() - m_value.len();
+
+        let records = slice_records(&m_value)
+            .map_err(|e| decoding_error(e.inside(prefix_len).within(value)))?;
+
+        if records.len() > 1 {
+            return Err(decoding_error(DecodingError::new(
+                value.to_vec(),
+                "HugeInt",
+                DecodingErrors::TooMuch(
+                    "Records".to_string(),
+                    1,
+                    records.len().try_into().unwrap_or(u32::MAX),
+                ),
+            )));
+        };
+
+        let record_start = prefix_len + records.first().map_or(0, |range| range.start);
+
+        let digits: Vec<u8> = match records.first().map(|range| &m_value[range.clone()]) {
+            Some(entries) => entries.to_vec(),
+            None => {
+                return Err(decoding_error(DecodingError::new(
+                    value.to_vec(),
+                    "HugeInt",
+                    DecodingErrors::NotEnough(
+                        "Bytes".to_string(),
+                        4,
+                        value.len().try_into().unwrap_or(u32::MAX),
+                    ),
+                )))
+            }
+        };
+
+        check_limit(
+            value,
+            "HugeInt",
+            "HugeInt digits",
+            limits.max_digits,
+            digits.len(),
+        )
+        .map_err(decoding_error)?;
+
+        match BigNum::from_digits(negative, &digits) {
+            Some(number) => Ok(number),
+            None => {
+                let (position, digit) = digits
+                    .iter()
+                    .enumerate()
+                    .find(|(_, d)| **d > 9)
+                    .unwrap_or((0, &0));
+
+                Err(decoding_error(
+                    DecodingError::new(
+                        value.to_vec(),
+                        "HugeInt",
+                        DecodingErrors::InvalidByte(
+                            *digit,
+                            position.try_into().unwrap_or(u32::MAX),
+                            (0..=9).collect(),
+                        ),
+                    )
+                    .at(record_start + position),
+                ))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::{
+        commom::{
+            delimiters::{BUFFER_END, END_RECORD, START_RECORD},
+            Codec, Lazy, ValueOrBuffer,
+        },
+        types::{BigNum, HugeInt},
+    };
+
+    #[test]
+    fn test_encoding() {
+        let encoding = Lazy::<HugeInt>::new(ValueOrBuffer::Value(BigNum::from(-1000)));
+
+        assert_eq!(
+            encoding.buff().unwrap().to_vec(),
+            vec![b'!', b'-', START_RECORD, 1, 0, 0, 0, END_RECORD, BUFFER_END]
+        );
+    }
+
+    #[test]
+    fn test_encoding_decoding() {
+        let values = [
+            BigNum::zero(),
+            BigNum::from(1),
+            BigNum::from(-1),
+            BigNum::from(i64::MIN),
+            BigNum::from(u128::MAX),
+            BigNum::from(i128::MIN),
+            BigNum::from_str("-98765432109876543210987654321098765432109876543210").unwrap(),
+        ];
+
+        for value in values {
+            let encoding = Lazy::<HugeInt>::new(ValueOrBuffer::Value(value.clone()));
+            let decoding =
+                Lazy::<HugeInt>::new(ValueOrBuffer::Buffer(encoding.buff().unwrap().to_vec()));
+
+            assert_eq!(*decoding.value().unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_invalid_digit() {
+        let buff = vec![b'!', b'+', START_RECORD, 1, 10, END_RECORD, BUFFER_END];
+
+        let invalid = HugeInt::decode(&buff);
+
+        match invalid {
+            Err(crate::commom::errors::TypeError::Decoding(decoding_error)) => {
+                assert!(matches!(
+                    *decoding_error.cause,
+                    crate::commom::errors::DecodingErrors::InvalidByte(10, 1, _)
+                ))
+            }
+            other => panic!("Expected an invalid byte error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn wrong_buffer() {
+        let buff = vec![b'?', START_RECORD, 1, 0, 0, 0, END_RECORD, BUFFER_END];
+
+        let wrong = Lazy::<HugeInt>::new(ValueOrBuffer::Buffer(buff));
+
+        match wrong.value() {
+            Ok(_) => false,
+            Err(e) => match e {
+                crate::commom::errors::TypeError::Encoding(_) => false,
+                crate::commom::errors::TypeError::Decoding(decoding_error) => {
+                    matches!(
+                        *decoding_error.cause,
+                        crate::commom::errors::DecodingErrors::FirstByteError(_, _, _)
+                    )
+                }
+            },
+        };
+    }
+}
@@ -1,124 +1,55 @@
+use std::io::Write;
+
+use crate::commom::{decode_limits::check_limit, DecodeLimits};
 use crate::commom::{
-    delimiters::{BUFFER_END, END_RECORD, START_RECORD},
-    errors::{
-        decoding_error, encoding_error, DecodingError, DecodingErrors, EncodingError,
-        EncodingErrors, TypeResult,
-    },
-    join_parts,
+    delimiters::{END_RECORD, START_RECORD},
+    errors::{decoding_error, DecodingError, DecodingErrors, TypeResult},
     modifiers::{MINUS, PLUS},
-    slice_records, to_ascii_code, BuffPart, CoprotoType, Uint8Buff, ValueOrBuffer,
+    slice_records, take_first_byte, write_bytes, write_digits, Codec,
 };
 
 #[derive(Debug)]
-pub struct Integer {
-    pub first_byte: u8,
-    pub modifier_byte: Option<u8>,
-    pub modifier_char: Option<char>,
-    pub first_char: char,
-    pub value_of: TypeResult<i32>,
-    pub buff: TypeResult<Uint8Buff>,
-}
+pub struct Integer;
 
-impl CoprotoType<i32> for Integer {
+impl Codec for Integer {
+    type Value = i32;
     const FIRST_BYTE: u8 = b':';
-    fn new(value: ValueOrBuffer<i32>) -> Self {
-        match value {
-            ValueOrBuffer::Value(value) => Self {
-                first_byte: b':',
-                first_char: ':',
-                modifier_byte: Some(if value < 0 { b'-' } else { b'+' }),
-                modifier_char: Some(if value < 0 { '-' } else { '+' }),
-                value_of: Ok(value),
-                buff: Self::encode(value),
-            },
-            ValueOrBuffer::Buffer(vec) => {
-                let value = Self::decode(vec.clone());
-                let modifier_char = match value {
-                    Ok(value) => Some(if value < 0 { '-' } else { '+' }),
-                    Err(_) => None,
-                };
-
-                let modifier_byte = match modifier_char {
-                    Some(char) => match to_ascii_code(char) {
-                        Ok(c) => Some(c),
-                        Err(_) => todo!(),
-                    },
-                    None => None,
-                };
-                Self {
-                    first_byte: b':',
-                    first_char: ':',
-                    modifier_char,
-                    modifier_byte,
-                    value_of: value,
-                    buff: Ok(vec.clone()),
-                }
-            }
-        }
-    }
-
-    fn encode(value: i32) -> TypeResult<Uint8Buff> {
-        let signal: u8 = if value < 0 { MINUS } else { PLUS };
-
-        let mut abs_value: i32 = value.abs();
-
-        let mut digits_arr: Vec<u8> = vec![];
-
-        let mut digit: u8 = match (abs_value % 10).try_into() {
-            Ok(digit) => digit,
-            Err(e) => {
-                return Err(encoding_error(EncodingError {
-                    from: value.to_string(),
-                    to: "u8 digit".to_string(),
-                    origin: EncodingErrors::InternalError(Box::new(e)),
-                }))
-            }
-        };
 
-        while abs_value != 0 {
-            digits_arr.insert(0, digit);
+    fn write_value<W: Write>(value: &i32, writer: &mut W) -> TypeResult<()> {
+        let signal: u8 = if *value < 0 { MINUS } else { PLUS };
 
-            abs_value /= 10;
-
-            digit = match (abs_value % 10).try_into() {
-                Ok(digit) => digit,
-                Err(e) => {
-                    return Err(encoding_error(EncodingError {
-                        from: value.to_string(),
-                        to: "u8 digit".to_string(),
-                        origin: EncodingErrors::InternalError(Box::new(e)),
-                    }))
-                }
-            };
-        }
-
-        let parts: Vec<BuffPart> = vec![
-            BuffPart::Val(b':'),
-            BuffPart::Val(signal),
-            BuffPart::Val(START_RECORD),
-            BuffPart::Arr(digits_arr),
-            BuffPart::Val(END_RECORD),
-            BuffPart::Val(BUFFER_END),
-        ];
-
-        Ok(join_parts(parts))
+        write_bytes(writer, &[Self::FIRST_BYTE, signal, START_RECORD])?;
+        write_digits(writer, value.unsigned_abs().into())?;
+        write_bytes(writer, &[END_RECORD])
     }
 
-    fn decode(value: Uint8Buff) -> TypeResult<i32> {
-        let mut m_value = value.clone();
+    fn decode_with(value: &[u8], limits: &DecodeLimits) -> TypeResult<i32> {
+        check_limit(
+            value,
+            "Integer",
+            "Message size",
+            limits.max_message_size,
+            value.len(),
+        )
+        .map_err(decoding_error)?;
+
+        let mut m_value = value.to_vec();
 
-        let first_byte = m_value.remove(0);
+        let first_byte = take_first_byte(&mut m_value, "Integer").map_err(decoding_error)?;
 
         if first_byte != Self::FIRST_BYTE {
-            return Err(decoding_error(DecodingError {
-                from: value,
-                to: "Integer".to_string(),
-                cause: DecodingErrors::FirstByteError(
-                    "Integer".to_string(),
-                    Self::FIRST_BYTE,
-                    first_byte,
-                ),
-            }));
+            return Err(decoding_error(
+                DecodingError::new(
+                    value.to_vec(),
+                    "Integer",
+                    DecodingErrors::FirstByteError(
+                        "Integer".to_string(),
+                        Self::FIRST_BYTE,
+                        first_byte,
+                    ),
+                )
+                .at(0),
+            ));
         };
 
         let signal = match m_value.first() {
@@ -134,101 +65,92 @@ impl CoprotoType<i32> for Integer {
                 _ => 1,
             },
             None => {
-                return Err(decoding_error(DecodingError {
-                    from: value.clone(),
-                    to: "Integer".to_string(),
-                    cause: DecodingErrors::NotEnough(
+                return Err(decoding_error(DecodingError::new(
+                    value.to_vec(),
+                    "Integer",
+                    DecodingErrors::NotEnough(
                         "Bytes".to_string(),
                         4,
-                        value.len().try_into().unwrap(),
+                        value.len().try_into().unwrap_or(u32::MAX),
                     ),
-                }))
+                )))
             }
         };
 
-        let records = slice_records(m_value.clone());
+        let prefix_len = value.len() - m_value.len();
+
+        let records = slice_records(&m_value)
+            .map_err(|e| decoding_error(e.inside(prefix_len).within(value)))?;
 
         if records.len() > 1 {
-            return Err(decoding_error(DecodingError {
-                from: value,
-                to: "Integer".to_string(),
-                cause: DecodingErrors::TooMuch(
+            return Err(decoding_error(DecodingError::new(
+                value.to_vec(),
+                "Integer",
+                DecodingErrors::TooMuch(
                     "Records".to_string(),
                     1,
-                    records.len().try_into().unwrap(),
+                    records.len().try_into().unwrap_or(u32::MAX),
                 ),
-            }));
+            )));
         };
 
-        let digits: Vec<u8> = match records.first() {
+        let record_start = prefix_len + records.first().map_or(0, |range| range.start);
+
+        let digits: Vec<u8> = match records.first().map(|range| &m_value[range.clone()]) {
             Some(entries) => entries.to_vec(),
             None => {
-                return Err(decoding_error(DecodingError {
-                    from: value.clone(),
-                    to: "Integer".to_string(),
-                    cause: DecodingErrors::NotEnough(
+                return Err(decoding_error(DecodingError::new(
+                    value.to_vec(),
+                    "Integer",
+                    DecodingErrors::NotEnough(
                         "Bytes".to_string(),
                         4,
-                        value.len().try_into().unwrap(),
+                        value.len().try_into().unwrap_or(u32::MAX),
                     ),
-                }))
+                )))
             }
         };
 
         let mut number: i32 = 0;
 
-        let digits_count: i64 = match digits.len().try_into() {
-            Ok(digits_count) => digits_count,
-            Err(_) => {
-                return Err(decoding_error(DecodingError {
-                    cause: DecodingErrors::SizeConversionError(
-                        digits.len().to_string(),
-                        "i64".to_string(),
-                    ),
-                    from: value,
-                    to: "Integer".to_string(),
-                }))
+        for (position, digit) in digits.iter().enumerate() {
+            if *digit > 9 {
+                return Err(decoding_error(
+                    DecodingError::new(
+                        value.to_vec(),
+                        "Integer",
+                        DecodingErrors::InvalidByte(
+                            *digit,
+                            position.try_into().unwrap_or(u32::MAX),
+                            (0..=9).collect(),
+                        ),
+                    )
+                    .at(record_start + position),
+                ));
             }
-        };
 
-        for i in 0..digits_count {
-            let index: usize = match (i).try_into() {
-                Ok(index) => index,
-                Err(_) => {
-                    return Err(decoding_error(DecodingError {
-                        from: value,
-                        to: "Integer".to_string(),
-                        cause: DecodingErrors::SizeConversionError(
-                            i.to_string(),
-                            "usize".to_string(),
-                        ),
-                    }))
-                }
+            let d_32: i32 = (*digit).into();
+
+            let next = match signal {
+                -1 => number.checked_mul(10).and_then(|n| n.checked_sub(d_32)),
+                _ => number.checked_mul(10).and_then(|n| n.checked_add(d_32)),
             };
 
-            let position: u32 = match (digits_count - i - 1).try_into() {
-                Ok(position) => position,
-                Err(_) => {
-                    return Err(decoding_error(DecodingError {
-                        from: value,
-                        to: "Integer".to_string(),
-                        cause: DecodingErrors::SizeConversionError(
-                            i.to_string(),
-                            "usize".to_string(),
+            number = match next {
+                Some(n) => n,
+                None => {
+                    return Err(decoding_error(DecodingError::new(
+                        value.to_vec(),
+                        "Integer",
+                        DecodingErrors::Overflow(
+                            digits.iter().map(|d| d.to_string()).collect(),
+                            "i32".to_string(),
                         ),
-                    }))
+                    )))
                 }
             };
-
-            let d_64: i32 = (digits[index]).into();
-
-            let part = d_64 * (10i32.pow(position));
-
-            number += part;
         }
 
-        number *= signal;
-
         Ok(number)
     }
 }
@@ -238,39 +160,40 @@ mod tests {
     use crate::{
         commom::{
             delimiters::{BUFFER_END, END_RECORD, START_RECORD},
-            CoprotoType, ValueOrBuffer,
+            Codec, Lazy, ValueOrBuffer,
         },
         types::Integer,
     };
 
     #[test]
     fn test_positive() {
-        let positive_encoding = Integer::new(ValueOrBuffer::Value(1000));
+        let positive_encoding = Lazy::<Integer>::new(ValueOrBuffer::Value(1000));
 
-        let positive_encoding_buff = positive_encoding.buff.unwrap();
+        let positive_encoding_buff = positive_encoding.buff().unwrap().to_vec();
 
-        let positive_decoding = Integer::new(ValueOrBuffer::Buffer(positive_encoding_buff.clone()));
+        let positive_decoding =
+            Lazy::<Integer>::new(ValueOrBuffer::Buffer(positive_encoding_buff.clone()));
 
-        let positive_decoding_buff = positive_decoding.buff.unwrap();
+        let positive_decoding_buff = positive_decoding.buff().unwrap().to_vec();
 
         assert_eq!(
             positive_encoding_buff,
             vec![b':', b'+', START_RECORD, 1, 0, 0, 0, END_RECORD, BUFFER_END]
         );
 
-        assert_eq!(positive_encoding.value_of.unwrap(), 1000);
+        assert_eq!(*positive_encoding.value().unwrap(), 1000);
 
         assert_eq!(
             positive_decoding_buff,
             vec![b':', b'+', START_RECORD, 1, 0, 0, 0, END_RECORD, BUFFER_END]
         );
 
-        assert_eq!(positive_decoding.value_of.unwrap(), 1000);
+        assert_eq!(*positive_decoding.value().unwrap(), 1000);
     }
 
     #[test]
     fn no_signal() {
-        let positive_decoding = Integer::new(ValueOrBuffer::Buffer(vec![
+        let positive_decoding = Lazy::<Integer>::new(ValueOrBuffer::Buffer(vec![
             b':',
             START_RECORD,
             1,
@@ -280,68 +203,109 @@ mod tests {
             END_RECORD,
             BUFFER_END,
         ]));
-        assert_eq!(positive_decoding.value_of.unwrap(), 1000);
+        assert_eq!(*positive_decoding.value().unwrap(), 1000);
     }
 
     #[test]
     fn test_negative() {
-        let negative_encoding = Integer::new(ValueOrBuffer::Value(-1000));
+        let negative_encoding = Lazy::<Integer>::new(ValueOrBuffer::Value(-1000));
 
-        let negative_encoding_buff = negative_encoding.buff.unwrap();
+        let negative_encoding_buff = negative_encoding.buff().unwrap().to_vec();
 
-        let negative_decoding = Integer::new(ValueOrBuffer::Buffer(negative_encoding_buff.clone()));
+        let negative_decoding =
+            Lazy::<Integer>::new(ValueOrBuffer::Buffer(negative_encoding_buff.clone()));
 
-        let negative_decoding_buff = negative_decoding.buff.unwrap();
+        let negative_decoding_buff = negative_decoding.buff().unwrap().to_vec();
 
         assert_eq!(
             negative_encoding_buff,
             vec![b':', b'-', START_RECORD, 1, 0, 0, 0, END_RECORD, BUFFER_END]
         );
 
-        assert_eq!(negative_encoding.value_of.unwrap(), -1000);
+        assert_eq!(*negative_encoding.value().unwrap(), -1000);
 
         assert_eq!(
             negative_decoding_buff,
             vec![b':', b'-', START_RECORD, 1, 0, 0, 0, END_RECORD, BUFFER_END]
         );
 
-        assert_eq!(negative_decoding.value_of.unwrap(), -1000);
+        assert_eq!(*negative_decoding.value().unwrap(), -1000);
     }
 
     #[test]
     fn test_encoding_decoding() {
-        let positive_encoding = Integer::new(ValueOrBuffer::Value(1000));
-        let positive_buff = positive_encoding.buff.unwrap();
-        let positive_decoding = Integer::new(ValueOrBuffer::Buffer(positive_buff));
+        let positive_encoding = Lazy::<Integer>::new(ValueOrBuffer::Value(1000));
+        let positive_buff = positive_encoding.buff().unwrap().to_vec();
+        let positive_decoding = Lazy::<Integer>::new(ValueOrBuffer::Buffer(positive_buff));
 
         assert_eq!(
-            positive_decoding.value_of.unwrap(),
-            positive_encoding.value_of.unwrap()
+            *positive_decoding.value().unwrap(),
+            *positive_encoding.value().unwrap()
         );
 
-        let negative_encoding = Integer::new(ValueOrBuffer::Value(-1000));
-        let negative_buff = negative_encoding.buff.unwrap();
-        let negative_decoding = Integer::new(ValueOrBuffer::Buffer(negative_buff));
+        let negative_encoding = Lazy::<Integer>::new(ValueOrBuffer::Value(-1000));
+        let negative_buff = negative_encoding.buff().unwrap().to_vec();
+        let negative_decoding = Lazy::<Integer>::new(ValueOrBuffer::Buffer(negative_buff));
 
         assert_eq!(
-            negative_decoding.value_of.unwrap(),
-            negative_encoding.value_of.unwrap()
+            *negative_decoding.value().unwrap(),
+            *negative_encoding.value().unwrap()
         );
     }
 
+    #[test]
+    fn test_boundaries() {
+        for value in [i32::MIN, i32::MIN + 1, -1, 0, 1, i32::MAX - 1, i32::MAX] {
+            let encoding = Lazy::<Integer>::new(ValueOrBuffer::Value(value));
+            let decoding =
+                Lazy::<Integer>::new(ValueOrBuffer::Buffer(encoding.buff().unwrap().to_vec()));
+
+            assert_eq!(*decoding.value().unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_overflow() {
+        let mut min_buff = vec![b':', b'-', START_RECORD, 2, 1, 4, 7, 4, 8, 3, 6, 4, 8];
+        min_buff.extend([END_RECORD, BUFFER_END]);
+
+        assert_eq!(Integer::decode(&min_buff).unwrap(), i32::MIN);
+
+        min_buff[1] = b'+';
+
+        let positive_overflow = Integer::decode(&min_buff);
+
+        let mut below_min_buff = vec![b':', b'-', START_RECORD, 2, 1, 4, 7, 4, 8, 3, 6, 4, 9];
+        below_min_buff.extend([END_RECORD, BUFFER_END]);
+
+        let negative_overflow = Integer::decode(&below_min_buff);
+
+        for result in [positive_overflow, negative_overflow] {
+            match result {
+                Err(crate::commom::errors::TypeError::Decoding(decoding_error)) => {
+                    assert!(matches!(
+                        *decoding_error.cause,
+                        crate::commom::errors::DecodingErrors::Overflow(_, _)
+                    ))
+                }
+                other => panic!("Expected an overflow error, got {:?}", other),
+            }
+        }
+    }
+
     #[test]
     fn wrong_buffer() {
         let buff = vec![b'?', START_RECORD, 1, 0, 0, 0, END_RECORD, BUFFER_END];
 
-        let wrong = Integer::new(ValueOrBuffer::Buffer(buff));
+        let wrong = Lazy::<Integer>::new(ValueOrBuffer::Buffer(buff));
 
-        match wrong.value_of {
+        match wrong.value() {
             Ok(_) => false,
             Err(e) => match e {
                 crate::commom::errors::TypeError::Encoding(_) => false,
                 crate::commom::errors::TypeError::Decoding(decoding_error) => {
                     matches!(
-                        decoding_error.cause,
+                        *decoding_error.cause,
                         crate::commom::errors::DecodingErrors::FirstByteError(_, _, _)
                     )
                 }
@@ -1,13 +1,19 @@
+pub mod big_num;
 pub mod bigint;
 pub mod boolean;
+pub mod bytes;
 pub mod double;
+pub mod huge_int;
 pub mod integer;
 pub mod null;
 pub mod string;
 pub mod supported_types;
+pub use big_num::{BigNum, BigNumError};
 pub use bigint::BigInt;
 pub use boolean::Boolean;
+pub use bytes::Bytes;
 pub use double::Double;
+pub use huge_int::HugeInt;
 pub use integer::Integer;
 pub use null::Null;
 pub use string::String;
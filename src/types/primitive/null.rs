@@ -1,45 +1,22 @@
+use std::io::Write;
+
+use crate::commom::decode_limits::check_limit;
 use crate::commom::{
-    delimiters::{BUFFER_END, END_RECORD, START_RECORD},
+    delimiters::{END_RECORD, START_RECORD},
     errors::{
         decoding_error, encoding_error, DecodingError, DecodingErrors, EncodingError, TypeResult,
     },
-    join_parts, slice_records, BuffPart, CoprotoType, Uint8Buff,
+    slice_records, take_first_byte, write_bytes, Codec, DecodeLimits,
 };
 
 #[derive(Debug)]
-pub struct Null {
-    pub first_byte: u8,
-    pub modifier_byte: Option<u8>,
-    pub modifier_char: Option<char>,
-    pub first_char: char,
-    pub value_of: TypeResult<Option<()>>,
-    pub buff: TypeResult<Uint8Buff>,
-}
+pub struct Null;
 
-impl CoprotoType<Option<()>> for Null {
+impl Codec for Null {
+    type Value = Option<()>;
     const FIRST_BYTE: u8 = b'-';
-    fn new(value: crate::commom::ValueOrBuffer<Option<()>>) -> Self {
-        match value {
-            crate::commom::ValueOrBuffer::Value(v) => Self {
-                first_byte: b'-',
-                modifier_byte: None,
-                modifier_char: None,
-                first_char: '-',
-                value_of: Ok(v),
-                buff: Self::encode(v),
-            },
-            crate::commom::ValueOrBuffer::Buffer(vec) => Self {
-                first_byte: b'-',
-                modifier_byte: None,
-                modifier_char: None,
-                first_char: '-',
-                value_of: Self::decode(vec.clone()),
-                buff: Ok(vec),
-            },
-        }
-    }
 
-    fn encode(value: Option<()>) -> TypeResult<Uint8Buff> {
+    fn write_value<W: Write>(value: &Option<()>, writer: &mut W) -> TypeResult<()> {
         if value.is_some() {
             return Err(encoding_error(EncodingError::new(
                 "Some(())",
@@ -50,51 +27,68 @@ impl CoprotoType<Option<()>> for Null {
             )));
         }
 
-        let parts: Vec<BuffPart> = vec![
-            BuffPart::Val(b'-'),
-            BuffPart::Val(START_RECORD),
-            BuffPart::Val(END_RECORD),
-            BuffPart::Val(BUFFER_END),
-        ];
-
-        Ok(join_parts(parts))
+        write_bytes(writer, &[b'-', START_RECORD, END_RECORD])
     }
 
-    fn decode(value: Uint8Buff) -> TypeResult<Option<()>> {
-        let mut m_value = value.clone();
+    fn decode_with(value: &[u8], limits: &DecodeLimits) -> TypeResult<Option<()>> {
+        check_limit(
+            value,
+            "Null",
+            "Message size",
+            limits.max_message_size,
+            value.len(),
+        )
+        .map_err(decoding_error)?;
 
-        let first_byte = m_value.remove(0);
+        let mut m_value = value.to_vec();
+
+        let first_byte = take_first_byte(&mut m_value, "Null").map_err(decoding_error)?;
 
         if first_byte != b'-' {
-            return Err(decoding_error(DecodingError {
-                from: value,
-                to: "Boolean".to_string(),
-                cause: DecodingErrors::FirstByteError("Boolean".to_string(), b'-', first_byte),
-            }));
+            return Err(decoding_error(
+                DecodingError::new(
+                    value.to_vec(),
+                    "Boolean",
+                    DecodingErrors::FirstByteError("Boolean".to_string(), b'-', first_byte),
+                )
+                .at(0),
+            ));
         };
 
-        let records = slice_records(m_value.clone());
+        let prefix_len = value.len() - m_value.len();
+
+        let records = slice_records(&m_value)
+            .map_err(|e| decoding_error(e.inside(prefix_len).within(value)))?;
 
         if records.len() > 1 {
             return Err(decoding_error(DecodingError::new(
-                value,
+                value.to_vec(),
                 "Double",
                 DecodingErrors::TooMuch(
                     "Records".to_string(),
                     1,
-                    records.len().try_into().unwrap(),
+                    records.len().try_into().unwrap_or(u32::MAX),
                 ),
             )));
         };
 
-        match records.first() {
+        let record_start = prefix_len + records.first().map_or(0, |range| range.start);
+
+        match records.first().map(|range| &m_value[range.clone()]) {
             Some(v) => match v.is_empty() {
                 true => Ok(None),
-                false => Err(decoding_error(DecodingError::new(
-                    value.clone(),
-                    "Null",
-                    DecodingErrors::TooMuch("Bytes".to_string(), 0, v.len().try_into().unwrap()),
-                ))),
+                false => Err(decoding_error(
+                    DecodingError::new(
+                        value.to_vec(),
+                        "Null",
+                        DecodingErrors::TooMuch(
+                            "Bytes".to_string(),
+                            0,
+                            v.len().try_into().unwrap_or(u32::MAX),
+                        ),
+                    )
+                    .at(record_start),
+                )),
             },
             None => Ok(None),
         }
@@ -106,20 +100,20 @@ mod tests {
     use crate::{
         commom::{
             delimiters::{BUFFER_END, END_RECORD, START_RECORD},
-            join_parts, BuffPart, CoprotoType, ValueOrBuffer,
+            join_parts, BuffPart, Lazy, ValueOrBuffer,
         },
         types::Null,
     };
 
     #[test]
     fn test_encoding_decoding() {
-        let null_encoding = Null::new(ValueOrBuffer::Value(None));
-        let null_buff = null_encoding.buff.unwrap();
-        let null_decoding = Null::new(ValueOrBuffer::Buffer(null_buff));
+        let null_encoding = Lazy::<Null>::new(ValueOrBuffer::Value(None));
+        let null_buff = null_encoding.buff().unwrap().to_vec();
+        let null_decoding = Lazy::<Null>::new(ValueOrBuffer::Buffer(null_buff));
 
         assert_eq!(
-            null_decoding.value_of.unwrap(),
-            null_encoding.value_of.unwrap()
+            *null_decoding.value().unwrap(),
+            *null_encoding.value().unwrap()
         );
     }
 
@@ -133,15 +127,15 @@ mod tests {
             BuffPart::Val(BUFFER_END),
         ];
 
-        let invalid_byte = Null::new(ValueOrBuffer::Buffer(join_parts(parts)));
+        let invalid_byte = Lazy::<Null>::new(ValueOrBuffer::Buffer(join_parts(parts)));
 
-        match invalid_byte.value_of {
+        match invalid_byte.value() {
             Ok(_) => false,
             Err(e) => match e {
                 crate::commom::errors::TypeError::Encoding(_) => false,
                 crate::commom::errors::TypeError::Decoding(decoding_error) => {
                     matches!(
-                        decoding_error.cause,
+                        *decoding_error.cause,
                         crate::commom::errors::DecodingErrors::TooMuch(_, _, _)
                     )
                 }
@@ -153,15 +147,15 @@ mod tests {
     fn wrong_buffer() {
         let buff = vec![b'?', START_RECORD, 0, END_RECORD, BUFFER_END];
 
-        let wrong = Null::new(ValueOrBuffer::Buffer(buff));
+        let wrong = Lazy::<Null>::new(ValueOrBuffer::Buffer(buff));
 
-        match wrong.value_of {
+        match wrong.value() {
             Ok(_) => false,
             Err(e) => match e {
                 crate::commom::errors::TypeError::Encoding(_) => false,
                 crate::commom::errors::TypeError::Decoding(decoding_error) => {
                     matches!(
-                        decoding_error.cause,
+                        *decoding_error.cause,
                         crate::commom::errors::DecodingErrors::FirstByteError(_, _, _)
                     )
                 }
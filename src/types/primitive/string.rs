@@ -1,107 +1,41 @@
+use std::io::Write;
+
 use crate::commom::{
-    delimiters::{BUFFER_END, END_RECORD, START_RECORD},
-    errors::{decoding_error, DecodingError, DecodingErrors, TypeResult},
-    join_parts, slice_records, BuffPart, CoprotoType, Uint8Buff,
+    delimiters::{END_RECORD, START_RECORD},
+    errors::TypeResult,
+    write_bytes, write_escaped, Codec, DecodeLimits,
 };
+use crate::types::borrowed::string_ref;
 
 #[derive(Debug)]
-pub struct String {
-    pub first_byte: u8,
-    pub modifier_byte: Option<u8>,
-    pub modifier_char: Option<char>,
-    pub first_char: char,
-    pub value_of: TypeResult<std::string::String>,
-    pub buff: TypeResult<Uint8Buff>,
-}
+pub struct String;
 
-impl CoprotoType<std::string::String> for String {
+impl Codec for String {
+    type Value = std::string::String;
     const FIRST_BYTE: u8 = b'+';
-    fn new(value: crate::commom::ValueOrBuffer<std::string::String>) -> Self {
-        match value {
-            crate::commom::ValueOrBuffer::Value(v) => Self {
-                first_byte: b'+',
-                modifier_byte: None,
-                modifier_char: None,
-                first_char: '+',
-                value_of: Ok(v.clone()),
-                buff: Self::encode(v),
-            },
-            crate::commom::ValueOrBuffer::Buffer(vec) => Self {
-                first_byte: b'+',
-                modifier_byte: None,
-                modifier_char: None,
-                first_char: '+',
-                value_of: Self::decode(vec.clone()),
-                buff: Ok(vec),
-            },
-        }
-    }
-
-    fn encode(value: std::string::String) -> TypeResult<Uint8Buff> {
-        let value_buff = value.into_bytes();
-
-        let parts: Vec<BuffPart> = vec![
-            BuffPart::Val(b'+'),
-            BuffPart::Val(START_RECORD),
-            BuffPart::Arr(value_buff),
-            BuffPart::Val(END_RECORD),
-            BuffPart::Val(BUFFER_END),
-        ];
 
-        Ok(join_parts(parts))
+    fn write_value<W: Write>(value: &std::string::String, writer: &mut W) -> TypeResult<()> {
+        write_str(value, writer)
     }
 
-    fn decode(value: Uint8Buff) -> TypeResult<std::string::String> {
-        let mut m_value = value.clone();
-
-        let first_byte = m_value.remove(0);
-
-        if first_byte != b'+' {
-            return Err(decoding_error(DecodingError {
-                from: value,
-                to: "String".to_string(),
-                cause: DecodingErrors::FirstByteError("String".to_string(), b'+', first_byte),
-            }));
-        };
-
-        let records = slice_records(m_value.clone());
-
-        if records.len() > 1 {
-            return Err(decoding_error(DecodingError::new(
-                value,
-                "Double",
-                DecodingErrors::TooMuch(
-                    "Records".to_string(),
-                    1,
-                    records.len().try_into().unwrap(),
-                ),
-            )));
-        };
-
-        match records.first() {
-            Some(buff) => match std::string::String::from_utf8(buff.to_vec()) {
-                Ok(string) => Ok(string),
-                Err(e) => Err(decoding_error(DecodingError::new(
-                    value,
-                    "String",
-                    DecodingErrors::InternalError(Box::new(e)),
-                ))),
-            },
-            None => Err(decoding_error(DecodingError::new(
-                value,
-                "String",
-                DecodingErrors::NotEnough("Records".to_string(), 1, 0),
-            ))),
-        }
+    fn decode_with(value: &[u8], limits: &DecodeLimits) -> TypeResult<std::string::String> {
+        Ok(string_ref(value, limits)?.into_owned())
     }
 }
 
+pub(crate) fn write_str<W: Write>(value: &str, writer: &mut W) -> TypeResult<()> {
+    write_bytes(writer, &[b'+', START_RECORD])?;
+    write_escaped(writer, value.as_bytes())?;
+    write_bytes(writer, &[END_RECORD])
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
         commom::{
-            delimiters::{BUFFER_END, END_RECORD, START_RECORD},
-            join_parts, BuffPart, CoprotoType, ValueOrBuffer,
+            delimiters::{BUFFER_END, END_RECORD, ESCAPE, START_RECORD},
+            errors::{DecodingErrors, TypeError},
+            Codec, DecodeLimits, Lazy, ValueOrBuffer,
         },
         types::String,
     };
@@ -109,33 +43,103 @@ mod tests {
     #[test]
     fn test_encoding_decoding() {
         let string_encoding =
-            String::new(ValueOrBuffer::Value("Hello, fellow rustacean!".to_string()));
-        let string_buff = string_encoding.buff.unwrap();
-        let string_decoding = String::new(ValueOrBuffer::Buffer(string_buff));
+            Lazy::<String>::new(ValueOrBuffer::Value("Hello, fellow rustacean!".to_string()));
+        let string_buff = string_encoding.buff().unwrap().to_vec();
+        let string_decoding = Lazy::<String>::new(ValueOrBuffer::Buffer(string_buff));
+
+        assert_eq!(
+            *string_decoding.value().unwrap(),
+            *string_encoding.value().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_every_byte_value() {
+        for byte in 0..=u8::MAX {
+            let value: std::string::String =
+                [char::from(byte), 'x', char::from(byte)].iter().collect();
+
+            let encoded = String::encode(&value).unwrap();
+
+            assert_eq!(
+                encoded.iter().filter(|b| **b == START_RECORD).count(),
+                1,
+                "byte {} leaked a delimiter",
+                byte
+            );
+
+            assert_eq!(String::decode(&encoded).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_delimiters_in_string() {
+        let value = "\u{1b}\u{1c}\u{1d}\u{1e}\u{1f}".to_string();
+
+        let encoded = String::encode(&value).unwrap();
 
         assert_eq!(
-            string_decoding.value_of.unwrap(),
-            string_encoding.value_of.unwrap()
+            encoded,
+            vec![
+                b'+',
+                START_RECORD,
+                ESCAPE,
+                0x3b,
+                ESCAPE,
+                0x3c,
+                ESCAPE,
+                0x3d,
+                ESCAPE,
+                0x3e,
+                ESCAPE,
+                0x3f,
+                END_RECORD,
+                BUFFER_END
+            ]
         );
+
+        assert_eq!(String::decode(&encoded).unwrap(), value);
     }
 
     #[test]
     fn wrong_buffer() {
         let buff = vec![b'?', START_RECORD, 0, END_RECORD, BUFFER_END];
 
-        let wrong = String::new(ValueOrBuffer::Buffer(buff));
+        let wrong = Lazy::<String>::new(ValueOrBuffer::Buffer(buff));
 
-        match wrong.value_of {
+        match wrong.value() {
             Ok(_) => false,
             Err(e) => match e {
                 crate::commom::errors::TypeError::Encoding(_) => false,
                 crate::commom::errors::TypeError::Decoding(decoding_error) => {
                     matches!(
-                        decoding_error.cause,
+                        *decoding_error.cause,
                         crate::commom::errors::DecodingErrors::FirstByteError(_, _, _)
                     )
                 }
             },
         };
     }
+
+    #[test]
+    fn length_limit_counts_unescaped_bytes() {
+        let buff = String::encode(&"\u{1c}\u{1c}\u{1c}".to_string()).unwrap();
+
+        let mut limits = DecodeLimits::new();
+        limits.max_string_length = 3;
+        assert!(String::decode_with(&buff, &limits).is_ok());
+
+        limits.max_string_length = 2;
+        match String::decode_with(&buff, &limits) {
+            Err(TypeError::Decoding(e)) => assert!(matches!(
+                *e.cause,
+                DecodingErrors::LimitExceeded {
+                    limit: 2,
+                    found: 3,
+                    ..
+                }
+            )),
+            other => panic!("Unexpected {:?}", other),
+        }
+    }
 }
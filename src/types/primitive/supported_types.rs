@@ -1,5 +1,9 @@
 use std::fmt::{Debug, Display};
 
+use super::BigNum;
+use crate::types::convert::exact_f64;
+use crate::types::notation::write_notation;
+
 #[derive(Clone, PartialEq)]
 pub enum SupportedTypes {
     BigInt(i64),
@@ -8,6 +12,15 @@ pub enum SupportedTypes {
     Integer(i32),
     Null(Option<()>),
     String(std::string::String),
+    HugeInt(BigNum),
+    Bytes(Vec<u8>),
+    Array(Vec<SupportedTypes>),
+    NamedValue(std::string::String, Box<SupportedTypes>),
+    Table(Vec<std::string::String>, Vec<Vec<SupportedTypes>>),
+    Command(std::string::String, Vec<std::string::String>),
+    /// A value decoded by an extension type, under the first byte it was
+    /// read with so it is written back the same way.
+    Extension(u8, Box<SupportedTypes>),
 }
 
 impl SupportedTypes {
@@ -19,32 +32,176 @@ impl SupportedTypes {
             SupportedTypes::Integer(_) => "Integer",
             SupportedTypes::Null(_) => "Null",
             SupportedTypes::String(_) => "String",
+            SupportedTypes::HugeInt(_) => "HugeInt",
+            SupportedTypes::Bytes(_) => "Bytes",
+            SupportedTypes::Array(_) => "Array",
+            SupportedTypes::NamedValue(_, _) => "NamedValue",
+            SupportedTypes::Table(_, _) => "Table",
+            SupportedTypes::Command(_, _) => "Command",
+            SupportedTypes::Extension(_, _) => "Extension",
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, SupportedTypes::Null(_))
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            SupportedTypes::Boolean(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    // The integer accessors read every integer type as long as the value
+    // fits, like `FromValue` does.
+
+    pub fn as_i32(&self) -> Option<i32> {
+        self.as_i128().and_then(|v| i32::try_from(v).ok())
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_i128().and_then(|v| i64::try_from(v).ok())
+    }
+
+    pub fn as_i128(&self) -> Option<i128> {
+        match self {
+            SupportedTypes::Integer(v) => Some((*v).into()),
+            SupportedTypes::BigInt(v) => Some((*v).into()),
+            SupportedTypes::HugeInt(v) => i128::try_from(v).ok(),
+            _ => None,
+        }
+    }
+
+    /// Only reads HugeInt, since other integers have no `BigNum` to borrow.
+    /// `BigNum::from_value` reads them all.
+    pub fn as_big_num(&self) -> Option<&BigNum> {
+        match self {
+            SupportedTypes::HugeInt(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Also reads Integer and BigInt, like `f64::from_value`, as long as the
+    /// f64 holds them exactly.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            SupportedTypes::Double(v) => Some(*v),
+            SupportedTypes::Integer(v) => Some((*v).into()),
+            SupportedTypes::BigInt(v) => exact_f64(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            SupportedTypes::String(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            SupportedTypes::Bytes(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[SupportedTypes]> {
+        match self {
+            SupportedTypes::Array(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_named_value(&self) -> Option<(&str, &SupportedTypes)> {
+        match self {
+            SupportedTypes::NamedValue(name, value) => Some((name, value)),
+            _ => None,
+        }
+    }
+
+    pub fn as_table(&self) -> Option<(&[std::string::String], &[Vec<SupportedTypes>])> {
+        match self {
+            SupportedTypes::Table(headers, rows) => Some((headers, rows)),
+            _ => None,
+        }
+    }
+
+    pub fn as_command(&self) -> Option<(&str, &[std::string::String])> {
+        match self {
+            SupportedTypes::Command(name, args) => Some((name, args)),
+            _ => None,
+        }
+    }
+
+    /// The value of the first NamedValue called `name`, whether this is that
+    /// NamedValue or an Array holding it.
+    pub fn get(&self, name: &str) -> Option<&SupportedTypes> {
+        match self {
+            SupportedTypes::NamedValue(n, value) if n == name => Some(value),
+            SupportedTypes::Array(values) => values.iter().find_map(|v| match v {
+                SupportedTypes::NamedValue(n, value) if n == name => Some(&**value),
+                _ => None,
+            }),
+            _ => None,
         }
     }
 }
 
+// Both print the text notation described in `types::notation`.
 impl Debug for SupportedTypes {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            SupportedTypes::BigInt(v) => write!(f, "{}_BigInt", v),
-            SupportedTypes::Boolean(v) => write!(f, "{}_Boolean", v),
-            SupportedTypes::Double(v) => write!(f, "{}_Double", v),
-            SupportedTypes::Integer(v) => write!(f, "{}_Integer", v),
-            SupportedTypes::Null(v) => write!(f, "{:?}_Null", v),
-            SupportedTypes::String(v) => write!(f, "\"{}\"_String", v),
-        }
+        write_notation(f, self)
     }
 }
 
 impl Display for SupportedTypes {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            SupportedTypes::BigInt(v) => write!(f, "{}_BigInt", v),
-            SupportedTypes::Boolean(v) => write!(f, "{}_Boolean", v),
-            SupportedTypes::Double(v) => write!(f, "{}_Double", v),
-            SupportedTypes::Integer(v) => write!(f, "{}_Integer", v),
-            SupportedTypes::Null(v) => write!(f, "{:?}_Null", v),
-            SupportedTypes::String(v) => write!(f, "{}_String", v),
-        }
+        write_notation(f, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{BigNum, SupportedTypes};
+
+    #[test]
+    fn accessors_widen_like_from_value() {
+        let value = SupportedTypes::Integer(7);
+        assert_eq!(value.as_i32(), Some(7));
+        assert_eq!(value.as_i64(), Some(7));
+        assert_eq!(value.as_i128(), Some(7));
+        assert_eq!(value.as_f64(), Some(7.0));
+        assert_eq!(value.as_str(), None);
+        assert!(!value.is_null());
+
+        let huge = SupportedTypes::HugeInt(BigNum::from(u128::MAX));
+        assert_eq!(huge.as_i64(), None);
+        assert_eq!(huge.as_i128(), None);
+        assert_eq!(huge.as_big_num(), Some(&BigNum::from(u128::MAX)));
+
+        let fits = SupportedTypes::HugeInt(BigNum::from(-5));
+        assert_eq!(fits.as_i32(), Some(-5));
+        assert_eq!(fits.as_i64(), Some(-5));
+        assert_eq!(SupportedTypes::BigInt(1 << 40).as_i32(), None);
+        assert_eq!(SupportedTypes::BigInt(i64::MAX).as_f64(), None);
+        assert_eq!(SupportedTypes::BigInt(1 << 40).as_i64(), Some(1 << 40));
+        assert_eq!(SupportedTypes::Double(1.0).as_i64(), None);
+
+        assert_eq!(SupportedTypes::String("a".to_string()).as_str(), Some("a"));
+        assert!(SupportedTypes::Null(None).is_null());
+    }
+
+    #[test]
+    fn get_finds_named_values() {
+        let value = SupportedTypes::Array(vec![
+            SupportedTypes::NamedValue("id".to_string(), Box::new(SupportedTypes::Integer(1))),
+            SupportedTypes::Integer(2),
+        ]);
+
+        assert_eq!(value.get("id").and_then(|v| v.as_i64()), Some(1));
+        assert_eq!(value.get("name"), None);
+        assert_eq!(value.as_array().map(|values| values.len()), Some(2));
     }
 }
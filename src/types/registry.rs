@@ -0,0 +1,486 @@
+//! Which decoder handles each first byte.
+//!
+//! [`infer_buffer`](super::infer_buffer) and everything built on it (nested
+//! values, [`Decoder`](super::Decoder), borrowed decoding) look first bytes
+//! up in the registry installed with [`with_registry`], or in the built-in
+//! one when none is. Applications add their own types under
+//! [`EXTENSION_FIRST_BYTES`]. Their encodings must keep the usual record
+//! framing, `FIRST_BYTE [modifier] START_RECORD ... END_RECORD`, so frames
+//! and composites can be split without knowing the type.
+//!
+//! Extension values decode to [`SupportedTypes::Extension`], which keeps the
+//! first byte so encoding can hand the value back to the same type.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::ops::RangeInclusive;
+use std::sync::OnceLock;
+use std::{cell::RefCell, rc::Rc};
+
+use crate::commom::diagnostics::{emit, Event};
+use crate::commom::errors::{
+    decoding_error, encoding_error, DecodingError, DecodingErrors, EncodingError, EncodingErrors,
+    RegistryError, TypeResult,
+};
+use crate::commom::{Codec, DecodeLimits};
+use crate::types::{FromValue, SupportedTypes};
+
+use super::{
+    Array, BigInt, Boolean, Bytes, Command, Double, HugeInt, Integer, NamedValue, Null, Table,
+};
+
+/// Decodes a whole value whose first byte picked it. Values nested in a
+/// composite come without the trailing `BUFFER_END`.
+pub type DecodeFn = fn(&[u8], &DecodeLimits) -> TypeResult<SupportedTypes>;
+
+/// Writes back a value its [`DecodeFn`] returned, nested: first byte
+/// included, trailing `BUFFER_END` left out.
+pub type EncodeFn = fn(&SupportedTypes, &mut dyn Write) -> TypeResult<()>;
+
+/// First bytes left free for extension types. Every built-in first byte and
+/// delimiter is ASCII.
+pub const EXTENSION_FIRST_BYTES: RangeInclusive<u8> = 0x80..=0xff;
+
+#[derive(Debug, Clone)]
+struct Entry {
+    name: String,
+    decode: DecodeFn,
+    // Built-in types are encoded by their own variants.
+    encode: Option<EncodeFn>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TypeRegistry {
+    entries: BTreeMap<u8, Entry>,
+}
+
+impl Default for TypeRegistry {
+    fn default() -> Self {
+        let built_in: [(u8, &str, DecodeFn); 12] = [
+            (BigInt::FIRST_BYTE, "BigInt", |buff, limits| {
+                BigInt::decode_with(buff, limits).map(SupportedTypes::BigInt)
+            }),
+            (Boolean::FIRST_BYTE, "Boolean", |buff, limits| {
+                Boolean::decode_with(buff, limits).map(SupportedTypes::Boolean)
+            }),
+            (Double::FIRST_BYTE, "Double", |buff, limits| {
+                Double::decode_with(buff, limits).map(SupportedTypes::Double)
+            }),
+            (Integer::FIRST_BYTE, "Integer", |buff, limits| {
+                Integer::decode_with(buff, limits).map(SupportedTypes::Integer)
+            }),
+            (Null::FIRST_BYTE, "Null", |buff, limits| {
+                Null::decode_with(buff, limits).map(SupportedTypes::Null)
+            }),
+            (super::String::FIRST_BYTE, "String", |buff, limits| {
+                super::String::decode_with(buff, limits).map(SupportedTypes::String)
+            }),
+            (HugeInt::FIRST_BYTE, "HugeInt", |buff, limits| {
+                HugeInt::decode_with(buff, limits).map(SupportedTypes::HugeInt)
+            }),
+            (Bytes::FIRST_BYTE, "Bytes", |buff, limits| {
+                Bytes::decode_with(buff, limits).map(SupportedTypes::Bytes)
+            }),
+            (Array::FIRST_BYTE, "Array", |buff, limits| {
+                Array::decode_with(buff, limits).map(SupportedTypes::Array)
+            }),
+            (NamedValue::FIRST_BYTE, "NamedValue", |buff, limits| {
+                let (name, value) = NamedValue::decode_with(buff, limits)?;
+                Ok(SupportedTypes::NamedValue(name, Box::new(value)))
+            }),
+            (Table::FIRST_BYTE, "Table", |buff, limits| {
+                let (headers, rows) = Table::decode_with(buff, limits)?;
+                Ok(SupportedTypes::Table(headers, rows))
+            }),
+            (Command::FIRST_BYTE, "Command", |buff, limits| {
+                let (name, args) = Command::decode_with(buff, limits)?;
+                Ok(SupportedTypes::Command(name, args))
+            }),
+        ];
+
+        let entries = built_in
+            .into_iter()
+            .map(|(first_byte, name, decode)| {
+                let entry = Entry {
+                    name: name.to_string(),
+                    decode,
+                    encode: None,
+                };
+
+                (first_byte, entry)
+            })
+            .collect();
+
+        Self { entries }
+    }
+}
+
+fn decode_as<C: Codec>(buff: &[u8], limits: &DecodeLimits) -> TypeResult<SupportedTypes>
+where
+    C::Value: Into<SupportedTypes>,
+{
+    C::decode_with(buff, limits).map(Into::into)
+}
+
+fn encode_as<C: Codec>(value: &SupportedTypes, mut writer: &mut dyn Write) -> TypeResult<()>
+where
+    C::Value: FromValue,
+{
+    C::write_value(&C::Value::from_value(value.clone())?, &mut writer)
+}
+
+impl TypeRegistry {
+    /// A registry holding the built-in types.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decodes values starting with `first_byte` with `decode`, reporting
+    /// them as `name`, and writes them back with `encode`.
+    pub fn register(
+        &mut self,
+        first_byte: u8,
+        name: &str,
+        decode: DecodeFn,
+        encode: EncodeFn,
+    ) -> Result<(), RegistryError> {
+        if let Some(entry) = self.entries.get(&first_byte) {
+            return Err(RegistryError::Conflict {
+                first_byte,
+                existing: entry.name.clone(),
+            });
+        }
+
+        if !EXTENSION_FIRST_BYTES.contains(&first_byte) {
+            return Err(RegistryError::Reserved(first_byte));
+        }
+
+        let entry = Entry {
+            name: name.to_string(),
+            decode,
+            encode: Some(encode),
+        };
+
+        self.entries.insert(first_byte, entry);
+        Ok(())
+    }
+
+    /// Registers `C` under its `FIRST_BYTE`, converting what it decodes
+    /// with `Into<SupportedTypes>` and what it encodes with `FromValue`.
+    pub fn register_codec<C: Codec>(&mut self, name: &str) -> Result<(), RegistryError>
+    where
+        C::Value: Into<SupportedTypes> + FromValue,
+    {
+        self.register(C::FIRST_BYTE, name, decode_as::<C>, encode_as::<C>)
+    }
+
+    pub fn contains(&self, first_byte: u8) -> bool {
+        self.entries.contains_key(&first_byte)
+    }
+
+    pub fn name(&self, first_byte: u8) -> Option<&str> {
+        self.entries
+            .get(&first_byte)
+            .map(|entry| entry.name.as_str())
+    }
+
+    pub fn first_bytes(&self) -> Vec<u8> {
+        self.entries.keys().copied().collect()
+    }
+
+    /// Decodes `buff` with the decoder its first byte is registered to.
+    pub fn decode_with(&self, buff: &[u8], limits: &DecodeLimits) -> TypeResult<SupportedTypes> {
+        let first_byte = match buff.first() {
+            Some(fb) => *fb,
+            None => {
+                return Err(decoding_error(DecodingError::new(
+                    vec![],
+                    "Infer",
+                    DecodingErrors::CouldNotFind(0, "First byte".to_string()),
+                )))
+            }
+        };
+
+        match self.entries.get(&first_byte) {
+            Some(entry) => {
+                emit(Event::TypeInferred { first_byte });
+                let value = (entry.decode)(buff, limits)?;
+
+                match entry.encode {
+                    Some(_) => Ok(SupportedTypes::Extension(first_byte, Box::new(value))),
+                    None => Ok(value),
+                }
+            }
+            None => Err(decoding_error(
+                DecodingError::new(
+                    buff.to_vec(),
+                    "Infer",
+                    DecodingErrors::UnknownFirstByte(first_byte, self.first_bytes()),
+                )
+                .at(0),
+            )),
+        }
+    }
+
+    /// Writes the value of a [`SupportedTypes::Extension`] with the encoder
+    /// registered to `first_byte`.
+    pub fn write_extension(
+        &self,
+        first_byte: u8,
+        value: &SupportedTypes,
+        writer: &mut dyn Write,
+    ) -> TypeResult<()> {
+        match self.entries.get(&first_byte).and_then(|entry| entry.encode) {
+            Some(encode) => encode(value, writer),
+            None => Err(encoding_error(EncodingError::new(
+                &format!("{:#04x}", first_byte),
+                "Extension",
+                EncodingErrors::InvalidValue(format!(
+                    "no extension type is registered to {:#04x}",
+                    first_byte
+                )),
+            ))),
+        }
+    }
+}
+
+thread_local! {
+    static REGISTRY: RefCell<Option<Rc<TypeRegistry>>> = const { RefCell::new(None) };
+}
+
+// Puts the previous registry back even if `f` panics.
+struct Restore(Option<Rc<TypeRegistry>>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        REGISTRY.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+/// Runs `f` decoding with `registry` on this thread. Calls can be nested;
+/// the innermost registry wins until it returns.
+pub fn with_registry<R>(registry: Rc<TypeRegistry>, f: impl FnOnce() -> R) -> R {
+    let previous = REGISTRY.with(|current| current.borrow_mut().replace(registry));
+    let _restore = Restore(previous);
+
+    f()
+}
+
+/// Runs `f` with the registry in use on this thread: the innermost one
+/// installed with [`with_registry`], or the built-in one.
+pub fn current<R>(f: impl FnOnce(&TypeRegistry) -> R) -> R {
+    static BUILT_IN: OnceLock<TypeRegistry> = OnceLock::new();
+
+    // Cloned out so decoders can reach the registry again while `f` runs.
+    match REGISTRY.with(|current| current.borrow().clone()) {
+        Some(registry) => f(&registry),
+        None => f(BUILT_IN.get_or_init(TypeRegistry::new)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::rc::Rc;
+
+    use crate::{
+        commom::{
+            delimiters::{BUFFER_END, END_RECORD, START_RECORD, VALUE_DELIMITER},
+            errors::{decoding_error, DecodingError, DecodingErrors, RegistryError, TypeError},
+            is_known_first_byte, write_bytes, Codec, DecodeLimits,
+        },
+        decode, encode,
+        types::{convert::mismatch, infer_borrowed, Decoder, FromValue, SupportedTypes, ValueRef},
+        TypeResult,
+    };
+
+    use super::{with_registry, TypeRegistry};
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Point {
+        lat: u8,
+        lon: u8,
+    }
+
+    impl From<Point> for SupportedTypes {
+        fn from(point: Point) -> Self {
+            SupportedTypes::NamedValue(
+                "geo".to_string(),
+                Box::new(SupportedTypes::Array(vec![
+                    SupportedTypes::Integer(point.lat.into()),
+                    SupportedTypes::Integer(point.lon.into()),
+                ])),
+            )
+        }
+    }
+
+    impl FromValue for Point {
+        fn from_value(value: SupportedTypes) -> TypeResult<Self> {
+            match value.get("geo").and_then(SupportedTypes::as_array) {
+                Some([lat, lon]) => Ok(Point {
+                    lat: u8::from_value(lat.clone())?,
+                    lon: u8::from_value(lon.clone())?,
+                }),
+                _ => Err(mismatch::<Self>(&value, "a geo NamedValue")),
+            }
+        }
+    }
+
+    // Two raw bytes, which is enough for the test and keeps clear of the delimiters.
+    struct GeoPoint;
+
+    impl Codec for GeoPoint {
+        type Value = Point;
+        const FIRST_BYTE: u8 = 0x80;
+
+        fn write_value<W: Write>(value: &Point, writer: &mut W) -> TypeResult<()> {
+            write_bytes(
+                writer,
+                &[
+                    Self::FIRST_BYTE,
+                    START_RECORD,
+                    value.lat,
+                    value.lon,
+                    END_RECORD,
+                ],
+            )
+        }
+
+        fn decode_with(buff: &[u8], _limits: &DecodeLimits) -> TypeResult<Point> {
+            match buff {
+                [Self::FIRST_BYTE, START_RECORD, lat, lon, END_RECORD]
+                | [Self::FIRST_BYTE, START_RECORD, lat, lon, END_RECORD, BUFFER_END] => Ok(Point {
+                    lat: *lat,
+                    lon: *lon,
+                }),
+                _ => Err(decoding_error(DecodingError::new(
+                    buff.to_vec(),
+                    "GeoPoint",
+                    DecodingErrors::CantFitValues("expected two bytes".to_string()),
+                ))),
+            }
+        }
+    }
+
+    fn registry() -> Rc<TypeRegistry> {
+        let mut registry = TypeRegistry::new();
+        registry.register_codec::<GeoPoint>("GeoPoint").unwrap();
+        Rc::new(registry)
+    }
+
+    fn unknown(result: TypeResult<SupportedTypes>) -> Vec<u8> {
+        match result {
+            Err(TypeError::Decoding(e)) => match *e.cause {
+                DecodingErrors::UnknownFirstByte(_, known) => known,
+                other => panic!("Unexpected cause {:?}", other),
+            },
+            other => panic!("Unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn built_ins_are_registered() {
+        let registry = TypeRegistry::new();
+
+        assert_eq!(registry.first_bytes().len(), 12);
+        assert_eq!(registry.name(b';'), Some("Double"));
+        assert_eq!(registry.name(b'{'), Some("Table"));
+
+        // The error lists every type, composites included.
+        let known = unknown(decode(&[b'?', START_RECORD, END_RECORD, BUFFER_END]));
+        assert_eq!(known, registry.first_bytes());
+    }
+
+    #[test]
+    fn extensions_decode_where_installed() {
+        let point = Point { lat: 10, lon: 20 };
+        let value = GeoPoint::encode(&point).unwrap();
+
+        let mut array = vec![b'[', START_RECORD, START_RECORD];
+        array.extend(&value[..value.len() - 1]);
+        array.extend([END_RECORD, VALUE_DELIMITER, END_RECORD, BUFFER_END]);
+
+        assert_eq!(unknown(decode(&value)).len(), 12);
+        assert!(!is_known_first_byte(GeoPoint::FIRST_BYTE));
+
+        let extension = SupportedTypes::Extension(GeoPoint::FIRST_BYTE, Box::new(point.into()));
+
+        with_registry(registry(), || {
+            assert!(is_known_first_byte(GeoPoint::FIRST_BYTE));
+            assert_eq!(decode(&value).unwrap(), extension);
+            assert_eq!(
+                decode(&array).unwrap(),
+                SupportedTypes::Array(vec![extension.clone()])
+            );
+            assert_eq!(
+                infer_borrowed(&value).unwrap(),
+                ValueRef::from(extension.clone())
+            );
+
+            let mut decoder = Decoder::new();
+            decoder.feed(&value);
+            assert_eq!(decoder.next().unwrap().unwrap(), extension);
+        });
+    }
+
+    #[test]
+    fn extensions_keep_their_wire_type() {
+        let point = Point { lat: 10, lon: 20 };
+        let value = GeoPoint::encode(&point).unwrap();
+
+        let mut array = vec![b'[', START_RECORD, START_RECORD];
+        array.extend(&value[..value.len() - 1]);
+        array.extend([END_RECORD, VALUE_DELIMITER, END_RECORD, BUFFER_END]);
+
+        with_registry(registry(), || {
+            for buff in [&value, &array] {
+                let decoded = decode(buff).unwrap();
+                assert_eq!(&encode(&decoded).unwrap(), buff);
+
+                let text = decoded.to_string();
+                assert_eq!(&encode(&text.parse().unwrap()).unwrap(), buff, "{}", text);
+            }
+        });
+
+        // Without the registry there is nothing to write the value with.
+        let extension = SupportedTypes::Extension(GeoPoint::FIRST_BYTE, Box::new(point.into()));
+        assert!(encode(&extension).is_err());
+    }
+
+    #[test]
+    fn conflicting_registrations_are_rejected() {
+        let mut registry = TypeRegistry::new();
+
+        assert_eq!(
+            registry.register(
+                b':',
+                "Money",
+                |_, _| Ok(SupportedTypes::Null(None)),
+                |_, _| Ok(())
+            ),
+            Err(RegistryError::Conflict {
+                first_byte: b':',
+                existing: "Integer".to_string()
+            })
+        );
+        assert_eq!(
+            registry.register(
+                b'm',
+                "Money",
+                |_, _| Ok(SupportedTypes::Null(None)),
+                |_, _| Ok(())
+            ),
+            Err(RegistryError::Reserved(b'm'))
+        );
+
+        registry.register_codec::<GeoPoint>("GeoPoint").unwrap();
+        assert_eq!(
+            registry.register_codec::<GeoPoint>("Other"),
+            Err(RegistryError::Conflict {
+                first_byte: 0x80,
+                existing: "GeoPoint".to_string()
+            })
+        );
+    }
+}